@@ -0,0 +1,287 @@
+use crate::{
+    models::{Context, Error},
+    utils::collection_utils::merge_counts,
+    utils::http_errors::HttpErrorKind,
+    utils::logging::{build_log_filter, parse_log_directives},
+    utils::messages::{format_error, format_info, format_success},
+};
+#[cfg(feature = "birthday")]
+use crate::{
+    constants::SCHEDULES_PER_PAGE,
+    schedule::prepare_birthday_preview,
+    utils::message_formatter::format_birthday_mention,
+    utils::messages::truncate_message,
+    utils::validation::require_guild,
+};
+#[cfg(feature = "birthday")]
+use poise::serenity_prelude as serenity;
+
+/// Show bot statistics (owner only)
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn stats(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+
+    let error_lines = HttpErrorKind::ALL
+        .iter()
+        .map(|kind| format!("• {}: **{}**", kind.label(), data.http_errors.get(*kind)))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.say(format!(
+        "📊 **Bot Statistics**\n\n\
+        Lobby channels: **{}**\n\
+        Temporary channels: **{}**\n\n\
+        **Discord API errors since startup**\n{}",
+        data.lobby_channels.len(),
+        data.temp_channels.len(),
+        error_lines
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Enable or disable maintenance mode, rejecting commands from everyone but the owners (owner only)
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn maintenance(
+    ctx: Context<'_>,
+    #[description = "Reject commands from non-owners"] enabled: bool,
+) -> Result<(), Error> {
+    ctx.data().set_maintenance(enabled);
+
+    let message = if enabled {
+        "Maintenance mode enabled, only owners can use commands."
+    } else {
+        "Maintenance mode disabled, commands are available to everyone again."
+    };
+    ctx.say(format_success(message)).await?;
+
+    tracing::info!("Maintenance mode set to {} by {}", enabled, ctx.author().id);
+
+    Ok(())
+}
+
+/// Change the log level at runtime, e.g. `shiibot_rs::schedule=debug` (owner only)
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn set_log_level(
+    ctx: Context<'_>,
+    #[description = "Comma-separated log directives, or `reset` for the defaults"] directive: String,
+) -> Result<(), Error> {
+    let extra = if directive.trim().eq_ignore_ascii_case("reset") {
+        Vec::new()
+    } else {
+        match parse_log_directives(&directive) {
+            Ok(directives) => directives,
+            Err(e) => {
+                ctx.say(format_error(&e)).await?;
+                return Ok(());
+            }
+        }
+    };
+
+    let applied = extra.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(",");
+    if let Err(e) = ctx.data().log_reload.reload(build_log_filter(extra)) {
+        tracing::error!("Failed to reload log filter: {}", e);
+        ctx.say(format_error("Failed to change the log level!")).await?;
+        return Ok(());
+    }
+
+    let message = if applied.is_empty() {
+        "Log level reset to the defaults.".to_string()
+    } else {
+        format!("Log level updated with `{}`.", applied)
+    };
+    ctx.say(format_success(&message)).await?;
+
+    tracing::info!("Log filter changed to '{}' by {}", applied, ctx.author().id);
+
+    Ok(())
+}
+
+/// Show how many times each command was used (owner only)
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn command_usage(ctx: Context<'_>) -> Result<(), Error> {
+    let data = ctx.data();
+
+    let stored = match data.db.get_command_usage().await {
+        Ok(stored) => stored,
+        Err(e) => {
+            tracing::error!("Failed to load command usage: {}", e);
+            ctx.say(format_error("Failed to load command usage!"))
+                .await?;
+            return Ok(());
+        }
+    };
+
+    // Include the invocations not flushed yet
+    let usage = merge_counts(stored.into_iter().chain(data.pending_command_usage()));
+    if usage.is_empty() {
+        ctx.say(format_info("No command has been used yet."))
+            .await?;
+        return Ok(());
+    }
+
+    let lines = usage
+        .iter()
+        .map(|(command, count)| format!("• `/{}`: **{}**", command, count))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.say(format!("📈 **Command Usage**\n\n{}", lines)).await?;
+
+    Ok(())
+}
+
+/// Show the schedule the manager is waiting on, optionally forcing a reload (owner only)
+#[cfg(feature = "birthday")]
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn schedule_status(
+    ctx: Context<'_>,
+    #[description = "Reload schedules from the database and recompute the wait"] reload: Option<bool>,
+) -> Result<(), Error> {
+    let data = ctx.data();
+
+    if reload.unwrap_or(false) {
+        data.schedule_reload_tx.send_modify(|val| *val += 1);
+        // Give the manager a moment to pick its next schedule
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    let next = data.next_schedule_tx.borrow().clone();
+    let message = match next {
+        Some((schedule_type, fire_time)) => {
+            let minutes = (fire_time - chrono::Utc::now()).num_minutes().max(0);
+            format_info(&format!(
+                "Next **{:?}** schedule fires <t:{}:F> (in {} minutes).",
+                schedule_type,
+                fire_time.timestamp(),
+                minutes
+            ))
+        }
+        None => format_info("The schedule manager is not waiting on any schedule."),
+    };
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// List every schedule of the bot, across all guilds (owner only)
+#[cfg(feature = "birthday")]
+#[poise::command(slash_command, owners_only, ephemeral)]
+pub async fn list_all_schedules(ctx: Context<'_>) -> Result<(), Error> {
+    let schedules = match ctx.data().db.get_all_schedules().await {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            tracing::error!("Failed to load schedules: {}", e);
+            ctx.say(format_error("Failed to load schedules!")).await?;
+            return Ok(());
+        }
+    };
+
+    if schedules.is_empty() {
+        ctx.say(format_info("No schedules are configured.")).await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = schedules
+        .iter()
+        .map(|schedule| {
+            let guild = match schedule.guild_id {
+                Some(guild_id) => {
+                    let guild_id = serenity::GuildId::new(guild_id as u64);
+                    match ctx.cache().guild(guild_id).map(|g| g.name.clone()) {
+                        Some(name) => format!("{} (`{}`)", name, guild_id),
+                        // Not in the cache: the bot most likely left this guild
+                        None => format!("⚠️ unknown guild (`{}`)", guild_id),
+                    }
+                }
+                None => "All guilds".to_string(),
+            };
+            let next_run = match schedule.next_run() {
+                Some(time) if schedule.enabled => format!("<t:{}:R>", time.timestamp()),
+                Some(_) => "disabled".to_string(),
+                None => "invalid cron".to_string(),
+            };
+
+            format!(
+                "**#{}** {:?} `{}` — {}\n`{}` {} — next: {}",
+                schedule.id,
+                schedule.schedule_type,
+                schedule.label,
+                guild,
+                schedule.cron_expression,
+                if schedule.enabled { "✅" } else { "⏸️" },
+                next_run
+            )
+        })
+        .collect();
+
+    let pages: Vec<String> = lines
+        .chunks(SCHEDULES_PER_PAGE)
+        .map(|chunk| {
+            format!(
+                "🗓️ **Schedules** ({} total)\n\n{}",
+                schedules.len(),
+                chunk.join("\n\n")
+            )
+        })
+        .collect();
+    let pages: Vec<&str> = pages.iter().map(String::as_str).collect();
+
+    poise::builtins::paginate(ctx, &pages).await?;
+
+    Ok(())
+}
+
+/// Exercise the birthday pipeline end-to-end without announcing anything (owner only)
+#[cfg(feature = "birthday")]
+#[poise::command(slash_command, owners_only, guild_only, ephemeral)]
+pub async fn selftest(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let data = ctx.data();
+    let user_id = ctx.author().id;
+
+    ctx.defer_ephemeral().await?;
+
+    // The announcement is built for the caller as if it were their birthday today,
+    // without storing anything
+    let http = &ctx.serenity_context().http;
+    let outcome = match prepare_birthday_preview(http, data, guild_id, user_id, None).await {
+        Ok(Some(notification)) => {
+            // Guilds that don't ping birthday users name them instead
+            let reference = notification
+                .user_ids
+                .iter()
+                .position(|id| *id == user_id)
+                .map(|index| {
+                    format_birthday_mention(
+                        user_id.get(),
+                        &notification.user_names[index],
+                        notification.ping_users,
+                    )
+                });
+            if reference.is_some_and(|reference| {
+                notification.messages.iter().any(|m| m.contains(&reference))
+            }) {
+                Ok(format!(
+                    "Announcement for <#{}>:\n>>> {}",
+                    notification.channel_id,
+                    truncate_message(&notification.messages.join("\n"), 1500)
+                ))
+            } else {
+                Err("the announcement does not mention you".to_string())
+            }
+        }
+        Ok(None) => Err("no announcement was produced, is `/setup_birthday` configured here?".to_string()),
+        Err(e) => Err(format!("building the announcement failed: {}", e)),
+    };
+
+    let report = match outcome {
+        Ok(preview) => format!("{}\n{}", format_success("Self-test passed."), preview),
+        Err(reason) => format_error(&format!("Self-test failed: {}.", reason)),
+    };
+    ctx.say(report).await?;
+
+    Ok(())
+}
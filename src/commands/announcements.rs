@@ -0,0 +1,114 @@
+use poise::serenity_prelude::GuildChannel;
+use tracing::{error, info};
+
+use crate::{
+    constants::{MAX_ANNOUNCEMENT_LENGTH, MAX_SCHEDULE_LABEL_LENGTH},
+    models::{Context, Error},
+    schedule::{next_run_from_cron, validate_cron_expression, ScheduleType},
+    utils::messages::{build_delete_success, format_error, format_info, format_success},
+    utils::schedule_utils::is_valid_schedule_label,
+    utils::string_utils::{is_empty_or_whitespace, process_newlines},
+    utils::validation::require_guild,
+};
+
+/// Post a message on a recurring schedule, e.g. a weekly reminder or a monthly event
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn add_announcement(
+    ctx: Context<'_>,
+    #[description = "Name of the announcement (letters, digits, - and _)"] label: String,
+    #[description = "Channel to post the message in"]
+    #[channel_types("Text")]
+    channel: GuildChannel,
+    #[description = "When to post it, as a cron expression in UTC (e.g. 0 0 18 * * Fri)"] cron: String,
+    #[description = "Message to post (use \\n for new lines)"] message: String,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let label = label.trim();
+    if !is_valid_schedule_label(label, MAX_SCHEDULE_LABEL_LENGTH) {
+        ctx.say(format_error(&format!(
+            "The announcement name must be 1 to {} letters, digits, `-` or `_`.",
+            MAX_SCHEDULE_LABEL_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    let message = process_newlines(message.trim());
+    if is_empty_or_whitespace(&message) || message.chars().count() > MAX_ANNOUNCEMENT_LENGTH {
+        ctx.say(format_error(&format!(
+            "The message must be 1 to {} characters long!",
+            MAX_ANNOUNCEMENT_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    let cron = cron.trim();
+    if let Err(e) = validate_cron_expression(cron) {
+        ctx.say(format_error(&e)).await?;
+        return Ok(());
+    }
+
+    let data = ctx.data();
+    let saved = data
+        .db
+        .upsert_announcement(guild_id, label, cron.to_string(), channel.id, &message)
+        .await;
+    if let Err(e) = saved {
+        error!("Failed to save announcement '{}': {}", label, e);
+        ctx.say(format_error("Failed to save the announcement!")).await?;
+        return Ok(());
+    }
+
+    data.schedule_reload_tx.send_modify(|val| *val += 1);
+
+    let next_run = next_run_from_cron(cron)
+        .map(|time| format!("<t:{}:F>", time.timestamp()))
+        .unwrap_or_else(|| "never".to_string());
+    ctx.say(format_success(&format!(
+        "Announcement `{}` saved: posted in <#{}> on cron `{}` (UTC). Next post: {}.",
+        label, channel.id, cron, next_run
+    )))
+    .await?;
+
+    info!("Saved announcement '{}' for guild {}", label, guild_id);
+
+    Ok(())
+}
+
+/// Stop posting a recurring announcement of this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn remove_announcement(
+    ctx: Context<'_>,
+    #[description = "Name of the announcement (see /list_schedules)"] label: String,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let label = label.trim();
+
+    match ctx
+        .data()
+        .db
+        .delete_schedule(guild_id, ScheduleType::Announcement, label)
+        .await
+    {
+        Ok(true) => {
+            ctx.data().schedule_reload_tx.send_modify(|val| *val += 1);
+            ctx.say(build_delete_success(&format!("Announcement `{}`", label)))
+                .await?;
+        }
+        Ok(false) => {
+            ctx.say(format_info(&format!(
+                "No announcement named `{}` exists for this server.",
+                label
+            )))
+            .await?;
+        }
+        Err(e) => {
+            error!("Failed to delete announcement '{}': {}", label, e);
+            ctx.say(format_error("Failed to remove the announcement!")).await?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,15 +1,38 @@
 use poise::serenity_prelude::{
-    ChannelType, CreateActionRow, CreateButton, CreateMessage, GuildChannel,
+    Attachment, ChannelId, CreateAttachment, ChannelType, CreateActionRow, CreateButton, CreateMessage, GuildChannel, GuildId,
+    User, UserId,
 };
-use chrono::Timelike;
+use chrono::{Datelike, Timelike};
 use tracing::{error, info, warn};
 
 use crate::{
+    constants::{
+        BIRTHDAY_ROLE_UPDATE_CRON, BIRTHDAY_STATS_BAR_WIDTH, DEFAULT_AGE_TEMPLATE, DEFAULT_SCHEDULE_LABEL, DEFAULT_UPCOMING_BIRTHDAYS, MAX_BIRTHDAY_REMINDER_DAYS,
+        MAX_EXPORT_FILE_SIZE,
+        MAX_IMPORT_ERRORS_SHOWN, MAX_IMPORT_FILE_SIZE, MAX_SCHEDULE_LABEL_LENGTH, MAX_TEMPLATE_RENDERED_LENGTH, MAX_UPCOMING_BIRTHDAYS,
+        SCHEDULES_PER_PAGE,
+    },
+    database::BirthdaySetting,
     models::{Context, Error},
-    utils::timezone::{local_time_to_cron, parse_time_string},
-    utils::messages::{build_delete_success, format_error, format_info},
-    utils::channel_utils::format_birthday_setup_message,
-    utils::validation::require_guild,
+    schedule::{
+        build_birthday_embed, fetch_guild_members, next_run_from_cron, prepare_birthday_preview, run_schedule_task,
+        send_birthday_notification, validate_cron_expression, ScheduleType,
+    },
+    utils::birthday_export::{export_birthdays_csv, export_birthdays_json},
+    utils::birthday_import::{is_json_import, parse_birthday_csv, parse_birthday_json, summarize_import, ImportError},
+    utils::datetime::{
+        calculate_age_today, format_date_display, get_month_name, group_birthdays_by_date,
+        sort_upcoming_birthdays, validate_birthday, week_dates,
+    },
+    utils::timezone::{local_time_to_cron, parse_time_string, parse_timezone},
+    utils::messages::{build_delete_success, build_save_success, format_error, decorate_message, format_info, format_success, format_warning},
+    utils::message_formatter::{
+        apply_message_template, build_activation_message, build_month_chart, find_template_issues, format_age_info, process_custom_text,
+        split_template_variants, validate_age_template,
+    },
+    utils::channel_utils::{format_birthday_display, format_birthday_setup_message},
+    utils::schedule_utils::is_valid_schedule_label,
+    utils::validation::{guild_context, require_guild, GuildContext},
 };
 
 /// Setup birthday collection in a channel
@@ -22,9 +45,9 @@ pub async fn setup_birthday(
     time: Option<String>,
     #[description = "Role to assign to users on their birthday (optional)"]
     birthday_role: Option<poise::serenity_prelude::Role>,
-    #[description = "Custom message for users WITH age (use {user}, {date}, {mention}, {age})"]
+    #[description = "Message for users WITH age ({user}, {date}, {mention}, {age}), variants split by {or}"]
     custom_message: Option<String>,
-    #[description = "Custom message for users WITHOUT age (use {user}, {date}, {mention})"]
+    #[description = "Message for users WITHOUT age ({user}, {date}, {mention}), variants split by {or}"]
     custom_message_without_age: Option<String>,
     #[description = "Custom header message (shown once at the top)"]
     custom_header: Option<String>,
@@ -36,8 +59,19 @@ pub async fn setup_birthday(
     collection_description: Option<String>,
     #[description = "Label for the button to set birthday"]
     collection_button: Option<String>,
+    #[description = "Post a one-off activation message in the notification channel (default: false)"]
+    announce_activation: Option<bool>,
+    #[description = "Send announcements as embeds instead of plain text (default: keep the current setting)"]
+    use_embed: Option<bool>,
+    #[description = "Also post a heads-up this many days before each birthday (default: keep the current one)"]
+    #[min = 1]
+    #[max = 30]
+    reminder_days: Option<u32>,
 ) -> Result<(), Error> {
-    let guild_id = require_guild(ctx.guild_id())?;
+    let GuildContext {
+        guild_id,
+        timezone: tz_str,
+    } = guild_context(ctx).await?;
 
     // Verify it's a text channel
     if notification_channel.kind != ChannelType::Text {
@@ -56,14 +90,6 @@ pub async fn setup_birthday(
         }
     };
 
-    // Get the guild's timezone from database
-    let tz_str = ctx
-        .data()
-        .db
-        .get_guild_timezone(guild_id)
-        .await
-        .unwrap_or_else(|_| "UTC".to_string());
-
     // Convert local time to UTC cron expression
     let (cron_expr, utc_time) = match local_time_to_cron(&time_str, &tz_str) {
         Ok(result) => result,
@@ -72,6 +98,10 @@ pub async fn setup_birthday(
             return Ok(());
         }
     };
+    if let Err(e) = validate_cron_expression(&cron_expr) {
+        ctx.say(format_error(&e)).await?;
+        return Ok(());
+    }
 
     info!(
         "Timezone conversion: {} {} -> {} UTC (from timezone {})",
@@ -108,6 +138,33 @@ pub async fn setup_birthday(
         return Ok(());
     }
 
+    // Left out, the embed setting stays as it was (plain text for a new setup)
+    if let Some(use_embed) = use_embed
+        && let Err(e) = ctx.data().db.set_birthday_setting(guild_id, &BirthdaySetting::UseEmbed(use_embed)).await
+    {
+        error!("Failed to save birthday embed setting: {}", e);
+        ctx.say(format_error("Failed to save birthday channel configuration!"))
+            .await?;
+        return Ok(());
+    }
+
+    // Left out, the reminder stays as it was (turned off with /set_birthday_reminder)
+    if let Some(reminder_days) = reminder_days
+        && let Err(e) = ctx
+            .data()
+            .db
+            .set_birthday_setting(
+                guild_id,
+                &BirthdaySetting::ReminderDays(Some(reminder_days.clamp(1, MAX_BIRTHDAY_REMINDER_DAYS))),
+            )
+            .await
+    {
+        error!("Failed to save birthday reminder setting: {}", e);
+        ctx.say(format_error("Failed to save birthday channel configuration!"))
+            .await?;
+        return Ok(());
+    }
+
     // Create or update the birthday schedule
     if let Err(e) = ctx
         .data()
@@ -115,6 +172,7 @@ pub async fn setup_birthday(
         .upsert_schedule(
             Some(guild_id),
             crate::schedule::ScheduleType::Birthday,
+            DEFAULT_SCHEDULE_LABEL,
             cron_expr,
             true,
         )
@@ -127,34 +185,13 @@ pub async fn setup_birthday(
     }
 
     // If a birthday role is specified, create/update the birthday role schedule at midnight
-    if birthday_role_id.is_some() {
-        let midnight_cron = match local_time_to_cron("00:00", &tz_str) {
-            Ok((cron, _)) => cron,
-            Err(e) => {
-                warn!(
-                    "Failed to create midnight cron for guild {}: {}",
-                    guild_id, e
-                );
-                "0 0 0 * * *".to_string() // Fallback to UTC midnight
-            }
-        };
-
-        if let Err(e) = ctx
-            .data()
-            .db
-            .upsert_schedule(
-                Some(guild_id),
-                crate::schedule::ScheduleType::BirthdayRole,
-                midnight_cron,
-                true,
-            )
-            .await
-        {
-            error!("Failed to save birthday role schedule: {}", e);
-            ctx.say(format_error("Failed to save birthday role schedule!"))
-                .await?;
-            return Ok(());
-        }
+    if birthday_role_id.is_some()
+        && let Err(e) = upsert_birthday_role_schedule(ctx, guild_id).await
+    {
+        error!("Failed to save birthday role schedule: {}", e);
+        ctx.say(format_error("Failed to save birthday role schedule!"))
+            .await?;
+        return Ok(());
     }
 
     // Signal schedule manager to reload
@@ -188,8 +225,9 @@ pub async fn setup_birthday(
 
     let message_content = format!("{}\n\n{}\n", title, description);
 
+    let settings = ctx.data().guild_settings_or_default(guild_id).await;
     let message = CreateMessage::new()
-        .content(message_content)
+        .content(decorate_message(&settings, &message_content))
         .components(vec![action_row]);
 
     // Send the message in the current channel
@@ -256,8 +294,39 @@ pub async fn setup_birthday(
     } else {
         String::new()
     };
+    let reminder_info = match reminder_days {
+        Some(days) => format!("\n🔔 Reminder posted {} day(s) before each birthday", days),
+        None => String::new(),
+    };
+
+    // Optionally post an activation message to check the channel works end-to-end
+    let activation_info = if announce_activation.unwrap_or(false) {
+        let activation = CreateMessage::new().content(decorate_message(
+            &settings,
+            &build_activation_message(&custom_header, &custom_footer),
+        ));
+        match notification_channel.id.send_message(ctx.http(), activation).await {
+            Ok(_) => "\n📣 Activation message posted in the notification channel".to_string(),
+            Err(e) => {
+                ctx.data().record_http_error(&e);
+                warn!(
+                    "Failed to post activation message in channel {}: {}",
+                    notification_channel.id, e
+                );
+                format!(
+                    "\n\n{}",
+                    format_warning("Could not post in the notification channel, please check the bot's permissions there.")
+                )
+            }
+        }
+    } else {
+        String::new()
+    };
 
-    ctx.say(format!("{}{}{}", base_message, custom_msg_info, role_info))
+    ctx.say(format!(
+        "{}{}{}{}{}",
+        base_message, custom_msg_info, role_info, reminder_info, activation_info
+    ))
         .await?;
 
     info!(
@@ -347,3 +416,1296 @@ pub async fn disable_birthday(ctx: Context<'_>) -> Result<(), Error> {
 
     Ok(())
 }
+
+/// Create or update the birthday role schedule so it runs at the guild's local midnight
+async fn upsert_birthday_role_schedule(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+) -> Result<(), sqlx::Error> {
+    ctx.data()
+        .db
+        .upsert_schedule(
+            Some(guild_id),
+            crate::schedule::ScheduleType::BirthdayRole,
+            DEFAULT_SCHEDULE_LABEL,
+            BIRTHDAY_ROLE_UPDATE_CRON.to_string(),
+            true,
+        )
+        .await?;
+    Ok(())
+}
+
+/// Give a role to users on the birthday they reach a milestone age (omit the role to remove it)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_milestone_role(
+    ctx: Context<'_>,
+    #[description = "Age that triggers the role (e.g. 18, 21, 30)"]
+    #[min = 1]
+    #[max = 150]
+    age: i32,
+    #[description = "Role to give for the day (leave empty to remove this milestone)"]
+    role: Option<poise::serenity_prelude::Role>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let Some(role) = role else {
+        match ctx.data().db.remove_milestone_role(guild_id, age).await {
+            Ok(true) => {
+                ctx.say(build_delete_success(&format!("Milestone role for age {}", age)))
+                    .await?;
+            }
+            Ok(false) => {
+                ctx.say(format_info(&format!("No milestone role is configured for age {}.", age)))
+                    .await?;
+            }
+            Err(e) => {
+                error!("Failed to remove milestone role: {}", e);
+                ctx.say(format_error("Failed to remove the milestone role!"))
+                    .await?;
+            }
+        }
+        return Ok(());
+    };
+
+    if let Err(e) = ctx.data().db.set_milestone_role(guild_id, age, role.id).await {
+        error!("Failed to save milestone role: {}", e);
+        ctx.say(format_error("Failed to save the milestone role!"))
+            .await?;
+        return Ok(());
+    }
+
+    // Milestone roles are handled by the hourly birthday role update
+    if let Err(e) = upsert_birthday_role_schedule(ctx, guild_id).await {
+        error!("Failed to save birthday role schedule: {}", e);
+        ctx.say(format_error("Failed to save birthday role schedule!"))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.data().schedule_reload_tx.send_modify(|val| *val += 1);
+
+    ctx.say(format_success(&format!(
+        "Users turning **{}** will get <@&{}> on their birthday. Only users who shared their birth year are eligible.",
+        age, role.id
+    )))
+    .await?;
+
+    info!(
+        "Set milestone role {} for age {} in guild {}",
+        role.id, age, guild_id
+    );
+
+    Ok(())
+}
+
+/// Save one birthday setting of the guild and confirm it with `message`,
+/// or point to `/setup_birthday` when birthdays aren't configured
+async fn save_birthday_setting(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    setting: BirthdaySetting,
+    message: &str,
+) -> Result<(), Error> {
+    match ctx.data().db.set_birthday_setting(guild_id, &setting).await {
+        Ok(true) => {
+            ctx.say(format_success(message)).await?;
+            info!("Set birthday setting {:?} for guild {}", setting, guild_id);
+        }
+        Ok(false) => {
+            ctx.say(format_info(
+                "Birthday notifications are not configured for this server. Use `/setup_birthday` first.",
+            ))
+            .await?;
+        }
+        Err(e) => {
+            error!("Failed to save birthday setting {:?}: {}", setting, e);
+            ctx.say(format_error("Failed to save the setting!")).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Announce each birthday in its own message instead of one combined message
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_separate_messages(
+    ctx: Context<'_>,
+    #[description = "Send one message per birthday person"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "Each birthday will now be announced in its own message."
+    } else {
+        "Birthdays will now be announced together in a single message."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::SeparateMessages(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Show the birthday you have stored
+#[poise::command(slash_command, ephemeral)]
+pub async fn birthday_view(ctx: Context<'_>) -> Result<(), Error> {
+    let birthday = match ctx.data().db.get_birthday(ctx.author().id).await {
+        Ok(birthday) => birthday,
+        Err(e) => {
+            error!("Failed to get birthday of {}: {}", ctx.author().id, e);
+            ctx.say(format_error("Failed to load your birthday!")).await?;
+            return Ok(());
+        }
+    };
+
+    let Some((month, day, year)) = birthday else {
+        ctx.say(format_info(
+            "You haven't set a birthday yet. Use the birthday button in the server to add it.",
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    let mut message = format!(
+        "🎂 Your birthday: **{}**",
+        format_birthday_display(day, get_month_name(month), year)
+    );
+    if let Some(year) = year {
+        message.push_str(&format!("\nYou turn **{}** this year.", calculate_age_today(year)));
+    }
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Set or correct the birthday of a member
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn set_user_birthday(
+    ctx: Context<'_>,
+    #[description = "Member whose birthday to set"] user: User,
+    #[description = "Day of the birthday"]
+    #[min = 1]
+    #[max = 31]
+    day: i32,
+    #[description = "Month of the birthday"]
+    #[min = 1]
+    #[max = 12]
+    month: i32,
+    #[description = "Birth year (optional)"] year: Option<i32>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    // Birthdays are global, a server may only set the birthday of its own members
+    if guild_id.member(ctx, user.id).await.is_err() {
+        ctx.say(format_error("This user is not a member of this server!"))
+            .await?;
+        return Ok(());
+    }
+
+    if let Err(err_msg) = validate_birthday(month, day, year, chrono::Utc::now().year()) {
+        ctx.say(format_error(&err_msg)).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.upsert_birthday(user.id, month, day, year).await {
+        error!("Failed to save birthday of {}: {}", user.id, e);
+        ctx.say(format_error("Failed to save the birthday!")).await?;
+        return Ok(());
+    }
+
+    info!(
+        "{} set the birthday of {} to {}/{}/{}",
+        ctx.author().id,
+        user.id,
+        month,
+        day,
+        year.map_or("None".to_string(), |y| y.to_string())
+    );
+
+    ctx.say(format!(
+        "{}\n<@{}>'s birthday: {}",
+        build_save_success("Birthday"),
+        user.id,
+        format_birthday_display(day, get_month_name(month), year)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Set your birthday, shared by every server the bot is in
+#[poise::command(slash_command, ephemeral)]
+pub async fn set_birthday(
+    ctx: Context<'_>,
+    #[description = "Day of your birthday"]
+    #[min = 1]
+    #[max = 31]
+    day: i32,
+    #[description = "Month of your birthday"]
+    #[min = 1]
+    #[max = 12]
+    month: i32,
+    #[description = "Your birth year, to show your age (optional)"]
+    #[min = 1901]
+    year: Option<i32>,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id;
+
+    // Same checks as the collection modal
+    if let Err(err_msg) = validate_birthday(month, day, year, chrono::Utc::now().year()) {
+        ctx.say(format_error(&err_msg)).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.upsert_birthday(user_id, month, day, year).await {
+        error!("Failed to save birthday of {}: {}", user_id, e);
+        ctx.say(format_error("Failed to save your birthday. Please try again later."))
+            .await?;
+        return Ok(());
+    }
+
+    info!("User {} set their birthday with /set_birthday", user_id);
+
+    ctx.say(format!(
+        "{}\n\nYour birthday: {}\n\nThis will be used across all servers where this bot is present.",
+        build_save_success("Birthday"),
+        format_birthday_display(day, get_month_name(month), year)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Delete the birthday you have stored
+#[poise::command(slash_command, ephemeral)]
+pub async fn birthday_remove(ctx: Context<'_>) -> Result<(), Error> {
+    let user_id = ctx.author().id;
+
+    match ctx.data().db.remove_birthday(user_id).await {
+        Ok(true) => {}
+        Ok(false) => {
+            ctx.say(format_info("You don't have a birthday stored.")).await?;
+            return Ok(());
+        }
+        Err(e) => {
+            error!("Failed to remove birthday of {}: {}", user_id, e);
+            ctx.say(format_error("Failed to remove your birthday!")).await?;
+            return Ok(());
+        }
+    }
+
+    // Don't leave a stale birthday role behind
+    if let Some(guild_id) = ctx.guild_id()
+        && let Ok(Some(role_id)) = ctx.data().db.get_birthday_role(guild_id).await
+        && let Some(member) = ctx.author_member().await
+        && member.roles.contains(&role_id)
+        && let Err(e) = ctx
+            .http()
+            .remove_member_role(guild_id, user_id, role_id, Some("Birthday removed"))
+            .await
+    {
+        warn!("Failed to remove birthday role from {} in guild {}: {}", user_id, guild_id, e);
+    }
+
+    ctx.say(build_delete_success("Birthday")).await?;
+
+    Ok(())
+}
+
+/// List the members whose birthday falls in the current week, by day
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn birthday_this_week(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    // Fetching the member list of a large server may take a while
+    ctx.defer_ephemeral().await?;
+
+    // The week is the one of the guild's timezone
+    let settings = ctx.data().guild_settings_or_default(guild_id).await;
+    let now = chrono::Utc::now();
+    let today = parse_timezone(&settings.timezone)
+        .map(|tz| now.with_timezone(&tz).date_naive())
+        .unwrap_or_else(|_| now.date_naive());
+    let week = week_dates(today);
+
+    let dates: Vec<(i32, i32)> = week
+        .iter()
+        .map(|date| (date.month() as i32, date.day() as i32))
+        .collect();
+    let birthdays = match ctx.data().db.get_birthdays_on_dates(&dates).await {
+        Ok(birthdays) => birthdays,
+        Err(e) => {
+            error!("Failed to get birthdays of the week: {}", e);
+            ctx.say(format_error("Failed to load birthdays!")).await?;
+            return Ok(());
+        }
+    };
+
+    let serenity_ctx = ctx.serenity_context();
+    let members = match fetch_guild_members(&serenity_ctx.http, &serenity_ctx.cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the server members!")).await?;
+            return Ok(());
+        }
+    };
+
+    // Birthdays are global, only list members of this server (without their age)
+    let named_birthdays: Vec<(i32, i32, String)> = birthdays
+        .into_iter()
+        .filter_map(|(user_id, month, day)| {
+            let member = members.get(&user_id)?;
+            Some((month, day, member.display_name().to_string()))
+        })
+        .collect();
+
+    let grouped = group_birthdays_by_date(&week, &named_birthdays);
+    if grouped.is_empty() {
+        ctx.say(format_info("No birthdays this week.")).await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = grouped
+        .into_iter()
+        .map(|(date, mut names)| {
+            names.sort();
+            format!(
+                "**{} {}**: {}",
+                date.format("%A"),
+                format_date_display(date.month() as i32, date.day() as i32),
+                names.join(", ")
+            )
+        })
+        .collect();
+
+    ctx.say(format!("🎂 **Birthdays this week**\n{}", lines.join("\n")))
+        .await?;
+
+    Ok(())
+}
+
+/// List the next birthdays of the server members
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn upcoming_birthdays(
+    ctx: Context<'_>,
+    #[description = "Number of birthdays to list (default 10)"]
+    #[min = 1]
+    #[max = 25]
+    count: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let count = count
+        .unwrap_or(DEFAULT_UPCOMING_BIRTHDAYS)
+        .min(MAX_UPCOMING_BIRTHDAYS) as usize;
+
+    // Fetching the member list of a large server may take a while
+    ctx.defer_ephemeral().await?;
+
+    let settings = ctx.data().guild_settings_or_default(guild_id).await;
+    let now = chrono::Utc::now();
+    let today = parse_timezone(&settings.timezone)
+        .map(|tz| now.with_timezone(&tz).date_naive())
+        .unwrap_or_else(|_| now.date_naive());
+
+    let birthdays = match ctx.data().db.get_all_birthdays().await {
+        Ok(birthdays) => birthdays,
+        Err(e) => {
+            error!("Failed to get birthdays: {}", e);
+            ctx.say(format_error("Failed to load birthdays!")).await?;
+            return Ok(());
+        }
+    };
+
+    let serenity_ctx = ctx.serenity_context();
+    let members = match fetch_guild_members(&serenity_ctx.http, &serenity_ctx.cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the server members!")).await?;
+            return Ok(());
+        }
+    };
+
+    // Birthdays are global, only list the current members of this server
+    let birthdays: Vec<(i32, i32, UserId)> = birthdays
+        .into_iter()
+        .filter(|(user_id, _, _)| members.contains_key(user_id))
+        .map(|(user_id, month, day)| (month, day, user_id))
+        .collect();
+
+    let mut lines = Vec::new();
+    for (date, days_until, user_id) in sort_upcoming_birthdays(today, &birthdays).into_iter().take(count) {
+        let Some(member) = members.get(&user_id) else {
+            continue;
+        };
+        let when = match days_until {
+            0 => "today".to_string(),
+            1 => "tomorrow".to_string(),
+            days => format!("in {} days", days),
+        };
+        lines.push(format!(
+            "**{}** ({}): {}",
+            format_date_display(date.month() as i32, date.day() as i32),
+            when,
+            member.display_name()
+        ));
+    }
+
+    if lines.is_empty() {
+        ctx.say(format_info("No member of this server has set a birthday yet."))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.say(format!("🎂 **Upcoming birthdays**\n{}", lines.join("\n")))
+        .await?;
+
+    Ok(())
+}
+
+/// Show how the birthdays of the server members spread over the months
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn birthday_stats(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    // Fetching the member list of a large server may take a while
+    ctx.defer_ephemeral().await?;
+
+    let serenity_ctx = ctx.serenity_context();
+    let members = match fetch_guild_members(&serenity_ctx.http, &serenity_ctx.cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the server members!")).await?;
+            return Ok(());
+        }
+    };
+
+    // Birthdays are global, only count the current members of this server
+    let member_ids: Vec<UserId> = members.into_keys().collect();
+    let counts = match ctx.data().db.count_birthdays_by_month(&member_ids).await {
+        Ok(counts) => counts,
+        Err(e) => {
+            error!("Failed to count birthdays: {}", e);
+            ctx.say(format_error("Failed to load birthdays!")).await?;
+            return Ok(());
+        }
+    };
+
+    let total: u32 = counts.iter().sum();
+    if total == 0 {
+        ctx.say(format_info("No member of this server has set a birthday yet."))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.say(format!(
+        "🎂 **Birthdays per month** ({} member(s))\n```\n{}\n```",
+        total,
+        build_month_chart(&counts, BIRTHDAY_STATS_BAR_WIDTH)
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// List the schedules running for this server, with their next run
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn list_schedules(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    // Global schedules (e.g. the birthday role update) also run for this server
+    let schedules = match ctx.data().db.get_guild_schedules(guild_id).await {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            error!("Failed to load schedules: {}", e);
+            ctx.say(format_error("Failed to load schedules!")).await?;
+            return Ok(());
+        }
+    };
+
+    if schedules.is_empty() {
+        ctx.say(format_info(
+            "No schedule is configured for this server. Use `/setup_birthday` first.",
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let lines: Vec<String> = schedules
+        .iter()
+        .map(|schedule| {
+            let next_run = match next_run_from_cron(&schedule.cron_expression) {
+                Some(time) if schedule.enabled => {
+                    format!("<t:{}:F> (<t:{}:R>)", time.timestamp(), time.timestamp())
+                }
+                Some(_) => "disabled".to_string(),
+                None => "invalid cron".to_string(),
+            };
+
+            format!(
+                "**{:?}** `{}`{} — `{}` {}\nNext run: {}",
+                schedule.schedule_type,
+                schedule.label,
+                if schedule.guild_id.is_none() { " (all servers)" } else { "" },
+                schedule.cron_expression,
+                if schedule.enabled { "✅" } else { "⏸️" },
+                next_run
+            )
+        })
+        .collect();
+
+    let pages: Vec<String> = lines
+        .chunks(SCHEDULES_PER_PAGE)
+        .map(|chunk| format!("🗓️ **Schedules** ({} total)\n\n{}", schedules.len(), chunk.join("\n\n")))
+        .collect();
+    let pages: Vec<&str> = pages.iter().map(String::as_str).collect();
+
+    poise::builtins::paginate(ctx, &pages).await?;
+
+    Ok(())
+}
+
+/// Add a named schedule, e.g. a second daily birthday announcement time
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn add_schedule(
+    ctx: Context<'_>,
+    #[description = "Task to schedule"] schedule_type: ScheduleType,
+    #[description = "Name of the schedule (letters, digits, - and _)"] label: String,
+    #[description = "Time to run it (HH:MM, 24-hour format, server timezone)"] time: String,
+) -> Result<(), Error> {
+    let GuildContext {
+        guild_id,
+        timezone: tz_str,
+    } = guild_context(ctx).await?;
+
+    if schedule_type == ScheduleType::Announcement {
+        ctx.say(format_info("Announcements carry a message and channel, use `/add_announcement` instead."))
+            .await?;
+        return Ok(());
+    }
+
+    let label = label.trim();
+    if !is_valid_schedule_label(label, MAX_SCHEDULE_LABEL_LENGTH) {
+        ctx.say(format_error(&format!(
+            "The schedule name must be 1 to {} letters, digits, `-` or `_`.",
+            MAX_SCHEDULE_LABEL_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    match ctx.data().db.get_birthday_config(guild_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            ctx.say(format_error(
+                "Birthday notifications are not configured for this server. Use `/setup_birthday` first.",
+            ))
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            error!("Failed to get birthday channel: {}", e);
+            ctx.say(format_error("Failed to save the schedule!")).await?;
+            return Ok(());
+        }
+    }
+
+    let (cron_expr, _) = match local_time_to_cron(&time, &tz_str) {
+        Ok(result) => result,
+        Err(e) => {
+            ctx.say(format_error(&e.to_string())).await?;
+            return Ok(());
+        }
+    };
+    if let Err(e) = validate_cron_expression(&cron_expr) {
+        ctx.say(format_error(&e)).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx
+        .data()
+        .db
+        .upsert_schedule(Some(guild_id), schedule_type.clone(), label, cron_expr.clone(), true)
+        .await
+    {
+        error!("Failed to save {:?} schedule '{}': {}", schedule_type, label, e);
+        ctx.say(format_error("Failed to save the schedule!")).await?;
+        return Ok(());
+    }
+
+    ctx.data().schedule_reload_tx.send_modify(|val| *val += 1);
+
+    ctx.say(format_success(&format!(
+        "Schedule `{}` ({:?}) saved: runs daily at {} ({}), cron `{}`.",
+        label, schedule_type, time, tz_str, cron_expr
+    )))
+    .await?;
+
+    Ok(())
+}
+
+/// Remove a named schedule of this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn remove_schedule(
+    ctx: Context<'_>,
+    #[description = "Task of the schedule"] schedule_type: ScheduleType,
+    #[description = "Name of the schedule (see /list_schedules)"] label: String,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let label = label.trim();
+
+    match ctx
+        .data()
+        .db
+        .delete_schedule(guild_id, schedule_type.clone(), label)
+        .await
+    {
+        Ok(true) => {
+            ctx.data().schedule_reload_tx.send_modify(|val| *val += 1);
+            ctx.say(build_delete_success(&format!("Schedule `{}`", label)))
+                .await?;
+        }
+        Ok(false) => {
+            ctx.say(format_info(&format!(
+                "No {:?} schedule named `{}` exists for this server.",
+                schedule_type, label
+            )))
+            .await?;
+        }
+        Err(e) => {
+            error!("Failed to delete {:?} schedule '{}': {}", schedule_type, label, e);
+            ctx.say(format_error("Failed to remove the schedule!")).await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Run a birthday schedule for this server right away, without waiting for its time
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn run_schedule_now(
+    ctx: Context<'_>,
+    #[description = "Task to run"] schedule_type: ScheduleType,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    if schedule_type == ScheduleType::Announcement {
+        ctx.say(format_info("Announcements can only be posted by their schedule."))
+            .await?;
+        return Ok(());
+    }
+
+    // Role updates fetch every member, which can take a while
+    ctx.defer_ephemeral().await?;
+
+    let data = ctx.data();
+    let result = run_schedule_task(
+        &ctx.serenity_context().http,
+        &ctx.serenity_context().cache,
+        data,
+        &schedule_type,
+        Some(guild_id.get() as i64),
+    )
+    .await;
+
+    let message = match (result, &schedule_type) {
+        (Ok(0), ScheduleType::Birthday) => {
+            format_info("No birthday was announced (none today, or birthdays aren't set up).")
+        }
+        (Ok(count), ScheduleType::Birthday) => {
+            format_success(&format!("Announced **{}** birthday(s).", count))
+        }
+        // Announcements returned early above
+        (Ok(count), _) => format_success(&format!("Birthday roles updated: **{}** change(s).", count)),
+        (Err(e), _) => {
+            error!("Failed to run {:?} schedule manually in guild {}: {}", schedule_type, guild_id, e);
+            format_error("Failed to run the schedule, check the logs for details.")
+        }
+    };
+
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Delete birthday announcements after some days (leave empty to keep them)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_announcement_retention(
+    ctx: Context<'_>,
+    #[description = "Days after which announcements are deleted (1 removes yesterday's)"]
+    #[min = 1]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = match days {
+        Some(days) => format!("Birthday announcements will be deleted {} day(s) after being posted.", days),
+        None => "Birthday announcements will be kept.".to_string(),
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::AnnouncementRetention(days), &message).await?;
+
+    Ok(())
+}
+
+/// Post a heads-up some days before each birthday (leave empty to turn it off)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_reminder(
+    ctx: Context<'_>,
+    #[description = "Days in advance to post the reminder"]
+    #[min = 1]
+    #[max = 30]
+    days: Option<u32>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let days = days.map(|days| days.clamp(1, MAX_BIRTHDAY_REMINDER_DAYS));
+
+    let message = match days {
+        Some(days) => format!("A reminder will be posted {} day(s) before each birthday.", days),
+        None => "Birthday reminders are turned off.".to_string(),
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::ReminderDays(days), &message).await?;
+
+    Ok(())
+}
+
+/// Post birthday announcements without sending notifications
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_silent(
+    ctx: Context<'_>,
+    #[description = "Suppress push and desktop notifications of birthday announcements"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "Birthday announcements will be posted silently."
+    } else {
+        "Birthday announcements will notify members again."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::SilentAnnouncements(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Also wish birthday users a happy birthday in DMs
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_dm_greeting(
+    ctx: Context<'_>,
+    #[description = "Send each birthday user a private greeting besides the announcement"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "Birthday users will also be greeted in DMs."
+    } else {
+        "Birthday users will no longer be greeted in DMs."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::DmNotifications(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Choose whether birthday announcements ping the birthday users or only name them
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_ping(
+    ctx: Context<'_>,
+    #[description = "Mention the birthday users (off shows their names without pinging)"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "Birthday announcements will ping the birthday users."
+    } else {
+        "Birthday announcements will name the birthday users without pinging them."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::PingUsers(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Change how ages are phrased in default birthday entries, e.g. `{age} ans`
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_age_format(
+    ctx: Context<'_>,
+    #[description = "Age phrase with {age}, e.g. \"{age} ans\" (leave empty for \"turning {age}\")"]
+    format: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let template = match format.as_deref().map(validate_age_template).transpose() {
+        Ok(template) => template,
+        Err(e) => {
+            ctx.say(format_error(&e)).await?;
+            return Ok(());
+        }
+    };
+
+    let example = format_age_info(Some(25), template.as_deref().unwrap_or(DEFAULT_AGE_TEMPLATE));
+    let message = format!("Ages will be shown as `{}` in birthday announcements.", example.trim());
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::AgeTemplate(template), &message).await?;
+
+    Ok(())
+}
+
+/// Open a thread under birthday announcements where members can leave wishes
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_wishes_thread(
+    ctx: Context<'_>,
+    #[description = "Create a wishes thread under each birthday announcement"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "A wishes thread will be opened under birthday announcements."
+    } else {
+        "Birthday announcements will no longer get a wishes thread."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::WishesThread(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Preview the birthday announcement of this server, with you as the birthday user
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn birthday_test(
+    ctx: Context<'_>,
+    #[description = "Preview the message for users who shared their birth year (default: true)"]
+    with_age: Option<bool>,
+    #[description = "Post the preview in the birthday channel instead of only to you (default: false)"]
+    in_channel: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let data = ctx.data();
+
+    ctx.defer_ephemeral().await?;
+
+    // A sample 30 years old user, or one who didn't share their birth year
+    let birth_year = with_age
+        .unwrap_or(true)
+        .then(|| chrono::Utc::now().year() - 30);
+    let http = &ctx.serenity_context().http;
+    let notification =
+        match prepare_birthday_preview(http, data, guild_id, ctx.author().id, birth_year).await {
+            Ok(Some(notification)) => notification,
+            Ok(None) => {
+                ctx.say(format_info(
+                    "Birthday notifications are not configured for this server. Use `/setup_birthday` first.",
+                ))
+                .await?;
+                return Ok(());
+            }
+            Err(e) => {
+                error!("Failed to build birthday preview for guild {}: {}", guild_id, e);
+                ctx.say(format_error("Failed to build the preview!")).await?;
+                return Ok(());
+            }
+        };
+
+    if in_channel.unwrap_or(false) {
+        // Silent, the test shouldn't notify the whole channel
+        match send_birthday_notification(http, guild_id, &notification, true).await {
+            Ok(_) => {
+                ctx.say(format_success(&format!(
+                    "Preview posted in <#{}>.",
+                    notification.channel_id
+                )))
+                .await?;
+            }
+            Err(e) => {
+                data.record_http_error(&e);
+                ctx.say(format_error(&format!(
+                    "Failed to post the preview in <#{}>!",
+                    notification.channel_id
+                )))
+                .await?;
+            }
+        }
+        return Ok(());
+    }
+
+    let mut reply = poise::CreateReply::default().ephemeral(true);
+    if notification.embeds.is_empty() {
+        reply = reply.content(notification.messages.join("\n\n"));
+    } else {
+        for embed in &notification.embeds {
+            reply = reply.embed(build_birthday_embed(embed));
+        }
+    }
+    ctx.send(reply).await?;
+
+    Ok(())
+}
+
+/// Check the custom birthday texts of this server for broken placeholders or formatting
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn validate_birthday_templates(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let config = match ctx.data().db.get_birthday_config(guild_id).await {
+        Ok(Some(config)) => config,
+        Ok(None) => {
+            ctx.say(format_info(
+                "Birthday notifications are not configured for this server. Use `/setup_birthday` first.",
+            ))
+            .await?;
+            return Ok(());
+        }
+        Err(e) => {
+            error!("Failed to get birthday config for guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the birthday configuration!")).await?;
+            return Ok(());
+        }
+    };
+
+    // Render every configured text (each variant of the messages) against a sample user
+    let date = format_date_display(3, 15);
+    let render_variants = |name: &str, template: Option<String>, age: &str| -> Vec<(String, String)> {
+        let variants = template.as_deref().map(split_template_variants).unwrap_or_default();
+        let count = variants.len();
+        variants
+            .into_iter()
+            .enumerate()
+            .map(|(index, variant)| {
+                let name = if count > 1 {
+                    format!("{} #{}", name, index + 1)
+                } else {
+                    name.to_string()
+                };
+                (name, apply_message_template(&variant, "Sample User", "<@0>", &date, age))
+            })
+            .collect()
+    };
+    let mut rendered = render_variants("Message with age", config.custom_message, "30");
+    rendered.extend(render_variants("Message without age", config.custom_message_without_age, ""));
+    rendered.extend(
+        [("Header", process_custom_text(&config.custom_header)), ("Footer", process_custom_text(&config.custom_footer))]
+            .into_iter()
+            .filter_map(|(name, text)| text.map(|text| (name.to_string(), text))),
+    );
+
+    if rendered.is_empty() {
+        ctx.say(format_info("This server uses the default birthday texts, there is nothing to check."))
+            .await?;
+        return Ok(());
+    }
+
+    let problems: Vec<String> = rendered
+        .iter()
+        .filter_map(|(name, text)| {
+            let issues = find_template_issues(text, MAX_TEMPLATE_RENDERED_LENGTH);
+            (!issues.is_empty()).then(|| format!("**{}**: {}", name, issues.join(", ")))
+        })
+        .collect();
+
+    if problems.is_empty() {
+        ctx.say(format_success(&format!(
+            "All {} custom text(s) render correctly.",
+            rendered.len()
+        )))
+        .await?;
+    } else {
+        ctx.say(format_warning(&format!(
+            "Some custom texts look broken, update them with `/setup_birthday`:\n{}",
+            problems.join("\n")
+        )))
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// DM birthday users when the announcement channel can't be reached
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_dm_fallback(
+    ctx: Context<'_>,
+    #[description = "Wish users a happy birthday in DMs if the bot loses access to the channel"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = if enabled {
+        "Birthday users will be wished a happy birthday in DMs if the announcement channel can't be reached."
+    } else {
+        "Birthday users will no longer be DMed when the announcement channel can't be reached."
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::DmFallback(enabled), message).await?;
+
+    Ok(())
+}
+
+/// Announce birthdays in another channel on weekends (leave empty to use the main channel)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_weekend_channel(
+    ctx: Context<'_>,
+    #[description = "Channel for Saturday and Sunday announcements"]
+    #[channel_types("Text")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let message = match &channel {
+        Some(channel) => format!("Weekend birthdays will now be announced in <#{}>.", channel.id),
+        None => "Weekend birthdays will now be announced in the main birthday channel.".to_string(),
+    };
+    let setting = BirthdaySetting::WeekendChannel(channel.map(|c| c.id));
+    save_birthday_setting(ctx, guild_id, setting, &message).await?;
+
+    Ok(())
+}
+
+/// Copy birthday announcements to other channels (leave empty to stop copying them)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_birthday_mirror_channels(
+    ctx: Context<'_>,
+    #[description = "Channel that also receives the announcements"]
+    #[channel_types("Text")]
+    first: Option<GuildChannel>,
+    #[description = "Another channel that also receives the announcements"]
+    #[channel_types("Text")]
+    second: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let mut channel_ids: Vec<ChannelId> = first.iter().chain(second.iter()).map(|c| c.id).collect();
+    channel_ids.dedup();
+
+    let message = if channel_ids.is_empty() {
+        "Birthday announcements will only be posted in the main channel.".to_string()
+    } else {
+        let mentions: Vec<String> = channel_ids.iter().map(|id| format!("<#{}>", id)).collect();
+        format!("Birthday announcements will also be posted in {}.", mentions.join(" and "))
+    };
+    save_birthday_setting(ctx, guild_id, BirthdaySetting::MirrorChannels(channel_ids), &message).await?;
+
+    Ok(())
+}
+
+/// File format of a birthday export
+#[derive(Debug, Clone, Copy, PartialEq, Eq, poise::ChoiceParameter)]
+pub enum ExportFormat {
+    #[name = "CSV"]
+    Csv,
+    #[name = "JSON"]
+    Json,
+}
+
+/// Export the birthdays of the server members as a file
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn export_birthdays(
+    ctx: Context<'_>,
+    #[description = "File format (default: CSV, which /import_birthdays accepts)"]
+    format: Option<ExportFormat>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let format = format.unwrap_or(ExportFormat::Csv);
+
+    // Fetching the member list of a large server may take a while
+    ctx.defer_ephemeral().await?;
+
+    let serenity_ctx = ctx.serenity_context();
+    let members = match fetch_guild_members(&serenity_ctx.http, &serenity_ctx.cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the server members!")).await?;
+            return Ok(());
+        }
+    };
+
+    // Birthdays are global, only export the current members of this server
+    let member_ids: Vec<UserId> = members.into_keys().collect();
+    let mut birthdays = match ctx.data().db.get_birthdays_for_users(&member_ids).await {
+        Ok(birthdays) => birthdays,
+        Err(e) => {
+            error!("Failed to load birthdays for export: {}", e);
+            ctx.say(format_error("Failed to load birthdays!")).await?;
+            return Ok(());
+        }
+    };
+
+    if birthdays.is_empty() {
+        ctx.say(format_info("No member of this server has set a birthday yet."))
+            .await?;
+        return Ok(());
+    }
+    birthdays.sort_by_key(|(user_id, ..)| *user_id);
+
+    let (content, extension) = match format {
+        ExportFormat::Csv => (export_birthdays_csv(&birthdays), "csv"),
+        ExportFormat::Json => (export_birthdays_json(&birthdays), "json"),
+    };
+    if content.len() > MAX_EXPORT_FILE_SIZE {
+        ctx.say(format_error(&format!(
+            "The export is too large to be sent as a file (max {} MB)!",
+            MAX_EXPORT_FILE_SIZE / (1024 * 1024)
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    let attachment = CreateAttachment::bytes(content.into_bytes(), format!("birthdays.{}", extension));
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format_success(&format!("Exported **{}** birthday(s).", birthdays.len())))
+            .attachment(attachment),
+    )
+    .await?;
+
+    info!("Exported {} birthday(s) of guild {}", birthdays.len(), guild_id);
+    Ok(())
+}
+
+/// Import birthdays of server members from a CSV or JSON file (year is optional)
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn import_birthdays(
+    ctx: Context<'_>,
+    #[description = "CSV file with user_id,month,day,year rows, or a JSON export of /export_birthdays"]
+    file: Attachment,
+    #[description = "Only report what would change, without saving anything (default: false)"]
+    preview: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    if file.size > MAX_IMPORT_FILE_SIZE {
+        ctx.say(format_error(&format!(
+            "The file is too large (max {} KB)!",
+            MAX_IMPORT_FILE_SIZE / 1024
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    ctx.defer_ephemeral().await?;
+
+    let content = match file.download().await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            ctx.data().record_http_error(&e);
+            error!("Failed to download import file: {}", e);
+            ctx.say(format_error("Failed to download the file!")).await?;
+            return Ok(());
+        }
+    };
+    let Ok(content) = String::from_utf8(content) else {
+        ctx.say(format_error("The file must be UTF-8 encoded text!")).await?;
+        return Ok(());
+    };
+
+    let current_year = chrono::Utc::now().year();
+    let mut parsed = if is_json_import(&file.filename, &content) {
+        parse_birthday_json(&content, current_year)
+    } else {
+        parse_birthday_csv(&content, current_year)
+    };
+
+    // Birthdays are global, don't let a server import birthdays of users it doesn't have
+    let serenity_ctx = ctx.serenity_context();
+    let members = match fetch_guild_members(&serenity_ctx.http, &serenity_ctx.cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load the server members!")).await?;
+            return Ok(());
+        }
+    };
+    let parsed_count = parsed.rows.len();
+    parsed
+        .rows
+        .retain(|row| members.contains_key(&UserId::new(row.user_id)));
+    let skipped = parsed_count - parsed.rows.len();
+
+    let birthdays: Vec<(UserId, i32, i32, Option<i32>)> = parsed
+        .rows
+        .iter()
+        .map(|row| (UserId::new(row.user_id), row.month, row.day, row.year))
+        .collect();
+
+    if preview.unwrap_or(false) {
+        let user_ids: Vec<UserId> = birthdays.iter().map(|(user_id, ..)| *user_id).collect();
+        let existing = match ctx.data().db.get_birthdays_for_users(&user_ids).await {
+            Ok(existing) => existing
+                .into_iter()
+                .map(|(user_id, month, day, year)| (user_id.get(), (month, day, year)))
+                .collect(),
+            Err(e) => {
+                error!("Failed to load existing birthdays: {}", e);
+                ctx.say(format_error("Failed to load the existing birthdays!")).await?;
+                return Ok(());
+            }
+        };
+        let summary = summarize_import(&parsed.rows, &existing);
+
+        let mut report = format_info(&format!(
+            "**Preview**, nothing was saved. Importing this file would:\n\
+            • Add **{}** new birthday(s)\n\
+            • Overwrite **{}** existing birthday(s)\n\
+            • Leave **{}** birthday(s) unchanged\n\
+            • Skip **{}** user(s) who are not members of this server\n\
+            • Reject **{}** row(s)",
+            summary.added,
+            summary.overwritten,
+            summary.unchanged,
+            skipped,
+            parsed.errors.len()
+        ));
+        if !parsed.errors.is_empty() {
+            report.push_str(&format!("\n{}", format_import_errors(&parsed.errors)));
+        }
+        ctx.say(report).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.upsert_birthdays(&birthdays).await {
+        error!("Failed to import birthdays: {}", e);
+        ctx.say(format_error("Failed to save the imported birthdays!")).await?;
+        return Ok(());
+    }
+
+    let mut report = if parsed.errors.is_empty() {
+        format_success(&format!("Imported **{}** birthday(s).", birthdays.len()))
+    } else {
+        format_warning(&format!(
+            "Imported **{}** birthday(s), **{}** row(s) rejected.",
+            birthdays.len(),
+            parsed.errors.len()
+        ))
+    };
+    if skipped > 0 {
+        report.push_str(&format!(
+            "\n{}",
+            format_info(&format!("Skipped **{}** user(s) who are not members of this server.", skipped))
+        ));
+    }
+    if !parsed.errors.is_empty() {
+        report.push_str(&format!("\n{}", format_import_errors(&parsed.errors)));
+    }
+    ctx.say(report).await?;
+
+    info!(
+        "Imported {} birthdays ({} rejected rows, {} non-members skipped) in guild {}",
+        birthdays.len(),
+        parsed.errors.len(),
+        skipped,
+        guild_id
+    );
+
+    Ok(())
+}
+
+/// List the first rejected rows of an import
+fn format_import_errors(errors: &[ImportError]) -> String {
+    let mut lines: Vec<String> = errors
+        .iter()
+        .take(MAX_IMPORT_ERRORS_SHOWN)
+        .map(|e| format!("• Line {}: {}", e.line, e.message))
+        .collect();
+    if errors.len() > MAX_IMPORT_ERRORS_SHOWN {
+        lines.push(format!("…and {} more", errors.len() - MAX_IMPORT_ERRORS_SHOWN));
+    }
+    lines.join("\n")
+}
@@ -0,0 +1,89 @@
+use poise::serenity_prelude::{self as serenity, CreateAllowedMentions, CreateMessage};
+use tracing::{info, warn};
+
+use crate::{
+    models::{Context, Error},
+    utils::http_errors::{HttpErrorKind, classify_http_error},
+    utils::messages::{format_error, format_success},
+};
+
+/// Send feedback or report an issue to the bot operator
+#[poise::command(slash_command, user_cooldown = 300, ephemeral)]
+pub async fn feedback(
+    ctx: Context<'_>,
+    #[description = "Your feedback or issue description"]
+    #[max_length = 1500]
+    message: String,
+) -> Result<(), Error> {
+    let author = ctx.author();
+    let guild = match ctx.guild_id() {
+        Some(guild_id) => {
+            let name = ctx.guild().map(|g| g.name.clone()).unwrap_or_default();
+            format!("{} (`{}`)", name, guild_id)
+        }
+        None => "Direct message".to_string(),
+    };
+
+    let content = format!(
+        "📨 **New feedback**\nFrom: {} (`{}`)\nServer: {}\n\n{}",
+        author.tag(),
+        author.id,
+        guild,
+        message
+    );
+
+    let delivered = match ctx.data().feedback_channel {
+        Some(channel_id) => {
+            let result = channel_id
+                .send_message(ctx.http(), build_feedback_message(&content))
+                .await;
+            report_delivery(ctx, result.map(|_| ()), &format!("channel {}", channel_id))
+        }
+        None => {
+            let owners = ctx.framework().options().owners.clone();
+            let mut delivered = false;
+            for owner_id in owners {
+                let result = owner_id
+                    .direct_message(ctx.http(), build_feedback_message(&content))
+                    .await;
+                delivered |= report_delivery(ctx, result.map(|_| ()), &format!("owner {}", owner_id));
+            }
+            delivered
+        }
+    };
+
+    if delivered {
+        ctx.say(format_success("Thanks! Your feedback has been sent to the bot operator."))
+            .await?;
+        info!("Forwarded feedback from user {}", author.id);
+    } else {
+        ctx.say(format_error("Your feedback could not be delivered, please try again later."))
+            .await?;
+    }
+
+    Ok(())
+}
+
+/// Build the forwarded feedback message. The text is user-written, so every mention
+/// is disabled to keep it from pinging anyone where it lands.
+fn build_feedback_message(content: &str) -> CreateMessage {
+    CreateMessage::new()
+        .content(content)
+        .allowed_mentions(CreateAllowedMentions::new())
+}
+
+/// Log the outcome of a feedback delivery, returning whether it succeeded
+fn report_delivery(ctx: Context<'_>, result: Result<(), serenity::Error>, target: &str) -> bool {
+    match result {
+        Ok(()) => true,
+        Err(e) => {
+            ctx.data().record_http_error(&e);
+            if classify_http_error(&e) == HttpErrorKind::Forbidden {
+                warn!("Not allowed to deliver feedback to {} (closed DMs or missing permissions)", target);
+            } else {
+                warn!("Failed to deliver feedback to {}: {}", target, e);
+            }
+            false
+        }
+    }
+}
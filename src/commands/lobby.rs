@@ -1,10 +1,29 @@
-use poise::serenity_prelude::{ChannelType, CreateChannel, GuildChannel};
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, ChannelType, CreateChannel, GuildChannel, GuildId, UserId,
+};
+use std::collections::BTreeSet;
 use tracing::{error, info};
 
 use crate::{
-    constants::DEFAULT_LOBBY_NAME,
-    models::{Context, Error},
-    utils::messages::{format_error, format_success},
+    constants::{
+        BULK_DELETE_DELAY_MS, BULK_DELETE_JITTER_MS, CLAIM_GRACE_PERIOD_SECS, KICK_COOLDOWN_SECS, DEFAULT_LOBBY_NAME, DEFAULT_NAME_TEMPLATE,
+        ARCHIVE_CATEGORY_NAME, MAX_CHANNEL_NAME_BLACKLIST_WORDS, MAX_CHANNEL_NAME_LENGTH, MAX_EMPTY_CHANNEL_GRACE_SECS,
+        MAX_PERSISTENT_CHANNELS_CAP,
+    },
+    handlers::{
+        ban_member_from_channel, delete_temp_channel, kick_member_from_channel,
+        restore_archived_channel, send_channel_config_message, transfer_channel_ownership,
+        unban_member_from_channel,
+    },
+    models::{Context, Error, LobbyChannel},
+    utils::messages::{build_context_error, format_error, format_info, format_success, format_warning},
+    utils::channel_utils::{
+        build_archive_category_name, clamp_bitrate, format_lobby_bitrate, is_valid_name_template,
+        max_bitrate_for_boost_tier, normalize_user_limit,
+    },
+    utils::string_utils::{normalize_whitespace, split_and_trim},
+    utils::http_errors::{HttpErrorKind, classify_http_error},
+    utils::permissions::can_claim_channel,
     utils::validation::require_guild,
 };
 
@@ -13,9 +32,28 @@ use crate::{
 pub async fn create_lobby(
     ctx: Context<'_>,
     #[description = "Name for the lobby channel"] name: Option<String>,
+    #[description = "Maximum users in each spawned channel (0 or empty for unlimited, max 99)"]
+    user_limit: Option<u32>,
+    #[description = "Name of spawned channels, with {user} and {count} placeholders"]
+    name_template: Option<String>,
+    #[description = "Bitrate of spawned channels in kbps (default: the lobby's own bitrate)"]
+    #[min = 8]
+    #[max = 384]
+    bitrate: Option<u32>,
 ) -> Result<(), Error> {
     let guild_id = require_guild(ctx.guild_id())?;
 
+    if let Some(template) = &name_template
+        && !is_valid_name_template(template, MAX_CHANNEL_NAME_LENGTH as usize)
+    {
+        ctx.say(format_error(&format!(
+            "The name template must not be empty and at most {} characters long!",
+            MAX_CHANNEL_NAME_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
     let lobby_name = name.unwrap_or_else(|| DEFAULT_LOBBY_NAME.to_string());
 
     // Create the lobby voice channel
@@ -24,25 +62,31 @@ pub async fn create_lobby(
             ctx.http(),
             CreateChannel::new(&lobby_name).kind(ChannelType::Voice),
         )
-        .await?;
+        .await
+        .inspect_err(|e| ctx.data().record_http_error(e))?;
 
-    // Store the lobby channel
-    ctx.data().lobby_channels.insert(channel.id, guild_id);
+    let lobby = LobbyChannel {
+        guild_id,
+        user_limit: user_limit.and_then(normalize_user_limit),
+        name_template,
+        bitrate: bitrate.map(|kbps| lobby_bitrate(ctx, kbps)),
+    };
 
     // Save to database
-    if let Err(e) = ctx
-        .data()
-        .db
-        .insert_lobby_channel(channel.id, guild_id)
-        .await
-    {
+    if let Err(e) = ctx.data().db.insert_lobby_channel(channel.id, &lobby).await {
         error!("Failed to save lobby channel to database: {}", e);
     }
 
+    let bitrate_line = lobby_bitrate_line(bitrate, &lobby);
+
+    // Store the lobby channel
+    ctx.data().lobby_channels.insert(channel.id, lobby);
+
     ctx.say(format!(
-        "{}\nLobby: <#{}>\nUsers joining this channel will get their own temporary voice channel!",
+        "{}\nLobby: <#{}>{}\nUsers joining this channel will get their own temporary voice channel!",
         format_success("Lobby channel created!"),
-        channel.id
+        channel.id,
+        bitrate_line
     ))
     .await?;
 
@@ -51,6 +95,24 @@ pub async fn create_lobby(
     Ok(())
 }
 
+/// Convert a bitrate in kbps to the bps stored with a lobby, clamped to what the guild's
+/// boost tier allows (left as is when the guild isn't cached, it is clamped on use)
+fn lobby_bitrate(ctx: Context<'_>, kbps: u32) -> u32 {
+    let bitrate = kbps.saturating_mul(1000);
+    match ctx.guild().map(|guild| u8::from(guild.premium_tier)) {
+        Some(boost_tier) => clamp_bitrate(bitrate, max_bitrate_for_boost_tier(boost_tier)),
+        None => bitrate,
+    }
+}
+
+/// Describe the bitrate saved for a lobby, as a line of the command's reply
+fn lobby_bitrate_line(requested_kbps: Option<u32>, lobby: &LobbyChannel) -> String {
+    match (requested_kbps, lobby.bitrate) {
+        (Some(kbps), Some(bitrate)) => format!("\nBitrate: {}", format_lobby_bitrate(kbps, bitrate)),
+        _ => String::new(),
+    }
+}
+
 /// Convert an existing voice channel into a lobby managed by the bot
 #[poise::command(slash_command, required_permissions = "MANAGE_CHANNELS")]
 pub async fn convert_to_lobby(
@@ -58,9 +120,28 @@ pub async fn convert_to_lobby(
     #[description = "The voice channel to convert into a lobby"]
     #[channel_types("Voice")]
     channel: GuildChannel,
+    #[description = "Maximum users in each spawned channel (0 or empty for unlimited, max 99)"]
+    user_limit: Option<u32>,
+    #[description = "Name of spawned channels, with {user} and {count} placeholders"]
+    name_template: Option<String>,
+    #[description = "Bitrate of spawned channels in kbps (default: the lobby's own bitrate)"]
+    #[min = 8]
+    #[max = 384]
+    bitrate: Option<u32>,
 ) -> Result<(), Error> {
     let guild_id = require_guild(ctx.guild_id())?;
 
+    if let Some(template) = &name_template
+        && !is_valid_name_template(template, MAX_CHANNEL_NAME_LENGTH as usize)
+    {
+        ctx.say(format_error(&format!(
+            "The name template must not be empty and at most {} characters long!",
+            MAX_CHANNEL_NAME_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
     // Verify the channel is a voice channel
     if channel.kind != ChannelType::Voice {
         ctx.say(format_error("The selected channel must be a voice channel!"))
@@ -81,23 +162,28 @@ pub async fn convert_to_lobby(
         return Ok(());
     }
 
-    // Store the lobby channel
-    ctx.data().lobby_channels.insert(channel.id, guild_id);
+    let lobby = LobbyChannel {
+        guild_id,
+        user_limit: user_limit.and_then(normalize_user_limit),
+        name_template,
+        bitrate: bitrate.map(|kbps| lobby_bitrate(ctx, kbps)),
+    };
 
     // Save to database
-    if let Err(e) = ctx
-        .data()
-        .db
-        .insert_lobby_channel(channel.id, guild_id)
-        .await
-    {
+    if let Err(e) = ctx.data().db.insert_lobby_channel(channel.id, &lobby).await {
         error!("Failed to save lobby channel to database: {}", e);
     }
 
+    let bitrate_line = lobby_bitrate_line(bitrate, &lobby);
+
+    // Store the lobby channel
+    ctx.data().lobby_channels.insert(channel.id, lobby);
+
     ctx.say(format!(
-        "{}\nChannel <#{}> is now a lobby! Users joining will get their own temporary voice channel.",
+        "{}\nChannel <#{}> is now a lobby! Users joining will get their own temporary voice channel.{}",
         format_success("Converted to lobby channel!"),
-        channel.id
+        channel.id,
+        bitrate_line
     ))
     .await?;
 
@@ -108,3 +194,1205 @@ pub async fn convert_to_lobby(
 
     Ok(())
 }
+
+/// Unregister a lobby so it stops spawning temporary channels, optionally deleting it
+#[poise::command(slash_command, required_permissions = "MANAGE_CHANNELS")]
+pub async fn remove_lobby(
+    ctx: Context<'_>,
+    #[description = "The lobby channel to unregister"]
+    #[channel_types("Voice")]
+    channel: GuildChannel,
+    #[description = "Also delete the channel from the server (default: false)"]
+    delete_channel: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let is_lobby = ctx
+        .data()
+        .lobby_channels
+        .get(&channel.id)
+        .is_some_and(|lobby| lobby.guild_id == guild_id);
+    if !is_lobby {
+        ctx.say(format_error("This channel is not a lobby!")).await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.remove_lobby_channel(channel.id).await {
+        error!("Failed to remove lobby channel from database: {}", e);
+        ctx.say(format_error("Failed to remove the lobby!")).await?;
+        return Ok(());
+    }
+    ctx.data().lobby_channels.remove(&channel.id);
+
+    info!("Removed lobby {} in guild {}", channel.id, guild_id);
+
+    if !delete_channel.unwrap_or(false) {
+        ctx.say(format_success(&format!(
+            "<#{}> is no longer a lobby. The channel itself was kept.",
+            channel.id
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    match channel.id.delete(ctx.http()).await {
+        Ok(_) => {
+            ctx.say(format_success(&format!(
+                "Lobby **{}** was removed and deleted.",
+                channel.name
+            )))
+            .await?;
+        }
+        Err(e) => {
+            ctx.data().record_http_error(&e);
+            error!("Failed to delete lobby channel {}: {}", channel.id, e);
+            ctx.say(format_warning(&format!(
+                "<#{}> is no longer a lobby, but the channel could not be deleted.",
+                channel.id
+            )))
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Post the configuration buttons of your temporary channel again
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn resend_config(ctx: Context<'_>) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+
+    let Some(is_persistent) = ctx
+        .data()
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| tc.is_persistent)
+    else {
+        ctx.say(format_error("Use this command in the chat of your temporary channel!"))
+            .await?;
+        return Ok(());
+    };
+
+    if !ctx.data().is_channel_owner(channel_id, ctx.author().id) {
+        ctx.say(format_error("Only the channel owner can do this!"))
+            .await?;
+        return Ok(());
+    }
+
+    let member = ctx
+        .author_member()
+        .await
+        .ok_or_else(|| build_context_error("in a server"))?
+        .into_owned();
+
+    if let Err(e) = send_channel_config_message(
+        ctx.serenity_context(),
+        channel_id,
+        &member,
+        is_persistent,
+        ctx.data(),
+    )
+    .await
+    {
+        error!("Failed to resend config message in {}: {}", channel_id, e);
+        ctx.say(format_error("Failed to send the configuration message!"))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success("Configuration message sent.")).await?;
+
+    Ok(())
+}
+
+/// Check if a user is currently connected to a voice channel, according to the cache
+fn is_in_voice_channel(ctx: Context<'_>, user_id: UserId, channel_id: ChannelId) -> bool {
+    ctx.guild().is_some_and(|guild| {
+        guild
+            .voice_states
+            .get(&user_id)
+            .is_some_and(|state| state.channel_id == Some(channel_id))
+    })
+}
+
+/// Give your temporary channel to another member connected to it
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn transfer_channel(
+    ctx: Context<'_>,
+    #[description = "The member who will own the channel"] new_owner: serenity::User,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+
+    if !ctx.data().temp_channels.contains_key(&channel_id) {
+        ctx.say(format_error("Use this command in the chat of your temporary channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if !ctx.data().is_channel_owner(channel_id, ctx.author().id) {
+        ctx.say(format_error("Only the channel owner can do this!"))
+            .await?;
+        return Ok(());
+    }
+
+    if new_owner.bot || new_owner.id == ctx.author().id {
+        ctx.say(format_error("Pick another member to own the channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if !is_in_voice_channel(ctx, new_owner.id, channel_id) {
+        ctx.say(format_error("The new owner must be connected to the channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if let Err(e) =
+        transfer_channel_ownership(ctx.serenity_context(), channel_id, new_owner.id, ctx.data()).await
+    {
+        error!("Failed to transfer channel {}: {}", channel_id, e);
+        ctx.say(format_error("Failed to transfer the channel!")).await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success(&format!("<@{}> now owns this channel.", new_owner.id)))
+        .await?;
+
+    Ok(())
+}
+
+/// Disconnect a member from your temporary channel and keep them out for a few minutes
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn kick_from_channel(
+    ctx: Context<'_>,
+    #[description = "The member to disconnect from your channel"] member: serenity::User,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+
+    if !ctx.data().temp_channels.contains_key(&channel_id) {
+        ctx.say(format_error("Use this command in the chat of your temporary channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if !ctx.data().is_channel_owner(channel_id, ctx.author().id) {
+        ctx.say(format_error("Only the channel owner can do this!"))
+            .await?;
+        return Ok(());
+    }
+
+    if member.id == ctx.author().id {
+        ctx.say(format_error("You can't kick yourself from your own channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if !is_in_voice_channel(ctx, member.id, channel_id) {
+        ctx.say(format_error("This member isn't connected to your channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if let Err(e) =
+        kick_member_from_channel(ctx.serenity_context(), channel_id, member.id, ctx.data()).await
+    {
+        error!("Failed to kick {} from channel {}: {}", member.id, channel_id, e);
+        ctx.say(format_error("Failed to kick the member!")).await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success(&format!(
+        "<@{}> was disconnected and can't rejoin for {} minutes.",
+        member.id,
+        KICK_COOLDOWN_SECS / 60
+    )))
+    .await?;
+
+    Ok(())
+}
+
+/// Check that a command is used in the chat of a temp channel owned by its author, telling
+/// them otherwise
+async fn ensure_own_temp_channel(ctx: Context<'_>, channel_id: ChannelId) -> Result<bool, Error> {
+    if !ctx.data().temp_channels.contains_key(&channel_id) {
+        ctx.say(format_error("Use this command in the chat of your temporary channel!"))
+            .await?;
+        return Ok(false);
+    }
+
+    if !ctx.data().is_channel_owner(channel_id, ctx.author().id) {
+        ctx.say(format_error("Only the channel owner can do this!"))
+            .await?;
+        return Ok(false);
+    }
+
+    Ok(true)
+}
+
+/// Keep a member out of your temporary channel until you unban them
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn ban_from_channel(
+    ctx: Context<'_>,
+    #[description = "The member to ban from your channel"] member: serenity::User,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+    if !ensure_own_temp_channel(ctx, channel_id).await? {
+        return Ok(());
+    }
+
+    if member.id == ctx.author().id {
+        ctx.say(format_error("You can't ban yourself from your own channel!"))
+            .await?;
+        return Ok(());
+    }
+
+    if let Err(e) =
+        ban_member_from_channel(ctx.serenity_context(), channel_id, member.id, ctx.data()).await
+    {
+        error!("Failed to ban {} from channel {}: {}", member.id, channel_id, e);
+        ctx.say(format_error("Failed to ban the member!")).await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success(&format!(
+        "<@{}> is banned from this channel, even after it is archived and restored.",
+        member.id
+    )))
+    .await?;
+
+    Ok(())
+}
+
+/// Let a banned member back into your temporary channel, or everyone when no member is given
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn unban_from_channel(
+    ctx: Context<'_>,
+    #[description = "The member to unban (leave empty to clear all bans)"] member: Option<serenity::User>,
+) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+    if !ensure_own_temp_channel(ctx, channel_id).await? {
+        return Ok(());
+    }
+
+    let user_ids = match &member {
+        Some(member) => vec![member.id],
+        None => match ctx.data().db.get_channel_bans(channel_id).await {
+            Ok(bans) => bans
+                .into_iter()
+                .filter(|ban| ban.expires_at.is_none())
+                .map(|ban| ban.user_id)
+                .collect(),
+            Err(e) => {
+                error!("Failed to get bans of channel {}: {}", channel_id, e);
+                ctx.say(format_error("Failed to load the bans of this channel!")).await?;
+                return Ok(());
+            }
+        },
+    };
+
+    let mut unbanned = 0;
+    for user_id in &user_ids {
+        match unban_member_from_channel(ctx.serenity_context(), channel_id, *user_id, ctx.data()).await {
+            Ok(true) => unbanned += 1,
+            Ok(false) => {}
+            Err(e) => error!("Failed to unban {} from channel {}: {}", user_id, channel_id, e),
+        }
+    }
+
+    let message = match (&member, unbanned) {
+        (Some(member), 1) => format_success(&format!("<@{}> can join this channel again.", member.id)),
+        (Some(member), _) => format_info(&format!("<@{}> isn't banned from this channel.", member.id)),
+        (None, 0) if user_ids.is_empty() => format_info("Nobody is banned from this channel."),
+        (None, _) => format_success(&format!(
+            "Cleared {} of {} ban(s) from this channel.",
+            unbanned,
+            user_ids.len()
+        )),
+    };
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// List the members banned or kicked from your temporary channel
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn channel_bans(ctx: Context<'_>) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+    if !ensure_own_temp_channel(ctx, channel_id).await? {
+        return Ok(());
+    }
+
+    let bans = match ctx.data().db.get_channel_bans(channel_id).await {
+        Ok(bans) => bans,
+        Err(e) => {
+            error!("Failed to get bans of channel {}: {}", channel_id, e);
+            ctx.say(format_error("Failed to load the bans of this channel!")).await?;
+            return Ok(());
+        }
+    };
+    let message = if bans.is_empty() {
+        format_info("Nobody is banned from this channel.")
+    } else {
+        let list = bans
+            .iter()
+            .map(|ban| match ban.expires_at {
+                Some(expires_at) => format!(
+                    "• <@{}> (kicked, back <t:{}:R>)",
+                    ban.user_id,
+                    expires_at.timestamp()
+                ),
+                None => format!("• <@{}>", ban.user_id),
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        format!(
+            "**Banned from this channel:**\n{}\n\nUse `/unban_from_channel` to let them back in.",
+            list
+        )
+    };
+    ctx.say(message).await?;
+
+    Ok(())
+}
+
+/// Take over a temporary channel whose owner has left it
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn claim_channel(ctx: Context<'_>) -> Result<(), Error> {
+    let channel_id = ctx.channel_id();
+    let user_id = ctx.author().id;
+
+    let Some((owner_id, owner_left_at)) = ctx
+        .data()
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| (tc.owner_id, tc.owner_left_at))
+    else {
+        ctx.say(format_error("Use this command in the chat of a temporary channel!"))
+            .await?;
+        return Ok(());
+    };
+
+    if owner_id == user_id {
+        ctx.say(format_info("You already own this channel.")).await?;
+        return Ok(());
+    }
+
+    if !is_in_voice_channel(ctx, user_id, channel_id) {
+        ctx.say(format_error("You must be connected to the channel to claim it!"))
+            .await?;
+        return Ok(());
+    }
+
+    let owner_present = is_in_voice_channel(ctx, owner_id, channel_id);
+    let secs_since_owner_left = owner_left_at.map(|left_at| left_at.elapsed().as_secs());
+    if !can_claim_channel(owner_present, secs_since_owner_left, CLAIM_GRACE_PERIOD_SECS) {
+        ctx.say(format_error(&format!(
+            "The owner must have left the channel for at least {} seconds before it can be claimed!",
+            CLAIM_GRACE_PERIOD_SECS
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) =
+        transfer_channel_ownership(ctx.serenity_context(), channel_id, user_id, ctx.data()).await
+    {
+        error!("Failed to claim channel {}: {}", channel_id, e);
+        ctx.say(format_error("Failed to claim the channel!")).await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success("You now own this channel.")).await?;
+
+    Ok(())
+}
+
+/// Show the owner, status and age of the temporary channel you're in
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn channel_info(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(temp_channel) = ctx
+        .data()
+        .temp_channels
+        .get(&ctx.channel_id())
+        .map(|tc| tc.clone())
+    else {
+        ctx.say(format_error("Use this command in the chat of a temporary channel!"))
+            .await?;
+        return Ok(());
+    };
+
+    let status = match (temp_channel.is_persistent, temp_channel.is_locked) {
+        (true, true) => "Persistent, locked",
+        (true, false) => "Persistent",
+        (false, true) => "Temporary, locked",
+        (false, false) => "Temporary",
+    };
+
+    ctx.say(format!(
+        "**Owner:** <@{}>\n**Lobby:** <#{}>\n**Status:** {}\n**Created:** <t:{}:R>",
+        temp_channel.owner_id,
+        temp_channel.lobby_channel_id,
+        status,
+        temp_channel.created_at.timestamp()
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Show who owns a temporary channel (defaults to the voice channel you're in)
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn whois_owner(
+    ctx: Context<'_>,
+    #[description = "Temporary channel to look up (default: your current voice channel)"]
+    #[channel_types("Voice")]
+    channel: Option<GuildChannel>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let channel_id = match channel {
+        Some(channel) => channel.id,
+        None => {
+            let current = ctx.guild().and_then(|guild| {
+                guild
+                    .voice_states
+                    .get(&ctx.author().id)
+                    .and_then(|voice_state| voice_state.channel_id)
+            });
+            match current {
+                Some(channel_id) => channel_id,
+                None => {
+                    ctx.say(format_error(
+                        "You're not in a voice channel, pick the channel to look up!",
+                    ))
+                    .await?;
+                    return Ok(());
+                }
+            }
+        }
+    };
+
+    let Some(temp_channel) = ctx
+        .data()
+        .temp_channels
+        .get(&channel_id)
+        .filter(|tc| tc.guild_id == guild_id)
+        .map(|tc| tc.clone())
+    else {
+        ctx.say(format_info(&format!("<#{}> is not a temporary channel.", channel_id)))
+            .await?;
+        return Ok(());
+    };
+
+    let status = match (temp_channel.is_persistent, temp_channel.is_archived) {
+        (_, true) => "Archived",
+        (true, false) => "Persistent",
+        (false, false) => "Temporary",
+    };
+
+    ctx.say(format!(
+        "**Channel:** <#{}>\n**Owner:** <@{}>\n**Lobby:** <#{}>\n**Status:** {}",
+        channel_id, temp_channel.owner_id, temp_channel.lobby_channel_id, status
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Enable or disable DMs telling owners how to restore their archived channels
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_archive_dm(
+    ctx: Context<'_>,
+    #[description = "Send a DM to owners when their persistent channel is archived"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    if let Err(e) = ctx.data().db.set_archive_dm_enabled(guild_id, enabled).await {
+        error!("Failed to save archive DM setting: {}", e);
+        ctx.say(format_error("Failed to save archive DM setting!")).await?;
+        return Ok(());
+    }
+
+    let message = if enabled {
+        "Owners will now receive a DM explaining how to restore their archived channels."
+    } else {
+        "Owners will no longer receive a DM when their channels are archived."
+    };
+    ctx.say(format_success(message)).await?;
+
+    info!("Set archive DM to {} for guild {}", enabled, guild_id);
+
+    Ok(())
+}
+
+/// Set how long an empty temporary channel is kept before being deleted or archived
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_empty_channel_grace(
+    ctx: Context<'_>,
+    #[description = "Seconds to wait for members to come back (0 to clean up immediately, max 600)"]
+    seconds: u32,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let seconds = seconds.min(MAX_EMPTY_CHANNEL_GRACE_SECS);
+
+    if let Err(e) = ctx.data().db.set_empty_channel_grace_secs(guild_id, seconds).await {
+        error!("Failed to save empty channel grace period: {}", e);
+        ctx.say(format_error("Failed to save the grace period!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    let message = if seconds == 0 {
+        "Empty temporary channels will be cleaned up immediately.".to_string()
+    } else {
+        format!(
+            "Empty temporary channels will be cleaned up after {} seconds if nobody comes back.",
+            seconds
+        )
+    };
+    ctx.say(format_success(&message)).await?;
+
+    info!("Set empty channel grace period to {}s for guild {}", seconds, guild_id);
+
+    Ok(())
+}
+
+/// Set the name of the category archived channels are moved to in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_archive_category_name(
+    ctx: Context<'_>,
+    #[description = "Base name of the archive category, e.g. Voice gives '📦 Voice Archive' (empty to reset)"]
+    name: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let base_name = name
+        .map(|n| normalize_whitespace(&n))
+        .filter(|n| !n.is_empty());
+    let category_name = base_name
+        .as_deref()
+        .map(build_archive_category_name)
+        .unwrap_or_else(|| ARCHIVE_CATEGORY_NAME.to_string());
+    if category_name.chars().count() > MAX_CHANNEL_NAME_LENGTH as usize {
+        ctx.say(format_error(&format!(
+            "The archive category name cannot exceed {} characters!",
+            MAX_CHANNEL_NAME_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.set_archive_category_base(guild_id, base_name).await {
+        error!("Failed to save archive category name: {}", e);
+        ctx.say(format_error("Failed to save the archive category name!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    ctx.say(format_success(&format!(
+        "New archive categories will be named **{}**. \
+        An existing archive category keeps its current name.",
+        category_name
+    )))
+    .await?;
+
+    info!("Set archive category name to '{}' for guild {}", category_name, guild_id);
+
+    Ok(())
+}
+
+/// Limit how many persistent channels each user may own in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_max_persistent_channels(
+    ctx: Context<'_>,
+    #[description = "Persistent channels allowed per user across all lobbies (0 for no limit, max 25)"]
+    max_channels: u32,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let max_channels = max_channels.min(MAX_PERSISTENT_CHANNELS_CAP);
+
+    if let Err(e) = ctx.data().db.set_max_persistent_channels(guild_id, max_channels).await {
+        error!("Failed to save persistent channels cap: {}", e);
+        ctx.say(format_error("Failed to save the persistent channels cap!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    let message = if max_channels == 0 {
+        "Users may own any number of persistent channels (one per lobby).".to_string()
+    } else {
+        format!(
+            "Users may own at most {} persistent channel(s) across all lobbies.",
+            max_channels
+        )
+    };
+    ctx.say(format_success(&message)).await?;
+
+    info!("Set persistent channels cap to {} for guild {}", max_channels, guild_id);
+
+    Ok(())
+}
+
+/// Enable or disable offering to restore all archived channels when a user joins any lobby
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_restore_all_archived(
+    ctx: Context<'_>,
+    #[description = "DM users a picker to restore their archived channels from other lobbies"]
+    enabled: bool,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    if let Err(e) = ctx.data().db.set_restore_all_on_join(guild_id, enabled).await {
+        error!("Failed to save restore-all setting: {}", e);
+        ctx.say(format_error("Failed to save restore-all setting!")).await?;
+        return Ok(());
+    }
+
+    let message = if enabled {
+        "Users joining any lobby will be offered to restore their other archived channels."
+    } else {
+        "Users will only get back the archived channel of the lobby they join."
+    };
+    ctx.say(format_success(message)).await?;
+
+    info!("Set restore-all on join to {} for guild {}", enabled, guild_id);
+
+    Ok(())
+}
+
+/// Forbid words in the names users give to their temporary channels
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_channel_name_blacklist(
+    ctx: Context<'_>,
+    #[description = "Comma-separated words forbidden in channel names (leave empty to clear)"]
+    words: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let mut words: Vec<String> = words
+        .map(|w| split_and_trim(&w.to_lowercase(), ','))
+        .unwrap_or_default();
+    words.sort();
+    words.dedup();
+    if words.len() > MAX_CHANNEL_NAME_BLACKLIST_WORDS {
+        ctx.say(format_error(&format!(
+            "The blacklist cannot contain more than {} words!",
+            MAX_CHANNEL_NAME_BLACKLIST_WORDS
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx.data().db.set_channel_name_blacklist(guild_id, &words).await {
+        error!("Failed to save channel name blacklist: {}", e);
+        ctx.say(format_error("Failed to save channel name blacklist!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    let message = if words.is_empty() {
+        "Channel name blacklist cleared.".to_string()
+    } else {
+        format!(
+            "Channel names containing any of these words will be rejected: {}",
+            words.join(", ")
+        )
+    };
+    ctx.say(format_success(&message)).await?;
+
+    info!("Set channel name blacklist ({} words) for guild {}", words.len(), guild_id);
+
+    Ok(())
+}
+
+/// Fill the unset settings of this server's lobbies with defaults or the given values
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_GUILD")]
+pub async fn apply_lobby_defaults(
+    ctx: Context<'_>,
+    #[description = "User limit for lobbies without one (0 or empty to keep unlimited, max 99)"]
+    user_limit: Option<u32>,
+    #[description = "Name template for lobbies without one (default: {user}'s Channel)"]
+    name_template: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let name_template = name_template.unwrap_or_else(|| DEFAULT_NAME_TEMPLATE.to_string());
+    if !is_valid_name_template(&name_template, MAX_CHANNEL_NAME_LENGTH as usize) {
+        ctx.say(format_error(&format!(
+            "The name template must not be empty and at most {} characters long!",
+            MAX_CHANNEL_NAME_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    let updated = match ctx
+        .data()
+        .db
+        .apply_lobby_defaults(guild_id, user_limit.and_then(normalize_user_limit), &name_template)
+        .await
+    {
+        Ok(updated) => updated,
+        Err(e) => {
+            error!("Failed to apply lobby defaults in guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to apply lobby defaults!")).await?;
+            return Ok(());
+        }
+    };
+
+    let count = updated.len();
+    for (channel_id, lobby) in updated {
+        ctx.data().lobby_channels.insert(channel_id, lobby);
+    }
+
+    if count == 0 {
+        ctx.say(format_info("Every lobby in this server already has its settings."))
+            .await?;
+    } else {
+        ctx.say(format_success(&format!("Applied defaults to {} lobbies.", count)))
+            .await?;
+    }
+
+    info!("Applied defaults to {} lobbies in guild {}", count, guild_id);
+
+    Ok(())
+}
+
+/// Get the caller's archived channels in a guild along with their names
+fn archived_channels_with_names(ctx: Context<'_>, guild_id: GuildId) -> Vec<(ChannelId, String)> {
+    let archived: Vec<ChannelId> = ctx
+        .data()
+        .owned_channels(guild_id, ctx.author().id)
+        .into_iter()
+        .filter(|(_, tc)| tc.is_archived)
+        .map(|(channel_id, _)| channel_id)
+        .collect();
+
+    let guild = ctx.guild();
+    archived
+        .into_iter()
+        .map(|channel_id| {
+            let name = guild
+                .as_ref()
+                .and_then(|g| g.channels.get(&channel_id).map(|c| c.name.clone()))
+                .unwrap_or_else(|| channel_id.to_string());
+            (channel_id, name)
+        })
+        .collect()
+}
+
+/// Autocomplete the caller's archived channels
+async fn autocomplete_archived_channel(
+    ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Vec::new();
+    };
+    let partial = partial.to_lowercase();
+
+    archived_channels_with_names(ctx, guild_id)
+        .into_iter()
+        .filter(|(_, name)| name.to_lowercase().contains(&partial))
+        .take(25)
+        .map(|(channel_id, name)| serenity::AutocompleteChoice::new(name, channel_id.to_string()))
+        .collect()
+}
+
+/// Resolve the archived channel picked by the caller, the only one when none is picked.
+/// Replies and returns None when there is no such channel or the choice is ambiguous.
+async fn select_archived_channel(
+    ctx: Context<'_>,
+    guild_id: GuildId,
+    channel: Option<String>,
+) -> Result<Option<ChannelId>, Error> {
+    let archived = archived_channels_with_names(ctx, guild_id);
+    if archived.is_empty() {
+        ctx.say(format_info("You don't have any archived channels in this server."))
+            .await?;
+        return Ok(None);
+    }
+
+    match channel {
+        Some(value) => {
+            let selected = value.trim().parse::<u64>().ok().and_then(|raw| {
+                archived
+                    .iter()
+                    .find(|(channel_id, _)| channel_id.get() == raw)
+                    .map(|(channel_id, _)| *channel_id)
+            });
+            if selected.is_none() {
+                ctx.say(format_error("That channel is not one of your archived channels!"))
+                    .await?;
+            }
+            Ok(selected)
+        }
+        None if archived.len() == 1 => Ok(Some(archived[0].0)),
+        None => {
+            let list = archived
+                .iter()
+                .map(|(_, name)| format!("• {}", name))
+                .collect::<Vec<_>>()
+                .join("\n");
+            ctx.say(format!(
+                "{}\n{}",
+                format_info("You have several archived channels, pick one with the `channel` option:"),
+                list
+            ))
+            .await?;
+            Ok(None)
+        }
+    }
+}
+
+/// Restore one of your archived channels without rejoining a lobby
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn restore_channel(
+    ctx: Context<'_>,
+    #[description = "The archived channel to restore"]
+    #[autocomplete = "autocomplete_archived_channel"]
+    channel: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let Some(channel_id) = select_archived_channel(ctx, guild_id, channel).await? else {
+        return Ok(());
+    };
+
+    let member = ctx
+        .author_member()
+        .await
+        .ok_or_else(|| build_context_error("in a server"))?
+        .into_owned();
+
+    // Only move the owner into the channel if they are already connected to voice
+    let in_voice = ctx
+        .guild()
+        .and_then(|g| g.voice_states.get(&member.user.id).and_then(|vs| vs.channel_id))
+        .is_some();
+
+    ctx.defer_ephemeral().await?;
+
+    if let Err(e) = restore_archived_channel(
+        ctx.serenity_context(),
+        &member,
+        guild_id,
+        channel_id,
+        in_voice,
+        ctx.data(),
+    )
+    .await
+    {
+        error!("Failed to restore archived channel {}: {}", channel_id, e);
+        ctx.say(format_error("Failed to restore your channel!")).await?;
+        return Ok(());
+    }
+
+    ctx.say(format_success(&format!("Your channel <#{}> has been restored!", channel_id)))
+        .await?;
+
+    Ok(())
+}
+
+/// Permanently delete one of your archived channels
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn delete_archived_channel(
+    ctx: Context<'_>,
+    #[description = "The archived channel to delete"]
+    #[autocomplete = "autocomplete_archived_channel"]
+    channel: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let Some(channel_id) = select_archived_channel(ctx, guild_id, channel).await? else {
+        return Ok(());
+    };
+
+    // A channel deleted by hand on Discord only leaves a stale row to clean up
+    if let Err(e) = channel_id.delete(ctx.http()).await {
+        ctx.data().record_http_error(&e);
+        if classify_http_error(&e) != HttpErrorKind::NotFound {
+            error!("Failed to delete archived channel {}: {}", channel_id, e);
+            ctx.say(format_error("Failed to delete your channel!")).await?;
+            return Ok(());
+        }
+        info!("Archived channel {} was already deleted on Discord", channel_id);
+    }
+
+    ctx.data().temp_channels.remove(&channel_id);
+    if let Err(e) = ctx.data().db.remove_temp_channel(channel_id).await {
+        error!("Failed to remove archived channel from database: {}", e);
+    }
+
+    ctx.say(format_success("Your archived channel has been deleted.")).await?;
+
+    info!(
+        "User {} deleted archived channel {} in guild {}",
+        ctx.author().id,
+        channel_id,
+        guild_id
+    );
+
+    Ok(())
+}
+
+/// Delete every temporary channel of this server (emergency cleanup)
+#[poise::command(slash_command, required_permissions = "MANAGE_CHANNELS", ephemeral)]
+pub async fn wipe_temp_channels(
+    ctx: Context<'_>,
+    #[description = "Set to true to confirm deleting all temporary channels"] confirm: bool,
+    #[description = "Also delete persistent and archived channels (default: false)"]
+    include_persistent: Option<bool>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    if !confirm {
+        ctx.say(format_info(
+            "Nothing was deleted. Run the command again with `confirm: true` to wipe all temporary channels.",
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let include_persistent = include_persistent.unwrap_or(false);
+    let targets: Vec<(ChannelId, serenity::UserId)> = ctx
+        .data()
+        .temp_channels
+        .iter()
+        .filter(|entry| entry.guild_id == guild_id)
+        .filter(|entry| include_persistent || !(entry.is_persistent || entry.is_archived))
+        .map(|entry| (*entry.key(), entry.owner_id))
+        .collect();
+
+    if targets.is_empty() {
+        ctx.say(format_info("There are no temporary channels to delete."))
+            .await?;
+        return Ok(());
+    }
+
+    ctx.defer_ephemeral().await?;
+
+    let mut deleted = 0;
+    for (index, (channel_id, owner_id)) in targets.iter().enumerate() {
+        // Pace the deletions to stay clear of Discord's rate limits
+        if index > 0 {
+            let delay = BULK_DELETE_DELAY_MS + ctx.data().rng.jitter_ms(BULK_DELETE_JITTER_MS);
+            tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+        }
+        if delete_temp_channel(ctx.serenity_context(), *channel_id, *owner_id, ctx.data()).await {
+            deleted += 1;
+        }
+    }
+
+    let failed = targets.len() - deleted;
+    let message = if failed == 0 {
+        format_success(&format!("Deleted {} temporary channel(s).", deleted))
+    } else {
+        format_error(&format!(
+            "Deleted {} temporary channel(s), {} could not be deleted.",
+            deleted, failed
+        ))
+    };
+    ctx.say(message).await?;
+
+    info!(
+        "Wiped {} temp channel(s) in guild {} (persistent included: {})",
+        deleted, guild_id, include_persistent
+    );
+
+    Ok(())
+}
+
+/// List your persistent channels in this server and whether they are archived
+#[poise::command(slash_command, guild_only, ephemeral)]
+pub async fn my_channels(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let channels = match ctx
+        .data()
+        .db
+        .get_persistent_channels_for_user(guild_id, ctx.author().id)
+        .await
+    {
+        Ok(channels) => channels,
+        Err(e) => {
+            error!("Failed to get persistent channels of {}: {}", ctx.author().id, e);
+            ctx.say(format_error("Failed to load your channels!")).await?;
+            return Ok(());
+        }
+    };
+
+    if channels.is_empty() {
+        ctx.say(format_info(
+            "You have no persistent channel in this server. Use **Make Persistent** in your temporary channel to keep it.",
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let existing = match guild_id.channels(ctx.http()).await {
+        Ok(channels) => channels,
+        Err(e) => {
+            ctx.data().record_http_error(&e);
+            error!("Failed to fetch channels of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to fetch the server channels!")).await?;
+            return Ok(());
+        }
+    };
+
+    let lines: Vec<String> = channels
+        .iter()
+        .map(|(channel_id, lobby_channel_id, is_archived)| {
+            let lobby = existing
+                .get(lobby_channel_id)
+                .map(|lobby| lobby.name.clone())
+                .unwrap_or_else(|| "deleted lobby".to_string());
+            let status = if !existing.contains_key(channel_id) {
+                "⚠️ deleted on Discord"
+            } else if *is_archived {
+                "📦 archived"
+            } else {
+                "🔊 active"
+            };
+            format!("• <#{}> from **{}**: {}", channel_id, lobby, status)
+        })
+        .collect();
+
+    ctx.say(format!("📌 **Your persistent channels**\n{}", lines.join("\n")))
+        .await?;
+
+    Ok(())
+}
+
+/// Time window for the lobby activity report
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ActivityPeriod {
+    #[name = "Last day"]
+    Day,
+    #[name = "Last week"]
+    Week,
+}
+
+impl ActivityPeriod {
+    fn hours(self) -> i32 {
+        match self {
+            ActivityPeriod::Day => 24,
+            ActivityPeriod::Week => 24 * 7,
+        }
+    }
+}
+
+/// Show how many temporary channels were active over time in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD", ephemeral)]
+pub async fn lobby_activity(
+    ctx: Context<'_>,
+    #[description = "Time window of the report (default: last day)"] period: Option<ActivityPeriod>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let period = period.unwrap_or(ActivityPeriod::Day);
+
+    let (min, max, avg, samples) = match ctx
+        .data()
+        .db
+        .get_lobby_activity_summary(guild_id, period.hours())
+        .await
+    {
+        Ok(summary) => summary,
+        Err(e) => {
+            error!("Failed to get lobby activity for guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load lobby activity!")).await?;
+            return Ok(());
+        }
+    };
+
+    let (Some(min), Some(max), Some(avg)) = (min, max, avg) else {
+        ctx.say(format_info(
+            "No activity has been recorded yet. Samples are taken every few minutes once a lobby exists.",
+        ))
+        .await?;
+        return Ok(());
+    };
+
+    ctx.say(format!(
+        "📈 **Lobby Activity ({})**\n\n\
+        Peak concurrent channels: **{}**\n\
+        Lowest: **{}**\n\
+        Average: **{:.1}**\n\
+        Samples: {}",
+        poise::ChoiceParameter::name(&period),
+        max,
+        min,
+        avg,
+        samples
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// List the lobby channels configured in this server
+#[poise::command(slash_command, guild_only, required_permissions = "MANAGE_CHANNELS")]
+pub async fn list_lobbies(ctx: Context<'_>) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let data = ctx.data();
+
+    let in_memory: BTreeSet<ChannelId> = data
+        .lobby_channels
+        .iter()
+        .filter(|entry| entry.guild_id == guild_id)
+        .map(|entry| *entry.key())
+        .collect();
+
+    // Cross-check with the database, which is the source of truth on restart
+    let stored: BTreeSet<ChannelId> = match data.db.get_all_lobby_channels().await {
+        Ok(lobbies) => lobbies
+            .into_iter()
+            .filter(|(_, lobby_guild_id)| *lobby_guild_id == guild_id)
+            .map(|(channel_id, _)| channel_id)
+            .collect(),
+        Err(e) => {
+            error!("Failed to load lobby channels for guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to load lobby channels!")).await?;
+            return Ok(());
+        }
+    };
+
+    let lobbies: BTreeSet<ChannelId> = in_memory.union(&stored).copied().collect();
+    if lobbies.is_empty() {
+        ctx.say(format_info(
+            "No lobby is configured in this server. Use `/create_lobby` or `/convert_to_lobby` to add one.",
+        ))
+        .await?;
+        return Ok(());
+    }
+
+    let existing = match guild_id.channels(ctx.http()).await {
+        Ok(channels) => channels,
+        Err(e) => {
+            ctx.data().record_http_error(&e);
+            error!("Failed to fetch channels of guild {}: {}", guild_id, e);
+            ctx.say(format_error("Failed to fetch the server channels!")).await?;
+            return Ok(());
+        }
+    };
+
+    let lines = lobbies
+        .iter()
+        .map(|channel_id| {
+            let mut line = format!("• <#{}>", channel_id);
+            if !existing.contains_key(channel_id) {
+                line.push_str(" ⚠️ deleted on Discord");
+            } else if !in_memory.contains(channel_id) {
+                line.push_str(" ⚠️ not loaded, restart the bot to activate it");
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    ctx.say(format!("🎙️ **Lobbies ({})**\n\n{}", lobbies.len(), lines))
+        .await?;
+
+    Ok(())
+}
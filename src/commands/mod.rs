@@ -1,9 +1,123 @@
 // Command modules
+mod admin;
+#[cfg(feature = "lobby")]
 mod lobby;
+#[cfg(feature = "birthday")]
 mod birthday;
+// Announcements run on the schedule manager, which comes with the birthday feature
+#[cfg(feature = "birthday")]
+mod announcements;
+mod feedback;
+mod settings;
 mod timezone;
 
+use crate::models::{Data, Error};
+
 // Re-export all commands
-pub use lobby::{create_lobby, convert_to_lobby};
-pub use birthday::{setup_birthday, disable_birthday};
-pub use timezone::setup_timezone;
+pub use admin::{command_usage, maintenance, set_log_level, stats};
+#[cfg(feature = "birthday")]
+pub use admin::{list_all_schedules, schedule_status, selftest};
+#[cfg(feature = "lobby")]
+pub use lobby::{
+    apply_lobby_defaults, channel_info, claim_channel, whois_owner, create_lobby, convert_to_lobby, delete_archived_channel,
+    my_channels, list_lobbies, lobby_activity, remove_lobby, resend_config,
+    restore_channel, set_archive_dm, set_empty_channel_grace, transfer_channel,
+    ban_from_channel, channel_bans, kick_from_channel, unban_from_channel, set_archive_category_name, set_channel_name_blacklist, set_max_persistent_channels, set_restore_all_archived, wipe_temp_channels,
+};
+#[cfg(feature = "birthday")]
+pub use birthday::{
+    birthday_remove, birthday_test, birthday_this_week, birthday_view, set_birthday, add_schedule, list_schedules, remove_schedule, run_schedule_now, set_user_birthday, upcoming_birthdays, birthday_stats, setup_birthday, disable_birthday, import_birthdays, export_birthdays,
+    set_birthday_announcement_retention, set_birthday_dm_greeting, set_birthday_ping, set_birthday_age_format, set_birthday_reminder, set_birthday_silent, set_birthday_wishes_thread,
+    validate_birthday_templates, set_birthday_dm_fallback,
+    set_birthday_mirror_channels, set_birthday_separate_messages, set_birthday_weekend_channel, set_milestone_role,
+};
+#[cfg(feature = "birthday")]
+pub use announcements::{add_announcement, remove_announcement};
+pub use feedback::feedback;
+pub use settings::set_message_branding;
+pub use timezone::{my_timezone, setup_timezone, set_timezone_from_offset, time};
+
+/// Build the list of commands to register, depending on the enabled features
+pub fn all_commands() -> Vec<poise::Command<Data, Error>> {
+    let mut commands = vec![
+        setup_timezone(),
+        set_timezone_from_offset(),
+        my_timezone(),
+        time(),
+        set_message_branding(),
+        feedback(),
+        stats(),
+        command_usage(),
+        maintenance(),
+        set_log_level(),
+    ];
+
+    #[cfg(feature = "lobby")]
+    commands.extend([
+        create_lobby(),
+        convert_to_lobby(),
+        remove_lobby(),
+        apply_lobby_defaults(),
+        transfer_channel(),
+        claim_channel(),
+        kick_from_channel(),
+        ban_from_channel(),
+        unban_from_channel(),
+        channel_bans(),
+        channel_info(),
+        whois_owner(),
+        my_channels(),
+        set_archive_dm(),
+        set_empty_channel_grace(),
+        set_restore_all_archived(),
+        set_channel_name_blacklist(),
+        set_archive_category_name(),
+        set_max_persistent_channels(),
+        restore_channel(),
+        delete_archived_channel(),
+        resend_config(),
+        wipe_temp_channels(),
+        lobby_activity(),
+        list_lobbies(),
+    ]);
+
+    #[cfg(feature = "birthday")]
+    commands.extend([
+        setup_birthday(),
+        disable_birthday(),
+        set_milestone_role(),
+        set_birthday_separate_messages(),
+        set_birthday_weekend_channel(),
+        set_birthday_mirror_channels(),
+        set_birthday_dm_fallback(),
+        set_birthday_announcement_retention(),
+        set_birthday_reminder(),
+        set_birthday_silent(),
+        set_birthday_dm_greeting(),
+        set_birthday_ping(),
+        set_birthday_age_format(),
+        set_birthday_wishes_thread(),
+        validate_birthday_templates(),
+        birthday_test(),
+        import_birthdays(),
+        export_birthdays(),
+        birthday_this_week(),
+        upcoming_birthdays(),
+        birthday_stats(),
+        birthday_view(),
+        set_birthday(),
+        birthday_remove(),
+        set_user_birthday(),
+        add_schedule(),
+        remove_schedule(),
+        list_schedules(),
+        run_schedule_now(),
+        add_announcement(),
+        remove_announcement(),
+        schedule_status(),
+        list_all_schedules(),
+        selftest(),
+    ]);
+
+    commands
+}
@@ -0,0 +1,66 @@
+use tracing::{error, info};
+
+use crate::{
+    models::{Context, Error},
+    utils::messages::{decorate_message, format_error, format_success},
+    utils::validation::require_guild,
+};
+
+/// Maximum length of a branding prefix or suffix
+const MAX_BRANDING_LENGTH: usize = 200;
+
+/// Set a branding line added before and/or after the bot's messages in this server
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_message_branding(
+    ctx: Context<'_>,
+    #[description = "Line added before every bot message (leave empty to remove)"]
+    prefix: Option<String>,
+    #[description = "Line added after every bot message (leave empty to remove)"]
+    suffix: Option<String>,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let prefix = prefix.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    let suffix = suffix.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+
+    let too_long = [&prefix, &suffix]
+        .iter()
+        .any(|text| text.as_ref().is_some_and(|t| t.chars().count() > MAX_BRANDING_LENGTH));
+    if too_long {
+        ctx.say(format_error(&format!(
+            "Branding lines cannot exceed {} characters!",
+            MAX_BRANDING_LENGTH
+        )))
+        .await?;
+        return Ok(());
+    }
+
+    if let Err(e) = ctx
+        .data()
+        .db
+        .set_message_branding(guild_id, prefix.clone(), suffix.clone())
+        .await
+    {
+        error!("Failed to save message branding: {}", e);
+        ctx.say(format_error("Failed to save message branding!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    let message = if prefix.is_none() && suffix.is_none() {
+        format_success("Message branding removed.")
+    } else {
+        // Show a preview of what decorated messages look like
+        let settings = ctx.data().guild_settings_or_default(guild_id).await;
+        format!(
+            "{}\n\n{}",
+            format_success("Message branding saved! Preview:"),
+            decorate_message(&settings, "*Example bot message*")
+        )
+    };
+    ctx.say(message).await?;
+
+    info!("Updated message branding for guild {}", guild_id);
+
+    Ok(())
+}
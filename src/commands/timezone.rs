@@ -1,17 +1,46 @@
+use poise::serenity_prelude as serenity;
 use tracing::{error, info};
 
 use crate::{
     models::{Context, Error},
-    utils::timezone::parse_timezone,
-    utils::messages::{format_error, format_success},
+    utils::timezone::{
+        format_local_time, offset_to_timezone, parse_timezone, parse_utc_offset, suggest_timezones,
+    },
+    utils::messages::{format_error, format_info, format_success, format_warning},
     utils::validation::require_guild,
 };
 
+/// Build the error shown for an unknown timezone, with examples of valid IANA names
+fn build_invalid_timezone_message(timezone: &str) -> String {
+    format!(
+        "{}\nPlease use a valid IANA timezone name like:\n\
+        • Europe/Paris\n\
+        • America/New_York\n\
+        • Asia/Tokyo\n\
+        • UTC\n\
+        \n\
+        You can find a full list at: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones",
+        format_error(&format!("Invalid timezone: '{}'", timezone))
+    )
+}
+
+/// Autocomplete IANA timezone names matching what was typed so far
+async fn autocomplete_timezone(
+    _ctx: Context<'_>,
+    partial: &str,
+) -> Vec<serenity::AutocompleteChoice> {
+    suggest_timezones(partial, 25)
+        .into_iter()
+        .map(|name| serenity::AutocompleteChoice::new(name, name))
+        .collect()
+}
+
 /// Set the timezone for this server
 #[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
 pub async fn setup_timezone(
     ctx: Context<'_>,
     #[description = "Timezone (e.g., Europe/Paris, America/New_York, Asia/Tokyo)"]
+    #[autocomplete = "autocomplete_timezone"]
     timezone: String,
 ) -> Result<(), Error> {
     let guild_id = require_guild(ctx.guild_id())?;
@@ -20,17 +49,7 @@ pub async fn setup_timezone(
     let tz = match parse_timezone(&timezone) {
         Ok(tz) => tz,
         Err(_) => {
-            ctx.say(format!(
-                "{}\nPlease use a valid IANA timezone name like:\n\
-                • Europe/Paris\n\
-                • America/New_York\n\
-                • Asia/Tokyo\n\
-                • UTC\n\
-                \n\
-                You can find a full list at: https://en.wikipedia.org/wiki/List_of_tz_database_time_zones",
-                format_error(&format!("Invalid timezone: '{}'", timezone))
-            ))
-            .await?;
+            ctx.say(build_invalid_timezone_message(&timezone)).await?;
             return Ok(());
         }
     };
@@ -46,6 +65,7 @@ pub async fn setup_timezone(
         ctx.say(format_error("Failed to save timezone setting!")).await?;
         return Ok(());
     }
+    ctx.data().invalidate_guild_settings(guild_id);
 
     // Show current time in the selected timezone
     let now = chrono::Utc::now().with_timezone(&tz);
@@ -63,3 +83,189 @@ pub async fn setup_timezone(
 
     Ok(())
 }
+
+/// Set the timezone for this server from a UTC offset (e.g., +02:00)
+#[poise::command(slash_command, required_permissions = "MANAGE_GUILD")]
+pub async fn set_timezone_from_offset(
+    ctx: Context<'_>,
+    #[description = "UTC offset (e.g., +02:00, -0500, +5:30)"]
+    offset: String,
+) -> Result<(), Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+
+    let timezone = match offset_to_timezone(&offset) {
+        Some(tz) => tz,
+        None if parse_utc_offset(&offset).is_some() => {
+            ctx.say(format!(
+                "{}\nThe regions using this offset observe daylight saving time. \
+                Use `/setup_timezone` with the IANA name of your region instead (e.g., America/St_Johns).",
+                format_error(&format!("No fixed timezone for the offset '{}'", offset))
+            ))
+            .await?;
+            return Ok(());
+        }
+        None => {
+            ctx.say(format!(
+                "{}\nPlease use an offset like `+02:00`, `-0500` or `+5:30` \
+                (between -12:00 and +14:00).",
+                format_error(&format!("Invalid UTC offset: '{}'", offset))
+            ))
+            .await?;
+            return Ok(());
+        }
+    };
+
+    let tz = parse_timezone(&timezone)?;
+
+    // Save timezone to database
+    if let Err(e) = ctx
+        .data()
+        .db
+        .set_guild_timezone(guild_id, timezone.clone())
+        .await
+    {
+        error!("Failed to save guild timezone: {}", e);
+        ctx.say(format_error("Failed to save timezone setting!")).await?;
+        return Ok(());
+    }
+    ctx.data().invalidate_guild_settings(guild_id);
+
+    let now = chrono::Utc::now().with_timezone(&tz);
+
+    ctx.say(format!(
+        "{}\nTimezone: **{}** (from offset `{}`)\nCurrent time: **{}**\n\n{}",
+        format_success("Server timezone configured!"),
+        timezone,
+        offset,
+        now.format("%Y-%m-%d %H:%M:%S %Z"),
+        format_warning(
+            "Fixed offsets don't follow daylight saving time. \
+            If your region observes DST, use `/setup_timezone` with an IANA name instead."
+        )
+    ))
+    .await?;
+
+    info!(
+        "Set timezone for guild {} to {} (from offset {})",
+        guild_id, timezone, offset
+    );
+
+    Ok(())
+}
+
+/// Set your personal timezone, used to show times to you (omit it to see the current one)
+#[poise::command(slash_command, ephemeral)]
+pub async fn my_timezone(
+    ctx: Context<'_>,
+    #[description = "Timezone (e.g., Europe/Paris, America/New_York, Asia/Tokyo)"]
+    #[autocomplete = "autocomplete_timezone"]
+    timezone: Option<String>,
+) -> Result<(), Error> {
+    let user_id = ctx.author().id;
+
+    let Some(timezone) = timezone else {
+        let message = match ctx.data().db.get_user_timezone(user_id).await {
+            Ok(Some(timezone)) => format_info(&format!(
+                "Your timezone is **{}**. Use `/my_timezone` with a timezone to change it.",
+                timezone
+            )),
+            Ok(None) => format_info(
+                "You haven't set a timezone yet. Use `/my_timezone` with a timezone like Europe/Paris.",
+            ),
+            Err(e) => {
+                error!("Failed to get user timezone: {}", e);
+                format_error("Failed to load your timezone!")
+            }
+        };
+        ctx.say(message).await?;
+        return Ok(());
+    };
+
+    let timezone = timezone.trim();
+    let tz = match parse_timezone(timezone) {
+        Ok(tz) => tz,
+        Err(_) => {
+            ctx.say(build_invalid_timezone_message(timezone)).await?;
+            return Ok(());
+        }
+    };
+
+    if let Err(e) = ctx.data().db.set_user_timezone(user_id, Some(timezone)).await {
+        error!("Failed to save user timezone: {}", e);
+        ctx.say(format_error("Failed to save your timezone!")).await?;
+        return Ok(());
+    }
+
+    let now = chrono::Utc::now().with_timezone(&tz);
+
+    ctx.say(format!(
+        "{}\nTimezone: **{}**\nCurrent time: **{}**",
+        format_success("Your timezone is saved!"),
+        timezone,
+        now.format("%Y-%m-%d %H:%M:%S %Z")
+    ))
+    .await?;
+
+    info!("Set timezone for user {} to {}", user_id, timezone);
+
+    Ok(())
+}
+
+/// Show the current time in this server's timezone, or in another one
+#[poise::command(slash_command, ephemeral)]
+pub async fn time(
+    ctx: Context<'_>,
+    #[description = "Timezone to use instead of the server's (e.g., Asia/Tokyo)"]
+    #[autocomplete = "autocomplete_timezone"]
+    timezone: Option<String>,
+) -> Result<(), Error> {
+    let now = chrono::Utc::now();
+
+    if let Some(timezone) = timezone {
+        let timezone = timezone.trim();
+        let message = match parse_timezone(timezone) {
+            Ok(tz) => format!("🕒 **{}** in {}", format_local_time(now, &tz), timezone),
+            Err(_) => build_invalid_timezone_message(timezone),
+        };
+        ctx.say(message).await?;
+        return Ok(());
+    }
+
+    // Outside of a server, fall back to the caller's own timezone
+    let (timezone, source) = match ctx.guild_id() {
+        Some(guild_id) => (
+            ctx.data().guild_settings_or_default(guild_id).await.timezone,
+            "server",
+        ),
+        None => match ctx.data().db.get_user_timezone(ctx.author().id).await {
+            Ok(Some(timezone)) => (timezone, "your"),
+            Ok(None) => ("UTC".to_string(), "default"),
+            Err(e) => {
+                error!("Failed to get user timezone: {}", e);
+                ("UTC".to_string(), "default")
+            }
+        },
+    };
+
+    let message = match parse_timezone(&timezone) {
+        Ok(tz) => format!(
+            "🕒 **{}** ({} timezone: {})",
+            format_local_time(now, &tz),
+            source,
+            timezone
+        ),
+        // The stored timezone is no longer known to chrono_tz
+        Err(_) => format!(
+            "🕒 **{}** (UTC)\n{}",
+            format_local_time(now, &chrono_tz::UTC),
+            format_warning(&format!(
+                "The stored timezone '{}' is not valid anymore. \
+                An admin can fix it with `/setup_timezone`.",
+                timezone
+            ))
+        ),
+    };
+    ctx.say(message).await?;
+
+    Ok(())
+}
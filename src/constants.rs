@@ -1,14 +1,171 @@
 /// Default name for lobby channels
+#[cfg(feature = "lobby")]
 pub const DEFAULT_LOBBY_NAME: &str = "➕ Create Voice Channel";
 
+/// Name template applied to lobbies without one by `/apply_lobby_defaults`
+#[cfg(feature = "lobby")]
+pub const DEFAULT_NAME_TEMPLATE: &str = "{user}'s Channel";
+
 /// Archive category name
+#[cfg(feature = "lobby")]
 pub const ARCHIVE_CATEGORY_NAME: &str = "📦 Archived Channels";
 
 /// Maximum number of messages to scan when cleaning up old bot messages
+#[cfg(feature = "lobby")]
 pub const MAX_MESSAGE_SCAN: u8 = 50;
 
+/// Delay between channel deletions when wiping temp channels in bulk (milliseconds)
+#[cfg(feature = "lobby")]
+pub const BULK_DELETE_DELAY_MS: u64 = 500;
+
+/// Maximum random jitter added to the bulk deletion delay (milliseconds)
+#[cfg(feature = "lobby")]
+pub const BULK_DELETE_JITTER_MS: u64 = 250;
+
+/// Number of members requested per page when fetching a guild's member list (Discord's maximum)
+#[cfg(feature = "birthday")]
+pub const MEMBERS_PAGE_SIZE: u64 = 1000;
+
+/// Default delay after each birthday role change, to spread role updates of large guilds
+/// (milliseconds, overridden by `ROLE_UPDATE_DELAY_MS`)
+pub const ROLE_UPDATE_DELAY_MS: u64 = 250;
+
+/// Time an owner must have left their temp channel before a member can claim it (seconds)
+#[cfg(feature = "lobby")]
+pub const CLAIM_GRACE_PERIOD_SECS: u64 = 60;
+
+/// Time a member kicked from a temp channel can't join it again (seconds)
+#[cfg(feature = "lobby")]
+pub const KICK_COOLDOWN_SECS: u64 = 300;
+
+/// Time before a user joining a lobby is offered to restore their archived channels again (seconds)
+#[cfg(feature = "lobby")]
+pub const RESTORE_OFFER_COOLDOWN_SECS: u64 = 86400;
+
+/// Maximum delay before an empty temp channel is deleted or archived (seconds)
+#[cfg(feature = "lobby")]
+pub const MAX_EMPTY_CHANNEL_GRACE_SECS: u32 = 600;
+
+/// Interval between two lobby activity samples (seconds)
+#[cfg(feature = "lobby")]
+pub const LOBBY_ACTIVITY_SAMPLE_INTERVAL_SECS: u64 = 300;
+
+/// Number of days lobby activity samples are kept for
+#[cfg(feature = "lobby")]
+pub const LOBBY_ACTIVITY_RETENTION_DAYS: i32 = 30;
+
+/// Interval between two flushes of command usage counts to the database (seconds)
+pub const COMMAND_USAGE_FLUSH_INTERVAL_SECS: u64 = 60;
+
 /// Maximum length for channel names
 pub const MAX_CHANNEL_NAME_LENGTH: u16 = 100;
 
+/// Lowest bitrate of a voice channel (bps)
+#[cfg(feature = "lobby")]
+pub const MIN_VOICE_BITRATE: u32 = 8_000;
+
+/// Highest configurable cap on persistent channels per user
+#[cfg(feature = "lobby")]
+pub const MAX_PERSISTENT_CHANNELS_CAP: u32 = 25;
+
+/// Maximum number of words in a guild's channel name blacklist
+#[cfg(feature = "lobby")]
+pub const MAX_CHANNEL_NAME_BLACKLIST_WORDS: usize = 50;
+
+/// Maximum user limit Discord accepts on a voice channel
+#[cfg(feature = "lobby")]
+pub const MAX_VOICE_USER_LIMIT: u32 = 99;
+
+/// Maximum length for channel topics
+#[cfg(feature = "lobby")]
+pub const MAX_CHANNEL_TOPIC_LENGTH: u16 = 1024;
+
+/// Retries of a database operation failing with a transient error
+#[cfg(feature = "birthday")]
+pub const DB_RETRY_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry of a failed database operation, doubled on every retry (milliseconds)
+#[cfg(feature = "birthday")]
+pub const DB_RETRY_BASE_DELAY_MS: u64 = 500;
+
+/// Longest delay between two retries of a failed database operation (milliseconds)
+#[cfg(feature = "birthday")]
+pub const DB_RETRY_MAX_DELAY_MS: u64 = 10_000;
+
+/// Maximum length of a scheduled announcement, leaving room for the guild branding
+#[cfg(feature = "birthday")]
+pub const MAX_ANNOUNCEMENT_LENGTH: usize = 1800;
+
+/// Delay before restarting the schedule manager after a panic (seconds)
+#[cfg(feature = "birthday")]
+pub const SCHEDULE_MANAGER_RESTART_DELAY_SECS: u64 = 5;
+
+/// Time given to the background tasks to stop on shutdown before the database is closed (seconds)
+pub const SHUTDOWN_TASKS_TIMEOUT_SECS: u64 = 10;
+
+/// Number of schedules shown per page when listing schedules
+#[cfg(feature = "birthday")]
+pub const SCHEDULES_PER_PAGE: usize = 10;
+
+/// Label of the schedule created by the setup commands
+pub const DEFAULT_SCHEDULE_LABEL: &str = "default";
+
+/// Maximum length of a schedule label
+#[cfg(feature = "birthday")]
+pub const MAX_SCHEDULE_LABEL_LENGTH: usize = 32;
+
+/// Length above which a rendered birthday template is reported as suspicious (characters)
+#[cfg(feature = "birthday")]
+pub const MAX_TEMPLATE_RENDERED_LENGTH: usize = 1000;
+
+/// Maximum size of an uploaded birthday import file (bytes)
+#[cfg(feature = "birthday")]
+pub const MAX_IMPORT_FILE_SIZE: u32 = 1024 * 1024;
+
+/// Maximum number of rejected rows listed in an import report
+#[cfg(feature = "birthday")]
+pub const MAX_IMPORT_ERRORS_SHOWN: usize = 10;
+
+/// Maximum size of a birthday export file (bytes), Discord's attachment limit for unboosted servers
+#[cfg(feature = "birthday")]
+pub const MAX_EXPORT_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Age phrase of the default birthday entries, shown in parentheses after the user
+#[cfg(feature = "birthday")]
+pub const DEFAULT_AGE_TEMPLATE: &str = "turning {age}";
+
+/// Maximum length of a custom age phrase (characters)
+#[cfg(feature = "birthday")]
+pub const MAX_AGE_TEMPLATE_LENGTH: usize = 50;
+
+/// Separator between the variants of a custom birthday message, one being picked per user.
+/// A placeholder-like token, as `||` would clash with Discord's spoiler markup
+#[cfg(feature = "birthday")]
+pub const TEMPLATE_VARIANT_SEPARATOR: &str = "{or}";
+
+/// Colour of the bar of birthday announcement embeds
+#[cfg(feature = "birthday")]
+pub const BIRTHDAY_EMBED_COLOUR: u32 = 0xF47FFF;
+
+/// Maximum number of days in advance a birthday reminder can be posted
+#[cfg(feature = "birthday")]
+pub const MAX_BIRTHDAY_REMINDER_DAYS: u32 = 30;
+
+/// Cron expression of the birthday role updates: every hour, so each user gets the role
+/// when the date changes in their own timezone
+pub const BIRTHDAY_ROLE_UPDATE_CRON: &str = "0 0 * * * *";
+
+/// Default number of birthdays listed by /upcoming_birthdays
+#[cfg(feature = "birthday")]
+pub const DEFAULT_UPCOMING_BIRTHDAYS: u32 = 10;
+
+/// Maximum number of birthdays listed by /upcoming_birthdays, to stay within the message length
+#[cfg(feature = "birthday")]
+pub const MAX_UPCOMING_BIRTHDAYS: u32 = 25;
+
+/// Width in characters of the longest bar of the /birthday_stats chart
+#[cfg(feature = "birthday")]
+pub const BIRTHDAY_STATS_BAR_WIDTH: usize = 20;
+
 /// Log directive for the application
 pub const LOG_DIRECTIVE: &str = "shiibot_rs=info";
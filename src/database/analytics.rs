@@ -0,0 +1,41 @@
+use super::Database;
+use sqlx::Error as SqlxError;
+
+impl Database {
+    /// Add a batch of command invocation counts to the stored tallies
+    pub async fn add_command_usage(&self, counts: &[(String, u64)]) -> Result<(), SqlxError> {
+        if counts.is_empty() {
+            return Ok(());
+        }
+
+        let commands: Vec<&str> = counts.iter().map(|(command, _)| command.as_str()).collect();
+        let increments: Vec<i64> = counts.iter().map(|(_, count)| *count as i64).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO command_usage (command, count, updated_at)
+            SELECT command, count, NOW() FROM UNNEST($1::TEXT[], $2::BIGINT[]) AS batch(command, count)
+            ON CONFLICT (command)
+            DO UPDATE SET count = command_usage.count + EXCLUDED.count, updated_at = NOW()
+            "#,
+        )
+        .bind(commands)
+        .bind(increments)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Get the stored invocation count of every command
+    pub async fn get_command_usage(&self) -> Result<Vec<(String, u64)>, SqlxError> {
+        let rows: Vec<(String, i64)> =
+            sqlx::query_as("SELECT command, count FROM command_usage")
+                .fetch_all(self.pool())
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(command, count)| (command, count as u64))
+            .collect())
+    }
+}
@@ -1,7 +1,121 @@
 use super::Database;
 use poise::serenity_prelude::{ChannelId, GuildId, MessageId, RoleId, UserId};
+use chrono::NaiveDate;
 use sqlx::Error as SqlxError;
 
+use crate::utils::channel_utils::{join_channel_id_list, parse_channel_id_list};
+
+/// Raw birthday row: user, month, day, year, timezone, whether the age is shown
+type RawBirthdayTimezoneRow = (i64, i32, i32, Option<i32>, Option<String>, bool);
+
+/// A stored birthday with the timezone it is celebrated in
+pub struct BirthdayWithTimezone {
+    pub user_id: UserId,
+    pub month: i32,
+    pub day: i32,
+    pub year: Option<i32>,
+    /// The user's own timezone, None when they didn't set one and the guild's timezone applies
+    pub timezone: Option<String>,
+    /// Whether the user lets their age be shown
+    pub show_age: bool,
+}
+
+/// Raw `birthday_channels` row, as read by `get_birthday_config`
+#[derive(sqlx::FromRow)]
+struct RawBirthdayConfigRow {
+    channel_id: i64,
+    custom_message: Option<String>,
+    custom_message_without_age: Option<String>,
+    custom_header: Option<String>,
+    custom_footer: Option<String>,
+    age_template: Option<String>,
+    separate_messages: bool,
+    weekend_channel_id: Option<i64>,
+    mirror_channel_ids: Option<String>,
+    dm_fallback: bool,
+    dm_notifications: bool,
+    announcement_retention_days: Option<i32>,
+    silent_announcements: bool,
+    wishes_thread: bool,
+    ping_birthday_users: bool,
+    use_embed: bool,
+    reminder_days: Option<i32>,
+}
+
+/// Birthday announcement configuration of a guild
+pub struct BirthdayConfig {
+    pub channel_id: ChannelId,
+    pub custom_message: Option<String>,
+    pub custom_message_without_age: Option<String>,
+    pub custom_header: Option<String>,
+    pub custom_footer: Option<String>,
+    /// Age phrase of the default announcement entries (None uses the default)
+    pub age_template: Option<String>,
+    /// Whether each birthday is announced in its own message
+    pub separate_messages: bool,
+    /// Channel birthdays are announced in on weekends, if any
+    pub weekend_channel_id: Option<ChannelId>,
+    /// Channels the announcement is copied to
+    pub mirror_channel_ids: Vec<ChannelId>,
+    /// Whether birthday users are DMed when the announcement channel can't be reached
+    pub dm_fallback: bool,
+    /// Whether birthday users get a private greeting in DMs besides the announcement
+    pub dm_notifications: bool,
+    /// After how many days announcements are deleted (None keeps them)
+    pub announcement_retention_days: Option<u32>,
+    /// Whether announcements are posted without notifying anyone
+    pub silent_announcements: bool,
+    /// Whether a thread for wishes is opened under announcements
+    pub wishes_thread: bool,
+    /// Whether birthday users are pinged by the announcement or only named
+    pub ping_users: bool,
+    /// Whether announcements are sent as embeds instead of plain text
+    pub use_embed: bool,
+    /// How many days in advance a birthday reminder is posted (None when disabled)
+    pub reminder_days: Option<u32>,
+}
+
+/// A single setting of the guild's birthday announcements, with its new value
+#[derive(Debug)]
+pub enum BirthdaySetting {
+    SeparateMessages(bool),
+    WeekendChannel(Option<ChannelId>),
+    /// Empty to stop mirroring
+    MirrorChannels(Vec<ChannelId>),
+    DmFallback(bool),
+    DmNotifications(bool),
+    /// None keeps announcements
+    AnnouncementRetention(Option<u32>),
+    SilentAnnouncements(bool),
+    WishesThread(bool),
+    PingUsers(bool),
+    UseEmbed(bool),
+    /// None disables the reminder
+    ReminderDays(Option<u32>),
+    /// None restores the default age phrase
+    AgeTemplate(Option<String>),
+}
+
+impl BirthdaySetting {
+    /// Column of `birthday_channels` holding the setting
+    fn column(&self) -> &'static str {
+        match self {
+            Self::SeparateMessages(_) => "separate_messages",
+            Self::WeekendChannel(_) => "weekend_channel_id",
+            Self::MirrorChannels(_) => "mirror_channel_ids",
+            Self::DmFallback(_) => "dm_fallback",
+            Self::DmNotifications(_) => "dm_notifications",
+            Self::AnnouncementRetention(_) => "announcement_retention_days",
+            Self::SilentAnnouncements(_) => "silent_announcements",
+            Self::WishesThread(_) => "wishes_thread",
+            Self::PingUsers(_) => "ping_birthday_users",
+            Self::UseEmbed(_) => "use_embed",
+            Self::ReminderDays(_) => "reminder_days",
+            Self::AgeTemplate(_) => "age_template",
+        }
+    }
+}
+
 impl Database {
     /// Save or update a user's birthday
     pub async fn upsert_birthday(
@@ -32,6 +146,65 @@ impl Database {
         Ok(())
     }
 
+    /// Set whether a user's age is shown in announcements
+    pub async fn set_birthday_show_age(&self, user_id: UserId, show_age: bool) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE user_birthdays SET show_age = $1 WHERE user_id = $2")
+            .bind(show_age)
+            .bind(user_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Save or update the birthdays of several users in a single statement. A missing year
+    /// keeps the stored one of users hiding their age, as exports leave it out.
+    pub async fn upsert_birthdays(
+        &self,
+        birthdays: &[(UserId, i32, i32, Option<i32>)],
+    ) -> Result<u64, SqlxError> {
+        if birthdays.is_empty() {
+            return Ok(0);
+        }
+
+        let user_ids: Vec<i64> = birthdays.iter().map(|(id, ..)| id.get() as i64).collect();
+        let months: Vec<i32> = birthdays.iter().map(|(_, month, ..)| *month).collect();
+        let days: Vec<i32> = birthdays.iter().map(|(_, _, day, _)| *day).collect();
+        let years: Vec<Option<i32>> = birthdays.iter().map(|(.., year)| *year).collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO user_birthdays (user_id, birth_month, birth_day, birth_year, updated_at)
+            SELECT user_id, birth_month, birth_day, birth_year, NOW()
+            FROM UNNEST($1::BIGINT[], $2::INT[], $3::INT[], $4::INT[])
+                AS batch(user_id, birth_month, birth_day, birth_year)
+            ON CONFLICT (user_id)
+            DO UPDATE SET
+                birth_month = EXCLUDED.birth_month,
+                birth_day = EXCLUDED.birth_day,
+                birth_year = CASE WHEN user_birthdays.show_age THEN EXCLUDED.birth_year
+                    ELSE COALESCE(EXCLUDED.birth_year, user_birthdays.birth_year) END,
+                updated_at = NOW()
+            "#,
+        )
+        .bind(user_ids)
+        .bind(months)
+        .bind(days)
+        .bind(years)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Delete a user's birthday, returning whether one was stored
+    pub async fn remove_birthday(&self, user_id: UserId) -> Result<bool, SqlxError> {
+        let result = sqlx::query("DELETE FROM user_birthdays WHERE user_id = $1")
+            .bind(user_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
     /// Get a user's birthday
     pub async fn get_birthday(
         &self,
@@ -47,14 +220,129 @@ impl Database {
         Ok(result)
     }
 
-    /// Get all users with birthdays on a specific date
+    /// Get the birthdays of several users at once (users without one are left out).
+    /// The birth year of users hiding their age is left out too.
+    pub async fn get_birthdays_for_users(
+        &self,
+        user_ids: &[UserId],
+    ) -> Result<Vec<(UserId, i32, i32, Option<i32>)>, SqlxError> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let ids: Vec<i64> = user_ids.iter().map(|id| id.get() as i64).collect();
+        let rows: Vec<(i64, i32, i32, Option<i32>)> = sqlx::query_as(
+            "SELECT user_id, birth_month, birth_day, CASE WHEN show_age THEN birth_year END \
+             FROM user_birthdays WHERE user_id = ANY($1)",
+        )
+        .bind(ids)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, month, day, year)| (UserId::new(user_id as u64), month, day, year))
+            .collect())
+    }
+
+    /// Count the birthdays of the given users per month (index 0 is January)
+    pub async fn count_birthdays_by_month(&self, user_ids: &[UserId]) -> Result<[u32; 12], SqlxError> {
+        let mut counts = [0u32; 12];
+        if user_ids.is_empty() {
+            return Ok(counts);
+        }
+
+        let ids: Vec<i64> = user_ids.iter().map(|id| id.get() as i64).collect();
+        let rows: Vec<(i32, i64)> = sqlx::query_as(
+            "SELECT birth_month, COUNT(*) FROM user_birthdays \
+             WHERE user_id = ANY($1) GROUP BY birth_month",
+        )
+        .bind(ids)
+        .fetch_all(self.pool())
+        .await?;
+
+        for (month, count) in rows {
+            if let Some(slot) = usize::try_from(month - 1).ok().and_then(|i| counts.get_mut(i)) {
+                *slot = count as u32;
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Get the birthday (month, day) of every user
+    pub async fn get_all_birthdays(&self) -> Result<Vec<(UserId, i32, i32)>, SqlxError> {
+        let rows: Vec<(i64, i32, i32)> =
+            sqlx::query_as("SELECT user_id, birth_month, birth_day FROM user_birthdays")
+                .fetch_all(self.pool())
+                .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, month, day)| (UserId::new(user_id as u64), month, day))
+            .collect())
+    }
+
+    /// Get all users with birthdays on any of the given (month, day) dates
+    pub async fn get_birthdays_on_dates(
+        &self,
+        dates: &[(i32, i32)],
+    ) -> Result<Vec<(UserId, i32, i32)>, SqlxError> {
+        let (months, days): (Vec<i32>, Vec<i32>) = dates.iter().copied().unzip();
+        let rows: Vec<(i64, i32, i32)> = sqlx::query_as(
+            "SELECT user_id, birth_month, birth_day FROM user_birthdays \
+             WHERE (birth_month, birth_day) IN (SELECT * FROM UNNEST($1::INTEGER[], $2::INTEGER[]))",
+        )
+        .bind(months)
+        .bind(days)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, month, day)| (UserId::new(user_id as u64), month, day))
+            .collect())
+    }
+
+    /// Get all users with birthdays on any of the given (month, day) dates, with their
+    /// birth year, timezone and whether they want their age shown
+    pub async fn get_birthdays_with_timezone_on_dates(
+        &self,
+        dates: &[(i32, i32)],
+    ) -> Result<Vec<BirthdayWithTimezone>, SqlxError> {
+        let (months, days): (Vec<i32>, Vec<i32>) = dates.iter().copied().unzip();
+        let rows: Vec<RawBirthdayTimezoneRow> = sqlx::query_as(
+            "SELECT b.user_id, b.birth_month, b.birth_day, b.birth_year, s.timezone, b.show_age \
+             FROM user_birthdays b LEFT JOIN user_settings s ON s.user_id = b.user_id \
+             WHERE (b.birth_month, b.birth_day) IN (SELECT * FROM UNNEST($1::INTEGER[], $2::INTEGER[]))",
+        )
+        .bind(months)
+        .bind(days)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(user_id, month, day, year, timezone, show_age)| BirthdayWithTimezone {
+                user_id: UserId::new(user_id as u64),
+                month,
+                day,
+                year,
+                timezone,
+                show_age,
+            })
+            .collect())
+    }
+
+    /// Get all users with birthdays on a specific date, with their birth year and whether
+    /// they want their age shown
     pub async fn get_birthdays_on_date(
         &self,
         month: i32,
         day: i32,
-    ) -> Result<Vec<(UserId, Option<i32>)>, SqlxError> {
-        let rows: Vec<(i64, Option<i32>)> = sqlx::query_as(
-            "SELECT user_id, birth_year FROM user_birthdays WHERE birth_month = $1 AND birth_day = $2",
+    ) -> Result<Vec<(UserId, Option<i32>, bool)>, SqlxError> {
+        let rows: Vec<(i64, Option<i32>, bool)> = sqlx::query_as(
+            "SELECT user_id, birth_year, show_age FROM user_birthdays \
+             WHERE birth_month = $1 AND birth_day = $2",
         )
         .bind(month)
         .bind(day)
@@ -63,7 +351,7 @@ impl Database {
 
         Ok(rows
             .into_iter()
-            .map(|(user_id, year)| (UserId::new(user_id as u64), year))
+            .map(|(user_id, year, show_age)| (UserId::new(user_id as u64), year, show_age))
             .collect())
     }
 
@@ -121,32 +409,40 @@ impl Database {
         Ok(())
     }
 
-    /// Get birthday notification channel for a guild
-    pub async fn get_birthday_channel(
-        &self,
-        guild_id: GuildId,
-    ) -> Result<
-        Option<(ChannelId, Option<MessageId>, Option<String>, Option<String>, Option<String>, Option<String>)>,
-        SqlxError,
-    > {
-        let result: Option<(i64, Option<i64>, Option<String>, Option<String>, Option<String>, Option<String>)> =
-            sqlx::query_as(
-                "SELECT channel_id, message_id, custom_message, custom_message_without_age, custom_header, custom_footer \
-                 FROM birthday_channels WHERE guild_id = $1",
-            )
-            .bind(guild_id.get() as i64)
-            .fetch_optional(self.pool())
-            .await?;
+    /// Get the birthday announcement configuration of a guild, None when it isn't set up
+    pub async fn get_birthday_config(&self, guild_id: GuildId) -> Result<Option<BirthdayConfig>, SqlxError> {
+        let row: Option<RawBirthdayConfigRow> = sqlx::query_as(
+            "SELECT channel_id, custom_message, custom_message_without_age, custom_header, custom_footer, \
+             age_template, separate_messages, weekend_channel_id, mirror_channel_ids, dm_fallback, \
+             dm_notifications, announcement_retention_days, silent_announcements, wishes_thread, \
+             ping_birthday_users, use_embed, reminder_days \
+             FROM birthday_channels WHERE guild_id = $1",
+        )
+        .bind(guild_id.get() as i64)
+        .fetch_optional(self.pool())
+        .await?;
 
-        Ok(result.map(|(channel_id, message_id, msg, msg_without_age, header, footer)| {
-            (
-                ChannelId::new(channel_id as u64),
-                message_id.map(|id| MessageId::new(id as u64)),
-                msg,
-                msg_without_age,
-                header,
-                footer,
-            )
+        Ok(row.map(|row| BirthdayConfig {
+            channel_id: ChannelId::new(row.channel_id as u64),
+            custom_message: row.custom_message,
+            custom_message_without_age: row.custom_message_without_age,
+            custom_header: row.custom_header,
+            custom_footer: row.custom_footer,
+            age_template: row.age_template,
+            separate_messages: row.separate_messages,
+            weekend_channel_id: row.weekend_channel_id.map(|id| ChannelId::new(id as u64)),
+            mirror_channel_ids: row
+                .mirror_channel_ids
+                .map(|ids| parse_channel_id_list(&ids).into_iter().map(ChannelId::new).collect())
+                .unwrap_or_default(),
+            dm_fallback: row.dm_fallback,
+            dm_notifications: row.dm_notifications,
+            announcement_retention_days: row.announcement_retention_days.map(|days| days as u32),
+            silent_announcements: row.silent_announcements,
+            wishes_thread: row.wishes_thread,
+            ping_users: row.ping_birthday_users,
+            use_embed: row.use_embed,
+            reminder_days: row.reminder_days.map(|days| days as u32),
         }))
     }
 
@@ -200,6 +496,166 @@ impl Database {
         }))
     }
 
+    /// Record the messages of a birthday announcement so they can be deleted later
+    pub async fn log_announcements(
+        &self,
+        guild_id: GuildId,
+        channel_id: ChannelId,
+        message_ids: &[MessageId],
+    ) -> Result<(), SqlxError> {
+        let ids: Vec<i64> = message_ids.iter().map(|id| id.get() as i64).collect();
+        sqlx::query(
+            "INSERT INTO announcement_log (message_id, guild_id, channel_id) \
+             SELECT message_id, $2, $3 FROM UNNEST($1::BIGINT[]) AS message_id \
+             ON CONFLICT (message_id) DO NOTHING",
+        )
+        .bind(ids)
+        .bind(guild_id.get() as i64)
+        .bind(channel_id.get() as i64)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+    /// Get the announcements of a guild posted at least `days` days ago
+    pub async fn get_expired_announcements(
+        &self,
+        guild_id: GuildId,
+        days: u32,
+    ) -> Result<Vec<(ChannelId, MessageId)>, SqlxError> {
+        let rows: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT channel_id, message_id FROM announcement_log \
+             WHERE guild_id = $1 AND posted_at::date <= CURRENT_DATE - $2::INTEGER",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(days as i32)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id, message_id)| {
+                (ChannelId::new(channel_id as u64), MessageId::new(message_id as u64))
+            })
+            .collect())
+    }
+    /// Forget logged announcements, once deleted
+    pub async fn remove_announcements(&self, message_ids: &[MessageId]) -> Result<(), SqlxError> {
+        let ids: Vec<i64> = message_ids.iter().map(|id| id.get() as i64).collect();
+        sqlx::query("DELETE FROM announcement_log WHERE message_id = ANY($1)")
+            .bind(ids)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+    /// Mark the birthday reminder of a guild as sent for a date, returning false when it already
+    /// was (another schedule run of the same day got there first)
+    pub async fn claim_birthday_reminder(&self, guild_id: GuildId, date: NaiveDate) -> Result<bool, SqlxError> {
+        let result = sqlx::query(
+            "UPDATE birthday_channels SET last_reminder_date = $1 \
+             WHERE guild_id = $2 AND (last_reminder_date IS NULL OR last_reminder_date < $1)",
+        )
+        .bind(date)
+        .bind(guild_id.get() as i64)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+    /// Change one setting of the guild's birthday announcements,
+    /// returning false when birthdays aren't configured for the guild
+    pub async fn set_birthday_setting(
+        &self,
+        guild_id: GuildId,
+        setting: &BirthdaySetting,
+    ) -> Result<bool, SqlxError> {
+        let sql = format!(
+            "UPDATE birthday_channels SET {} = $1 WHERE guild_id = $2",
+            setting.column()
+        );
+        let query = sqlx::query(&sql);
+        let query = match setting {
+            BirthdaySetting::SeparateMessages(enabled)
+            | BirthdaySetting::SilentAnnouncements(enabled)
+            | BirthdaySetting::UseEmbed(enabled)
+            | BirthdaySetting::PingUsers(enabled)
+            | BirthdaySetting::WishesThread(enabled)
+            | BirthdaySetting::DmNotifications(enabled)
+            | BirthdaySetting::DmFallback(enabled) => query.bind(*enabled),
+            BirthdaySetting::AnnouncementRetention(days) | BirthdaySetting::ReminderDays(days) => {
+                query.bind(days.map(|days| days as i32))
+            }
+            BirthdaySetting::AgeTemplate(template) => query.bind(template.clone()),
+            BirthdaySetting::WeekendChannel(channel_id) => {
+                query.bind(channel_id.map(|id| id.get() as i64))
+            }
+            BirthdaySetting::MirrorChannels(channel_ids) => {
+                let ids: Vec<u64> = channel_ids.iter().map(|id| id.get()).collect();
+                query.bind((!ids.is_empty()).then(|| join_channel_id_list(&ids)))
+            }
+        };
+        let result = query.bind(guild_id.get() as i64).execute(self.pool()).await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Set the role given to users on the birthday they reach a milestone age
+    pub async fn set_milestone_role(
+        &self,
+        guild_id: GuildId,
+        age: i32,
+        role_id: RoleId,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO birthday_milestone_roles (guild_id, age, role_id)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (guild_id, age)
+            DO UPDATE SET role_id = $3
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(age)
+        .bind(role_id.get() as i64)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Remove the milestone role for an age, returning whether one was configured
+    pub async fn remove_milestone_role(
+        &self,
+        guild_id: GuildId,
+        age: i32,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query(
+            "DELETE FROM birthday_milestone_roles WHERE guild_id = $1 AND age = $2",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(age)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get all milestone roles for a guild as (age, role) pairs
+    pub async fn get_milestone_roles(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<(i32, RoleId)>, SqlxError> {
+        let rows: Vec<(i32, i64)> = sqlx::query_as(
+            "SELECT age, role_id FROM birthday_milestone_roles WHERE guild_id = $1 ORDER BY age",
+        )
+        .bind(guild_id.get() as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(age, role_id)| (age, RoleId::new(role_id as u64)))
+            .collect())
+    }
+
     /// Check if any birthday channels are configured
     pub async fn has_any_birthday_channels(&self) -> Result<bool, SqlxError> {
         let count: (i64,) = sqlx::query_as("SELECT COUNT(*) FROM birthday_channels")
@@ -1,25 +1,123 @@
 use super::Database;
+use crate::models::LobbyChannel;
+use chrono::{DateTime, NaiveDateTime, Utc};
 use poise::serenity_prelude::{ChannelId, GuildId, UserId};
 use sqlx::Error as SqlxError;
 
+/// A stored temp channel: id, guild, owner, lobby, persistent, archived, locked and creation time
+pub type TempChannelRow = (ChannelId, GuildId, UserId, ChannelId, bool, bool, bool, DateTime<Utc>);
+
+type RawTempChannelRow = (i64, i64, i64, i64, bool, bool, bool, NaiveDateTime);
+
+/// Raw lobby row: id, guild, user limit, name template and bitrate
+type RawLobbyRow = (i64, i64, Option<i32>, Option<String>, Option<i32>);
+
+/// Raw channel ban row: user, expiry and whether the user was allowed to connect before
+type RawChannelBanRow = (i64, Option<NaiveDateTime>, bool);
+
+/// A member kept out of a temp channel: banned until lifted, or kicked until `expires_at`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelBan {
+    pub user_id: UserId,
+    /// None for a ban, the end of the cooldown for a kick
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Whether the member had an explicit `CONNECT` allow to give back once lifted
+    pub connect_allowed: bool,
+}
+
+impl From<RawChannelBanRow> for ChannelBan {
+    fn from((user_id, expires_at, connect_allowed): RawChannelBanRow) -> Self {
+        ChannelBan {
+            user_id: UserId::new(user_id as u64),
+            expires_at: expires_at.map(|expires_at| expires_at.and_utc()),
+            connect_allowed,
+        }
+    }
+}
+
 impl Database {
-    /// Insert a lobby channel into the database
+    /// Insert a lobby channel and the settings of its temp channels into the database
     pub async fn insert_lobby_channel(
         &self,
         channel_id: ChannelId,
-        guild_id: GuildId,
+        lobby: &LobbyChannel,
     ) -> Result<(), SqlxError> {
         sqlx::query(
-            "INSERT INTO lobby_channels (channel_id, guild_id) VALUES ($1, $2) \
+            "INSERT INTO lobby_channels (channel_id, guild_id, user_limit, name_template, bitrate) \
+             VALUES ($1, $2, $3, $4, $5) \
              ON CONFLICT (channel_id) DO NOTHING",
         )
         .bind(channel_id.get() as i64)
-        .bind(guild_id.get() as i64)
+        .bind(lobby.guild_id.get() as i64)
+        .bind(lobby.user_limit.map(|limit| limit as i32))
+        .bind(&lobby.name_template)
+        .bind(lobby.bitrate.map(|bitrate| bitrate as i32))
         .execute(self.pool())
         .await?;
         Ok(())
     }
 
+    /// Get all lobby channels with their spawn settings
+    pub async fn get_all_lobbies_full(&self) -> Result<Vec<(ChannelId, LobbyChannel)>, SqlxError> {
+        let rows: Vec<RawLobbyRow> = sqlx::query_as(
+            "SELECT channel_id, guild_id, user_limit, name_template, bitrate FROM lobby_channels",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id, guild_id, user_limit, name_template, bitrate)| {
+                (
+                    ChannelId::new(channel_id as u64),
+                    LobbyChannel {
+                        guild_id: GuildId::new(guild_id as u64),
+                        user_limit: user_limit.map(|limit| limit as u32),
+                        name_template,
+                        bitrate: bitrate.map(|bitrate| bitrate as u32),
+                    },
+                )
+            })
+            .collect())
+    }
+
+    /// Fill the unset spawn settings of a guild's lobbies, returning the updated lobbies
+    pub async fn apply_lobby_defaults(
+        &self,
+        guild_id: GuildId,
+        user_limit: Option<u32>,
+        name_template: &str,
+    ) -> Result<Vec<(ChannelId, LobbyChannel)>, SqlxError> {
+        let rows: Vec<RawLobbyRow> = sqlx::query_as(
+            "UPDATE lobby_channels \
+             SET user_limit = COALESCE(user_limit, $2), \
+                 name_template = COALESCE(name_template, $3) \
+             WHERE guild_id = $1 \
+               AND (name_template IS NULL OR (user_limit IS NULL AND $2 IS NOT NULL)) \
+             RETURNING channel_id, guild_id, user_limit, name_template, bitrate",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(user_limit.map(|limit| limit as i32))
+        .bind(name_template)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id, _, user_limit, name_template, bitrate)| {
+                (
+                    ChannelId::new(channel_id as u64),
+                    LobbyChannel {
+                        guild_id,
+                        user_limit: user_limit.map(|limit| limit as u32),
+                        name_template,
+                        bitrate: bitrate.map(|bitrate| bitrate as u32),
+                    },
+                )
+            })
+            .collect())
+    }
+
     /// Get all lobby channels
     pub async fn get_all_lobby_channels(&self) -> Result<Vec<(ChannelId, GuildId)>, SqlxError> {
         let rows: Vec<(i64, i64)> =
@@ -39,7 +137,6 @@ impl Database {
     }
 
     /// Remove a lobby channel from the database
-    #[allow(dead_code)]
     pub async fn remove_lobby_channel(&self, channel_id: ChannelId) -> Result<(), SqlxError> {
         sqlx::query("DELETE FROM lobby_channels WHERE channel_id = $1")
             .bind(channel_id.get() as i64)
@@ -70,12 +167,12 @@ impl Database {
         Ok(())
     }
 
-    /// Get all temp channels (including persistent and archived status)
+    /// Get all temp channels (including persistent, archived and locked status and creation time)
     pub async fn get_all_temp_channels(
         &self,
-    ) -> Result<Vec<(ChannelId, GuildId, UserId, ChannelId, bool, bool)>, SqlxError> {
-        let rows: Vec<(i64, i64, i64, i64, bool, bool)> = sqlx::query_as(
-            "SELECT channel_id, guild_id, owner_id, lobby_channel_id, is_persistent, is_archived \
+    ) -> Result<Vec<TempChannelRow>, SqlxError> {
+        let rows: Vec<RawTempChannelRow> = sqlx::query_as(
+            "SELECT channel_id, guild_id, owner_id, lobby_channel_id, is_persistent, is_archived, is_locked, created_at \
              FROM temp_channels",
         )
         .fetch_all(self.pool())
@@ -83,7 +180,7 @@ impl Database {
 
         Ok(rows
             .into_iter()
-            .map(|(channel_id, guild_id, owner_id, lobby_channel_id, is_persistent, is_archived)| {
+            .map(|(channel_id, guild_id, owner_id, lobby_channel_id, is_persistent, is_archived, is_locked, created_at)| {
                 (
                     ChannelId::new(channel_id as u64),
                     GuildId::new(guild_id as u64),
@@ -91,6 +188,8 @@ impl Database {
                     ChannelId::new(lobby_channel_id as u64),
                     is_persistent,
                     is_archived,
+                    is_locked,
+                    created_at.and_utc(),
                 )
             })
             .collect())
@@ -105,6 +204,120 @@ impl Database {
         Ok(())
     }
 
+    /// Ban a user from a temp channel (bans are dropped along with the channel).
+    /// A pending kick of the user becomes a ban.
+    pub async fn add_channel_ban(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        connect_allowed: bool,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO channel_bans (channel_id, user_id, connect_allowed) VALUES ($1, $2, $3) \
+             ON CONFLICT (channel_id, user_id) DO UPDATE SET expires_at = NULL",
+        )
+        .bind(channel_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .bind(connect_allowed)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Keep a kicked user out of a temp channel for a cooldown. A banned user stays banned,
+    /// and kicking again only extends the cooldown.
+    pub async fn add_channel_kick(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+        cooldown_secs: u64,
+        connect_allowed: bool,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            "INSERT INTO channel_bans (channel_id, user_id, expires_at, connect_allowed) \
+             VALUES ($1, $2, NOW() + $3 * INTERVAL '1 second', $4) \
+             ON CONFLICT (channel_id, user_id) DO UPDATE SET expires_at = EXCLUDED.expires_at \
+             WHERE channel_bans.expires_at IS NOT NULL",
+        )
+        .bind(channel_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .bind(cooldown_secs as f64)
+        .bind(connect_allowed)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Lift the ban or kick of a user from a temp channel, returning it if there was one
+    pub async fn remove_channel_ban(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Option<ChannelBan>, SqlxError> {
+        let row: Option<RawChannelBanRow> = sqlx::query_as(
+            "DELETE FROM channel_bans WHERE channel_id = $1 AND user_id = $2 \
+             RETURNING user_id, expires_at, connect_allowed",
+        )
+        .bind(channel_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.map(ChannelBan::from))
+    }
+
+    /// Lift the kick of a user from a temp channel once its cooldown is over, returning it.
+    /// Nothing is lifted when the user was kicked again or banned meanwhile.
+    pub async fn remove_expired_channel_kick(
+        &self,
+        channel_id: ChannelId,
+        user_id: UserId,
+    ) -> Result<Option<ChannelBan>, SqlxError> {
+        let row: Option<RawChannelBanRow> = sqlx::query_as(
+            "DELETE FROM channel_bans WHERE channel_id = $1 AND user_id = $2 \
+             AND expires_at IS NOT NULL AND expires_at <= NOW() \
+             RETURNING user_id, expires_at, connect_allowed",
+        )
+        .bind(channel_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(row.map(ChannelBan::from))
+    }
+
+    /// Get the users banned or still kicked from a temp channel, oldest first
+    pub async fn get_channel_bans(&self, channel_id: ChannelId) -> Result<Vec<ChannelBan>, SqlxError> {
+        let rows: Vec<RawChannelBanRow> = sqlx::query_as(
+            "SELECT user_id, expires_at, connect_allowed FROM channel_bans \
+             WHERE channel_id = $1 AND (expires_at IS NULL OR expires_at > NOW()) \
+             ORDER BY created_at",
+        )
+        .bind(channel_id.get() as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows.into_iter().map(ChannelBan::from).collect())
+    }
+
+    /// Get the kicks of every temp channel, expired ones included, to lift them after a restart
+    pub async fn get_all_channel_kicks(&self) -> Result<Vec<(ChannelId, ChannelBan)>, SqlxError> {
+        let rows: Vec<(i64, i64, Option<NaiveDateTime>, bool)> = sqlx::query_as(
+            "SELECT channel_id, user_id, expires_at, connect_allowed FROM channel_bans \
+             WHERE expires_at IS NOT NULL",
+        )
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id, user_id, expires_at, connect_allowed)| {
+                (
+                    ChannelId::new(channel_id as u64),
+                    ChannelBan::from((user_id, expires_at, connect_allowed)),
+                )
+            })
+            .collect())
+    }
+
     /// Set a temp channel as persistent
     pub async fn set_channel_persistent(
         &self,
@@ -133,6 +346,59 @@ impl Database {
         Ok(())
     }
 
+    /// Set the owner of a temp channel
+    pub async fn set_channel_owner(
+        &self,
+        channel_id: ChannelId,
+        owner_id: UserId,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE temp_channels SET owner_id = $1 WHERE channel_id = $2")
+            .bind(owner_id.get() as i64)
+            .bind(channel_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Set a temp channel as locked
+    pub async fn set_channel_locked(
+        &self,
+        channel_id: ChannelId,
+        is_locked: bool,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE temp_channels SET is_locked = $1 WHERE channel_id = $2")
+            .bind(is_locked)
+            .bind(channel_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Set the topic of a temp channel (None clears it)
+    pub async fn set_channel_topic(
+        &self,
+        channel_id: ChannelId,
+        topic: Option<String>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE temp_channels SET topic = $1 WHERE channel_id = $2")
+            .bind(topic)
+            .bind(channel_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    /// Get the stored topic of a temp channel
+    pub async fn get_channel_topic(&self, channel_id: ChannelId) -> Result<Option<String>, SqlxError> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT topic FROM temp_channels WHERE channel_id = $1")
+                .bind(channel_id.get() as i64)
+                .fetch_optional(self.pool())
+                .await?;
+
+        Ok(result.and_then(|(topic,)| topic))
+    }
+
     /// Get archived channel for a user from a specific lobby in a guild
     pub async fn get_archived_channel_for_user(
         &self,
@@ -154,6 +420,96 @@ impl Database {
         Ok(result.map(|(channel_id,)| ChannelId::new(channel_id as u64)))
     }
 
+    /// Get all archived channels of a user in a guild, from any lobby
+    pub async fn get_all_archived_channels_for_user(
+        &self,
+        guild_id: GuildId,
+        owner_id: UserId,
+    ) -> Result<Vec<ChannelId>, SqlxError> {
+        let rows: Vec<(i64,)> = sqlx::query_as(
+            "SELECT channel_id FROM temp_channels \
+             WHERE guild_id = $1 AND owner_id = $2 AND is_archived = TRUE \
+             ORDER BY channel_id",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(owner_id.get() as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id,)| ChannelId::new(channel_id as u64))
+            .collect())
+    }
+
+    /// Record that a user is offered to restore their archived channels of a guild,
+    /// returning false when they already were within the cooldown
+    pub async fn claim_restore_offer(
+        &self,
+        guild_id: GuildId,
+        user_id: UserId,
+        cooldown_secs: u64,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query(
+            "INSERT INTO restore_offers (guild_id, user_id) VALUES ($1, $2) \
+             ON CONFLICT (guild_id, user_id) DO UPDATE SET offered_at = NOW() \
+             WHERE restore_offers.offered_at <= NOW() - $3 * INTERVAL '1 second'",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(user_id.get() as i64)
+        .bind(cooldown_secs as f64)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Get the persistent channels of a user in a guild, with their lobby and archived status
+    pub async fn get_persistent_channels_for_user(
+        &self,
+        guild_id: GuildId,
+        owner_id: UserId,
+    ) -> Result<Vec<(ChannelId, ChannelId, bool)>, SqlxError> {
+        let rows: Vec<(i64, i64, bool)> = sqlx::query_as(
+            "SELECT channel_id, lobby_channel_id, is_archived FROM temp_channels \
+             WHERE guild_id = $1 AND owner_id = $2 AND is_persistent = TRUE \
+             ORDER BY channel_id",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(owner_id.get() as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(channel_id, lobby_channel_id, is_archived)| {
+                (
+                    ChannelId::new(channel_id as u64),
+                    ChannelId::new(lobby_channel_id as u64),
+                    is_archived,
+                )
+            })
+            .collect())
+    }
+
+    /// Count the persistent channels (archived or not) a user owns in a guild
+    pub async fn count_persistent_channels_for_user(
+        &self,
+        guild_id: GuildId,
+        owner_id: UserId,
+    ) -> Result<u64, SqlxError> {
+        let (count,): (i64,) = sqlx::query_as(
+            "SELECT COUNT(*) FROM temp_channels \
+             WHERE guild_id = $1 AND owner_id = $2 AND is_persistent = TRUE",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(owner_id.get() as i64)
+        .fetch_one(self.pool())
+        .await?;
+
+        Ok(count.max(0) as u64)
+    }
+
     /// Get archive category for a guild
     pub async fn get_archive_category(
         &self,
@@ -184,4 +540,52 @@ impl Database {
         .await?;
         Ok(())
     }
+
+    /// Record the current number of active temp channels of every guild with a lobby,
+    /// returning the number of samples written
+    pub async fn record_lobby_activity_samples(&self) -> Result<u64, SqlxError> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO lobby_activity_samples (guild_id, sampled_at, count)
+            SELECT lobbies.guild_id, NOW(), COUNT(temp.channel_id)::INTEGER
+            FROM (SELECT DISTINCT guild_id FROM lobby_channels) AS lobbies
+            LEFT JOIN temp_channels AS temp
+                ON temp.guild_id = lobbies.guild_id AND temp.is_archived = FALSE
+            GROUP BY lobbies.guild_id
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Delete lobby activity samples older than the given number of days
+    pub async fn prune_lobby_activity_samples(&self, retention_days: i32) -> Result<u64, SqlxError> {
+        let result = sqlx::query(
+            "DELETE FROM lobby_activity_samples WHERE sampled_at < NOW() - make_interval(days => $1)",
+        )
+        .bind(retention_days)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Get (min, max, average, sample count) of active temp channels for a guild over the last hours
+    pub async fn get_lobby_activity_summary(
+        &self,
+        guild_id: GuildId,
+        hours: i32,
+    ) -> Result<(Option<i32>, Option<i32>, Option<f64>, i64), SqlxError> {
+        sqlx::query_as(
+            "SELECT MIN(count), MAX(count), AVG(count)::FLOAT8, COUNT(*) \
+             FROM lobby_activity_samples \
+             WHERE guild_id = $1 AND sampled_at >= NOW() - make_interval(hours => $2)",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(hours)
+        .fetch_one(self.pool())
+        .await
+    }
 }
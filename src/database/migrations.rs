@@ -1,13 +1,34 @@
 use super::Database;
 use sqlx::Error as SqlxError;
 
+use crate::constants::{BIRTHDAY_ROLE_UPDATE_CRON, DEFAULT_SCHEDULE_LABEL};
+
 impl Database {
     /// Run database migrations to create tables
     pub(super) async fn run_migrations(&self) -> Result<(), SqlxError> {
         self.create_lobby_tables().await?;
         self.create_guild_settings_table().await?;
+        self.create_user_settings_table().await?;
+        #[cfg(feature = "birthday")]
         self.create_birthday_tables().await?;
         self.create_schedule_tables().await?;
+        self.create_analytics_tables().await?;
+        Ok(())
+    }
+
+    async fn create_analytics_tables(&self) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS command_usage (
+                command TEXT PRIMARY KEY,
+                count BIGINT NOT NULL DEFAULT 0,
+                updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
         Ok(())
     }
 
@@ -31,7 +52,9 @@ impl Database {
                 owner_id BIGINT NOT NULL,
                 lobby_channel_id BIGINT NOT NULL,
                 is_persistent BOOLEAN NOT NULL DEFAULT FALSE,
-                is_archived BOOLEAN NOT NULL DEFAULT FALSE
+                is_archived BOOLEAN NOT NULL DEFAULT FALSE,
+                topic TEXT,
+                created_at TIMESTAMP NOT NULL DEFAULT NOW()
             )
             "#,
         )
@@ -51,6 +74,63 @@ impl Database {
                               WHERE table_name = 'temp_channels' AND column_name = 'is_archived') THEN
                     ALTER TABLE temp_channels ADD COLUMN is_archived BOOLEAN NOT NULL DEFAULT FALSE;
                 END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'temp_channels' AND column_name = 'topic') THEN
+                    ALTER TABLE temp_channels ADD COLUMN topic TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'temp_channels' AND column_name = 'is_locked') THEN
+                    ALTER TABLE temp_channels ADD COLUMN is_locked BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'temp_channels' AND column_name = 'created_at') THEN
+                    ALTER TABLE temp_channels ADD COLUMN created_at TIMESTAMP NOT NULL DEFAULT NOW();
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'lobby_channels' AND column_name = 'user_limit') THEN
+                    ALTER TABLE lobby_channels ADD COLUMN user_limit INTEGER;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'lobby_channels' AND column_name = 'name_template') THEN
+                    ALTER TABLE lobby_channels ADD COLUMN name_template TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'lobby_channels' AND column_name = 'bitrate') THEN
+                    ALTER TABLE lobby_channels ADD COLUMN bitrate INTEGER;
+                END IF;
+            END $$;
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS channel_bans (
+                channel_id BIGINT NOT NULL REFERENCES temp_channels (channel_id) ON DELETE CASCADE,
+                user_id BIGINT NOT NULL,
+                created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (channel_id, user_id)
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        // Kicks are stored as bans with an expiry, remembering whether the member was
+        // explicitly allowed to connect before
+        sqlx::query(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'channel_bans' AND column_name = 'expires_at') THEN
+                    ALTER TABLE channel_bans ADD COLUMN expires_at TIMESTAMP;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'channel_bans' AND column_name = 'connect_allowed') THEN
+                    ALTER TABLE channel_bans ADD COLUMN connect_allowed BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
             END $$;
             "#,
         )
@@ -68,6 +148,39 @@ impl Database {
         .execute(self.pool())
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS lobby_activity_samples (
+                guild_id BIGINT NOT NULL,
+                sampled_at TIMESTAMP NOT NULL DEFAULT NOW(),
+                count INTEGER NOT NULL
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_lobby_activity_samples_guild_time \
+             ON lobby_activity_samples (guild_id, sampled_at)",
+        )
+        .execute(self.pool())
+        .await?;
+
+        // When users were last DMed the picker to restore their archived channels
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS restore_offers (
+                guild_id BIGINT NOT NULL,
+                user_id BIGINT NOT NULL,
+                offered_at TIMESTAMP NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (guild_id, user_id)
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
         Ok(())
     }
 
@@ -85,9 +198,70 @@ impl Database {
         .execute(self.pool())
         .await?;
 
+        // Add columns if they don't exist (for existing databases)
+        sqlx::query(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'archive_dm_enabled') THEN
+                    ALTER TABLE guild_settings ADD COLUMN archive_dm_enabled BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'message_prefix') THEN
+                    ALTER TABLE guild_settings ADD COLUMN message_prefix TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'message_suffix') THEN
+                    ALTER TABLE guild_settings ADD COLUMN message_suffix TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'empty_channel_grace_secs') THEN
+                    ALTER TABLE guild_settings ADD COLUMN empty_channel_grace_secs INTEGER NOT NULL DEFAULT 0;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'restore_all_on_join') THEN
+                    ALTER TABLE guild_settings ADD COLUMN restore_all_on_join BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'channel_name_blacklist') THEN
+                    ALTER TABLE guild_settings ADD COLUMN channel_name_blacklist TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'max_persistent_channels') THEN
+                    ALTER TABLE guild_settings ADD COLUMN max_persistent_channels INTEGER NOT NULL DEFAULT 0;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'guild_settings' AND column_name = 'archive_category_base') THEN
+                    ALTER TABLE guild_settings ADD COLUMN archive_category_base TEXT;
+                END IF;
+            END $$;
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        Ok(())
+    }
+
+    async fn create_user_settings_table(&self) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS user_settings (
+                user_id BIGINT PRIMARY KEY,
+                timezone TEXT,
+                created_at TIMESTAMP NOT NULL DEFAULT NOW(),
+                updated_at TIMESTAMP NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
         Ok(())
     }
 
+    #[cfg(feature = "birthday")]
     async fn create_birthday_tables(&self) -> Result<(), SqlxError> {
         sqlx::query(
             r#"
@@ -95,6 +269,7 @@ impl Database {
                 user_id BIGINT PRIMARY KEY,
                 birth_month INTEGER NOT NULL CHECK (birth_month BETWEEN 1 AND 12),
                 birth_day INTEGER NOT NULL CHECK (birth_day BETWEEN 1 AND 31),
+                -- The upper bound (current year) can't be a CHECK; validate_birth_year enforces it
                 birth_year INTEGER CHECK (birth_year IS NULL OR birth_year > 1900),
                 created_at TIMESTAMP NOT NULL DEFAULT NOW(),
                 updated_at TIMESTAMP NOT NULL DEFAULT NOW()
@@ -104,6 +279,14 @@ impl Database {
         .execute(self.pool())
         .await?;
 
+        // The daily birthday lookups filter on the date
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_user_birthdays_month_day \
+             ON user_birthdays (birth_month, birth_day)",
+        )
+        .execute(self.pool())
+        .await?;
+
         sqlx::query(
             r#"
             CREATE TABLE IF NOT EXISTS birthday_channels (
@@ -161,12 +344,94 @@ impl Database {
                               WHERE table_name = 'birthday_channels' AND column_name = 'custom_message_without_age') THEN
                     ALTER TABLE birthday_channels ADD COLUMN custom_message_without_age TEXT;
                 END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'separate_messages') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN separate_messages BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'weekend_channel_id') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN weekend_channel_id BIGINT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'dm_fallback') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN dm_fallback BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'announcement_retention_days') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN announcement_retention_days INTEGER;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'silent_announcements') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN silent_announcements BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'wishes_thread') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN wishes_thread BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'ping_birthday_users') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN ping_birthday_users BOOLEAN NOT NULL DEFAULT TRUE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'dm_notifications') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN dm_notifications BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'mirror_channel_ids') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN mirror_channel_ids TEXT;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'user_birthdays' AND column_name = 'show_age') THEN
+                    ALTER TABLE user_birthdays ADD COLUMN show_age BOOLEAN NOT NULL DEFAULT TRUE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'use_embed') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN use_embed BOOLEAN NOT NULL DEFAULT FALSE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'reminder_days') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN reminder_days INTEGER;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'last_reminder_date') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN last_reminder_date DATE;
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'birthday_channels' AND column_name = 'age_template') THEN
+                    ALTER TABLE birthday_channels ADD COLUMN age_template TEXT;
+                END IF;
             END $$;
             "#,
         )
         .execute(self.pool())
         .await?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS announcement_log (
+                message_id BIGINT PRIMARY KEY,
+                guild_id BIGINT NOT NULL,
+                channel_id BIGINT NOT NULL,
+                posted_at TIMESTAMP NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS birthday_milestone_roles (
+                guild_id BIGINT NOT NULL,
+                age INTEGER NOT NULL CHECK (age > 0),
+                role_id BIGINT NOT NULL,
+                PRIMARY KEY (guild_id, age)
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
         Ok(())
     }
 
@@ -175,11 +440,12 @@ impl Database {
         sqlx::query(
             r#"
             DO $$ BEGIN
-                CREATE TYPE schedule_type AS ENUM ('birthday', 'birthdayrole');
+                CREATE TYPE schedule_type AS ENUM ('birthday', 'birthdayrole', 'announcement');
             EXCEPTION
                 WHEN duplicate_object THEN 
                     -- Type already exists, try to add new values if they don't exist
                     ALTER TYPE schedule_type ADD VALUE IF NOT EXISTS 'birthdayrole';
+                    ALTER TYPE schedule_type ADD VALUE IF NOT EXISTS 'announcement';
             END $$;
             "#,
         )
@@ -203,6 +469,51 @@ impl Database {
         .execute(self.pool())
         .await?;
 
+        // Label telling apart several schedules of the same type in a guild,
+        // and last successful run used to catch up after downtime
+        sqlx::query(
+            r#"
+            DO $$
+            BEGIN
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'schedules' AND column_name = 'label') THEN
+                    ALTER TABLE schedules ADD COLUMN label TEXT NOT NULL DEFAULT 'default';
+                END IF;
+                IF NOT EXISTS (SELECT 1 FROM information_schema.columns 
+                              WHERE table_name = 'schedules' AND column_name = 'last_run') THEN
+                    ALTER TABLE schedules ADD COLUMN last_run TIMESTAMP;
+                END IF;
+            END $$;
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
+        // Birthday roles used to be updated once a day at the guild's midnight,
+        // they now follow the date of each user's own timezone
+        sqlx::query(
+            "UPDATE schedules SET cron_expression = $1, updated_at = NOW() \
+             WHERE schedule_type = 'birthdayrole' AND guild_id IS NOT NULL AND label = $2 \
+             AND cron_expression <> $1",
+        )
+        .bind(BIRTHDAY_ROLE_UPDATE_CRON)
+        .bind(DEFAULT_SCHEDULE_LABEL)
+        .execute(self.pool())
+        .await?;
+
+        // Message and channel of announcement schedules
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS scheduled_announcements (
+                schedule_id INTEGER PRIMARY KEY REFERENCES schedules (id) ON DELETE CASCADE,
+                channel_id BIGINT NOT NULL,
+                message TEXT NOT NULL
+            )
+            "#,
+        )
+        .execute(self.pool())
+        .await?;
+
         Ok(())
     }
 }
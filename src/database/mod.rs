@@ -1,7 +1,13 @@
 /// Database modules organized by feature
 mod migrations;
+mod analytics;
+#[cfg(feature = "lobby")]
 mod lobby;
+#[cfg(feature = "birthday")]
 mod birthday;
+#[cfg(feature = "birthday")]
+pub use birthday::{BirthdayConfig, BirthdaySetting};
+#[cfg(feature = "birthday")]
 mod schedule;
 mod settings;
 
@@ -31,6 +37,17 @@ impl Database {
         Ok(db)
     }
 
+    /// Check that the database answers a trivial query
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    /// Close every connection of the pool, waiting for the in-flight queries
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
     /// Get a reference to the connection pool (for internal use)
     pub(crate) fn pool(&self) -> &PgPool {
         &self.pool
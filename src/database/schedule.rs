@@ -1,111 +1,255 @@
 use super::Database;
-use poise::serenity_prelude::GuildId;
-use sqlx::Error as SqlxError;
+use poise::serenity_prelude::{ChannelId, GuildId};
+use chrono::{DateTime, NaiveDateTime, Utc};
+use sqlx::{Error as SqlxError, PgConnection};
+
+/// Raw schedule row: id, guild, type, label, cron expression, enabled, last run
+type ScheduleRow = (
+    i32,
+    Option<i64>,
+    crate::schedule::ScheduleType,
+    String,
+    String,
+    bool,
+    Option<NaiveDateTime>,
+);
+
+/// Convert raw schedule rows into schedules
+fn schedules_from_rows(rows: Vec<ScheduleRow>) -> Vec<crate::schedule::Schedule> {
+    rows.into_iter()
+        .map(
+            |(id, guild_id, schedule_type, label, cron_expression, enabled, last_run)| {
+                crate::schedule::Schedule {
+                    id,
+                    guild_id,
+                    schedule_type,
+                    label,
+                    cron_expression,
+                    enabled,
+                    last_run: last_run.map(|time| time.and_utc()),
+                }
+            },
+        )
+        .collect()
+}
 
 impl Database {
     /// Get all schedules from the database
     pub async fn get_all_schedules(&self) -> Result<Vec<crate::schedule::Schedule>, SqlxError> {
-        let rows: Vec<(i32, Option<i64>, crate::schedule::ScheduleType, String, bool)> =
-            sqlx::query_as(
-                "SELECT id, guild_id, schedule_type, cron_expression, enabled FROM schedules",
-            )
-            .fetch_all(self.pool())
-            .await?;
+        let rows: Vec<ScheduleRow> = sqlx::query_as(
+            "SELECT id, guild_id, schedule_type, label, cron_expression, enabled, last_run \
+             FROM schedules",
+        )
+        .fetch_all(self.pool())
+        .await?;
 
-        Ok(rows
-            .into_iter()
-            .map(
-                |(id, guild_id, schedule_type, cron_expression, enabled)| {
-                    crate::schedule::Schedule {
-                        id,
-                        guild_id,
-                        schedule_type,
-                        cron_expression,
-                        enabled,
-                    }
-                },
-            )
-            .collect())
+        Ok(schedules_from_rows(rows))
+    }
+
+    /// Get the schedules running for a guild, including the global ones, oldest first
+    pub async fn get_guild_schedules(
+        &self,
+        guild_id: GuildId,
+    ) -> Result<Vec<crate::schedule::Schedule>, SqlxError> {
+        let rows: Vec<ScheduleRow> = sqlx::query_as(
+            "SELECT id, guild_id, schedule_type, label, cron_expression, enabled, last_run \
+             FROM schedules WHERE guild_id = $1 OR guild_id IS NULL ORDER BY id",
+        )
+        .bind(guild_id.get() as i64)
+        .fetch_all(self.pool())
+        .await?;
+
+        Ok(schedules_from_rows(rows))
     }
 
-    /// Create or update a schedule
+    /// Create or update a schedule, identified by its guild, type and label.
+    /// Returns the id of the schedule.
     pub async fn upsert_schedule(
         &self,
         guild_id: Option<GuildId>,
         schedule_type: crate::schedule::ScheduleType,
+        label: &str,
         cron_expression: String,
         enabled: bool,
-    ) -> Result<(), SqlxError> {
+    ) -> Result<i32, SqlxError> {
+        let mut conn = self.pool().acquire().await?;
+        Self::upsert_schedule_on(&mut conn, guild_id, schedule_type, label, cron_expression, enabled).await
+    }
+
+    /// Create or update an announcement schedule along with the channel and message it posts,
+    /// both or neither being saved. Returns the id of the schedule.
+    pub async fn upsert_announcement(
+        &self,
+        guild_id: GuildId,
+        label: &str,
+        cron_expression: String,
+        channel_id: ChannelId,
+        message: &str,
+    ) -> Result<i32, SqlxError> {
+        let mut tx = self.pool().begin().await?;
+        let schedule_id = Self::upsert_schedule_on(
+            &mut tx,
+            Some(guild_id),
+            crate::schedule::ScheduleType::Announcement,
+            label,
+            cron_expression,
+            true,
+        )
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO scheduled_announcements (schedule_id, channel_id, message)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (schedule_id)
+            DO UPDATE SET channel_id = $2, message = $3
+            "#,
+        )
+        .bind(schedule_id)
+        .bind(channel_id.get() as i64)
+        .bind(message)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(schedule_id)
+    }
+
+    /// Create or update a schedule on a given connection, so it can be part of a transaction
+    async fn upsert_schedule_on(
+        conn: &mut PgConnection,
+        guild_id: Option<GuildId>,
+        schedule_type: crate::schedule::ScheduleType,
+        label: &str,
+        cron_expression: String,
+        enabled: bool,
+    ) -> Result<i32, SqlxError> {
         let guild_id_value = guild_id.map(|id| id.get() as i64);
 
-        // Check if a schedule of this type already exists for this guild
+        // Check if a schedule of this type and label already exists for this guild
         let existing: Option<(i32,)> = if let Some(gid) = guild_id_value {
             sqlx::query_as(
-                "SELECT id FROM schedules WHERE guild_id = $1 AND schedule_type = $2",
+                "SELECT id FROM schedules WHERE guild_id = $1 AND schedule_type = $2 AND label = $3",
             )
             .bind(gid)
             .bind(&schedule_type)
-            .fetch_optional(self.pool())
+            .bind(label)
+            .fetch_optional(&mut *conn)
             .await?
         } else {
             sqlx::query_as(
-                "SELECT id FROM schedules WHERE guild_id IS NULL AND schedule_type = $1",
+                "SELECT id FROM schedules WHERE guild_id IS NULL AND schedule_type = $1 AND label = $2",
             )
             .bind(&schedule_type)
-            .fetch_optional(self.pool())
+            .bind(label)
+            .fetch_optional(&mut *conn)
             .await?
         };
 
-        if existing.is_some() {
+        if let Some((id,)) = existing {
             // Update existing schedule
             if let Some(gid) = guild_id_value {
                 sqlx::query(
                     r#"
                     UPDATE schedules 
                     SET cron_expression = $1, enabled = $2, updated_at = NOW()
-                    WHERE guild_id = $3 AND schedule_type = $4
+                    WHERE guild_id = $3 AND schedule_type = $4 AND label = $5
                     "#,
                 )
                 .bind(&cron_expression)
                 .bind(enabled)
                 .bind(gid)
                 .bind(schedule_type)
-                .execute(self.pool())
+                .bind(label)
+                .execute(&mut *conn)
                 .await?;
             } else {
                 sqlx::query(
                     r#"
                     UPDATE schedules 
                     SET cron_expression = $1, enabled = $2, updated_at = NOW()
-                    WHERE guild_id IS NULL AND schedule_type = $3
+                    WHERE guild_id IS NULL AND schedule_type = $3 AND label = $4
                     "#,
                 )
                 .bind(&cron_expression)
                 .bind(enabled)
                 .bind(schedule_type)
-                .execute(self.pool())
+                .bind(label)
+                .execute(&mut *conn)
                 .await?;
             }
+            Ok(id)
         } else {
             // Insert new schedule
-            sqlx::query(
+            let (id,): (i32,) = sqlx::query_as(
                 r#"
-                INSERT INTO schedules (guild_id, schedule_type, cron_expression, enabled)
-                VALUES ($1, $2, $3, $4)
+                INSERT INTO schedules (guild_id, schedule_type, label, cron_expression, enabled)
+                VALUES ($1, $2, $3, $4, $5)
+                RETURNING id
                 "#,
             )
             .bind(guild_id_value)
             .bind(schedule_type)
+            .bind(label)
             .bind(&cron_expression)
             .bind(enabled)
-            .execute(self.pool())
+            .fetch_one(&mut *conn)
             .await?;
+            Ok(id)
         }
+    }
+
+    /// Get the channel and message posted by an announcement schedule
+    pub async fn get_scheduled_announcement(
+        &self,
+        schedule_id: i32,
+    ) -> Result<Option<(ChannelId, String)>, SqlxError> {
+        let row: Option<(i64, String)> = sqlx::query_as(
+            "SELECT channel_id, message FROM scheduled_announcements WHERE schedule_id = $1",
+        )
+        .bind(schedule_id)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(row.map(|(channel_id, message)| (ChannelId::new(channel_id as u64), message)))
+    }
+
+    /// Record when a schedule last ran successfully
+    pub async fn set_schedule_last_run(
+        &self,
+        schedule_id: i32,
+        last_run: DateTime<Utc>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query("UPDATE schedules SET last_run = $1 WHERE id = $2")
+            .bind(last_run.naive_utc())
+            .bind(schedule_id)
+            .execute(self.pool())
+            .await?;
 
         Ok(())
     }
 
-    /// Enable or disable a schedule by type for a specific guild (or globally if guild_id is None)
+    /// Delete a single schedule of a guild by type and label.
+    /// Returns whether a schedule was deleted.
+    pub async fn delete_schedule(
+        &self,
+        guild_id: GuildId,
+        schedule_type: crate::schedule::ScheduleType,
+        label: &str,
+    ) -> Result<bool, SqlxError> {
+        let result = sqlx::query(
+            "DELETE FROM schedules WHERE guild_id = $1 AND schedule_type = $2 AND label = $3",
+        )
+        .bind(guild_id.get() as i64)
+        .bind(schedule_type)
+        .bind(label)
+        .execute(self.pool())
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Enable or disable every schedule of a type for a specific guild (or globally if guild_id is None)
     pub async fn set_schedule_enabled(
         &self,
         guild_id: Option<GuildId>,
@@ -135,4 +279,14 @@ impl Database {
 
         Ok(())
     }
+
+    /// Delete all schedules of a guild (global schedules are never affected)
+    pub async fn delete_schedules_for_guild(&self, guild_id: GuildId) -> Result<u64, SqlxError> {
+        let result = sqlx::query("DELETE FROM schedules WHERE guild_id = $1")
+            .bind(guild_id.get() as i64)
+            .execute(self.pool())
+            .await?;
+
+        Ok(result.rows_affected())
+    }
 }
@@ -1,7 +1,21 @@
 use super::Database;
-use poise::serenity_prelude::GuildId;
+use poise::serenity_prelude::{GuildId, UserId};
 use sqlx::Error as SqlxError;
 
+use crate::{models::GuildSettings, utils::string_utils::split_and_trim};
+
+/// Raw guild settings row: timezone, prefix, suffix, grace period, channel name blacklist,
+/// persistent channels cap, archive category base name
+type GuildSettingsRow = (
+    String,
+    Option<String>,
+    Option<String>,
+    i32,
+    Option<String>,
+    i32,
+    Option<String>,
+);
+
 impl Database {
     /// Set timezone for a guild
     pub async fn set_guild_timezone(
@@ -24,15 +38,253 @@ impl Database {
         Ok(())
     }
 
-    /// Get timezone for a guild (returns "UTC" if not set)
-    pub async fn get_guild_timezone(&self, guild_id: GuildId) -> Result<String, SqlxError> {
-        let result: Option<(String,)> = sqlx::query_as(
-            "SELECT timezone FROM guild_settings WHERE guild_id = $1",
+    /// Set the personal timezone of a user (None to follow the server's timezone)
+    pub async fn set_user_timezone(
+        &self,
+        user_id: UserId,
+        timezone: Option<&str>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO user_settings (user_id, timezone, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (user_id)
+            DO UPDATE SET timezone = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(user_id.get() as i64)
+        .bind(timezone)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Get the personal timezone of a user, if they set one
+    pub async fn get_user_timezone(&self, user_id: UserId) -> Result<Option<String>, SqlxError> {
+        let result: Option<(Option<String>,)> =
+            sqlx::query_as("SELECT timezone FROM user_settings WHERE user_id = $1")
+                .bind(user_id.get() as i64)
+                .fetch_optional(self.pool())
+                .await?;
+
+        Ok(result.and_then(|(timezone,)| timezone))
+    }
+
+    /// Enable or disable archive notification DMs for a guild
+    #[cfg(feature = "lobby")]
+    pub async fn set_archive_dm_enabled(
+        &self,
+        guild_id: GuildId,
+        enabled: bool,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, archive_dm_enabled, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET archive_dm_enabled = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(enabled)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Check whether archive notification DMs are enabled for a guild (defaults to false)
+    #[cfg(feature = "lobby")]
+    pub async fn get_archive_dm_enabled(&self, guild_id: GuildId) -> Result<bool, SqlxError> {
+        let result: Option<(bool,)> = sqlx::query_as(
+            "SELECT archive_dm_enabled FROM guild_settings WHERE guild_id = $1",
+        )
+        .bind(guild_id.get() as i64)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(result.is_some_and(|(enabled,)| enabled))
+    }
+
+    /// Enable or disable offering to restore all archived channels when a user joins any lobby
+    #[cfg(feature = "lobby")]
+    pub async fn set_restore_all_on_join(
+        &self,
+        guild_id: GuildId,
+        enabled: bool,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, restore_all_on_join, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET restore_all_on_join = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(enabled)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Check whether joining any lobby offers to restore all archived channels (defaults to false)
+    #[cfg(feature = "lobby")]
+    pub async fn get_restore_all_on_join(&self, guild_id: GuildId) -> Result<bool, SqlxError> {
+        let result: Option<(bool,)> = sqlx::query_as(
+            "SELECT restore_all_on_join FROM guild_settings WHERE guild_id = $1",
+        )
+        .bind(guild_id.get() as i64)
+        .fetch_optional(self.pool())
+        .await?;
+
+        Ok(result.is_some_and(|(enabled,)| enabled))
+    }
+
+    /// Set the delay before empty temp channels of a guild are deleted or archived
+    #[cfg(feature = "lobby")]
+    pub async fn set_empty_channel_grace_secs(
+        &self,
+        guild_id: GuildId,
+        grace_secs: u32,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, empty_channel_grace_secs, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET empty_channel_grace_secs = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(grace_secs as i32)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Set the maximum number of persistent channels a user may own in a guild (0 for no limit)
+    #[cfg(feature = "lobby")]
+    pub async fn set_max_persistent_channels(
+        &self,
+        guild_id: GuildId,
+        max_channels: u32,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, max_persistent_channels, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET max_persistent_channels = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(max_channels as i32)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Set the base name of the archive category created in a guild (None for the default name)
+    #[cfg(feature = "lobby")]
+    pub async fn set_archive_category_base(
+        &self,
+        guild_id: GuildId,
+        base_name: Option<String>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, archive_category_base, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET archive_category_base = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(base_name)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Set the branding prefix and suffix added to the bot's messages in a guild
+    pub async fn set_message_branding(
+        &self,
+        guild_id: GuildId,
+        prefix: Option<String>,
+        suffix: Option<String>,
+    ) -> Result<(), SqlxError> {
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, message_prefix, message_suffix, updated_at)
+            VALUES ($1, $2, $3, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET message_prefix = $2, message_suffix = $3, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(prefix)
+        .bind(suffix)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Set the words forbidden in user-set channel names (an empty list clears the blacklist)
+    #[cfg(feature = "lobby")]
+    pub async fn set_channel_name_blacklist(
+        &self,
+        guild_id: GuildId,
+        words: &[String],
+    ) -> Result<(), SqlxError> {
+        let blacklist = (!words.is_empty()).then(|| words.join(","));
+        sqlx::query(
+            r#"
+            INSERT INTO guild_settings (guild_id, channel_name_blacklist, updated_at)
+            VALUES ($1, $2, NOW())
+            ON CONFLICT (guild_id)
+            DO UPDATE SET channel_name_blacklist = $2, updated_at = NOW()
+            "#,
+        )
+        .bind(guild_id.get() as i64)
+        .bind(blacklist)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    /// Get the settings of a guild (defaults when the guild has none)
+    pub async fn get_guild_settings(&self, guild_id: GuildId) -> Result<GuildSettings, SqlxError> {
+        let result: Option<GuildSettingsRow> = sqlx::query_as(
+            "SELECT timezone, message_prefix, message_suffix, empty_channel_grace_secs, \
+             channel_name_blacklist, max_persistent_channels, archive_category_base \
+             FROM guild_settings WHERE guild_id = $1",
         )
         .bind(guild_id.get() as i64)
         .fetch_optional(self.pool())
         .await?;
 
-        Ok(result.map(|(tz,)| tz).unwrap_or_else(|| "UTC".to_string()))
+        Ok(result
+            .map(
+                |(
+                    timezone,
+                    message_prefix,
+                    message_suffix,
+                    grace_secs,
+                    blacklist,
+                    max_persistent,
+                    archive_category_base,
+                )| GuildSettings {
+                    timezone,
+                    message_prefix,
+                    message_suffix,
+                    empty_channel_grace_secs: grace_secs.max(0) as u32,
+                    channel_name_blacklist: blacklist
+                        .map(|words| split_and_trim(&words, ','))
+                        .unwrap_or_default(),
+                    max_persistent_channels: max_persistent.max(0) as u32,
+                    archive_category_base,
+                },
+            )
+            .unwrap_or_default())
     }
 }
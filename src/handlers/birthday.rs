@@ -1,3 +1,4 @@
+use chrono::Datelike;
 use poise::serenity_prelude::{
     self as serenity, CreateInteractionResponse, CreateInteractionResponseMessage,
     EditInteractionResponse,
@@ -5,10 +6,13 @@ use poise::serenity_prelude::{
 use tracing::{error, info};
 
 use crate::models::{Data, Error};
-use crate::utils::datetime::{date_exists, get_month_name, is_valid_date};
+use crate::utils::datetime::{
+    get_month_name, parse_day, parse_month, parse_year, validate_birthday,
+};
 use crate::utils::channel_utils::format_birthday_display;
 use crate::utils::messages::{build_invalid_input_error, build_save_success, format_error};
 use crate::utils::string_utils::is_empty_or_whitespace;
+use crate::utils::timezone::resolve_timezone_input;
 
 /// Pure function: Extract input text from a modal component
 fn extract_input_value(
@@ -24,47 +28,55 @@ fn extract_input_value(
         })
 }
 
-/// Pure function: Parse and validate month
-fn parse_month(month_str: &str) -> Result<i32, String> {
-    month_str
-        .trim()
-        .parse::<i32>()
-        .ok()
-        .filter(|&m| (1..=12).contains(&m))
-        .ok_or_else(|| build_invalid_input_error("month", "a number between 1 and 12"))
-}
+/// Pure function: Parse and validate timezone (optional)
+fn parse_birthday_timezone(timezone_str: &str) -> Result<Option<String>, String> {
+    if is_empty_or_whitespace(timezone_str) {
+        return Ok(None);
+    }
 
-/// Pure function: Parse and validate day
-fn parse_day(day_str: &str) -> Result<i32, String> {
-    day_str
-        .trim()
-        .parse::<i32>()
-        .ok()
-        .filter(|&d| (1..=31).contains(&d))
-        .ok_or_else(|| build_invalid_input_error("day", "a number between 1 and 31"))
+    resolve_timezone_input(timezone_str).map(Some).ok_or_else(|| {
+        build_invalid_input_error(
+            "timezone",
+            "a timezone like Europe/Paris, a UTC offset like +02:00, or leave it empty",
+        )
+    })
 }
 
-/// Pure function: Parse and validate year (optional)
-fn parse_year(year_str: &str) -> Result<Option<i32>, String> {
-    if is_empty_or_whitespace(year_str) {
-        return Ok(None);
+/// Pure function: Parse whether the age is shown publicly (optional, defaults to yes)
+fn parse_show_age(show_age_str: &str) -> Result<bool, String> {
+    match show_age_str.trim().to_lowercase().as_str() {
+        "" | "yes" | "y" => Ok(true),
+        "no" | "n" => Ok(false),
+        _ => Err(build_invalid_input_error("answer", "yes or no, or leave it empty")),
     }
-    
-    year_str
-        .trim()
-        .parse::<i32>()
-        .ok()
-        .filter(|&y| y > 1900 && y <= 2100)
-        .map(Some)
-        .ok_or_else(|| build_invalid_input_error("year", "a valid year (1901-2100) or leave it empty"))
 }
 
 /// Handle the collect birthday button click
 pub async fn handle_collect_birthday_button(
     ctx: &serenity::Context,
     interaction: &serenity::ComponentInteraction,
-    _data: &Data,
+    data: &Data,
 ) -> Result<(), Error> {
+    // Prefill the timezone so submitting the modal keeps the one set with /my_timezone
+    let timezone = match data.db.get_user_timezone(interaction.user.id).await {
+        Ok(timezone) => timezone,
+        Err(e) => {
+            error!("Failed to get user timezone: {}", e);
+            None
+        }
+    };
+    let mut timezone_input = serenity::CreateInputText::new(
+        serenity::InputTextStyle::Short,
+        "Timezone (optional)",
+        "birth_timezone",
+    )
+    .placeholder("e.g., Europe/Paris or +02:00 (default: the server's)")
+    .required(false)
+    .max_length(64);
+    if let Some(timezone) = timezone {
+        timezone_input = timezone_input.value(timezone);
+    }
+
     // Show modal for birthday input
     let modal =
         serenity::CreateModal::new("birthday_modal", "🎂 Set Your Birthday").components(vec![
@@ -101,6 +113,17 @@ pub async fn handle_collect_birthday_button(
                 .min_length(4)
                 .max_length(4),
             ),
+            serenity::CreateActionRow::InputText(timezone_input),
+            serenity::CreateActionRow::InputText(
+                serenity::CreateInputText::new(
+                    serenity::InputTextStyle::Short,
+                    "Show your age publicly? (yes/no)",
+                    "birth_show_age",
+                )
+                .placeholder("yes (default) or no, your age stays visible to you")
+                .required(false)
+                .max_length(3),
+            ),
         ]);
 
     let response = CreateInteractionResponse::Modal(modal);
@@ -123,6 +146,8 @@ pub async fn handle_birthday_modal(
     let day_str = extract_input_value(components, 0).unwrap_or_default();
     let month_str = extract_input_value(components, 1).unwrap_or_default();
     let year_str = extract_input_value(components, 2).unwrap_or_default();
+    let timezone_str = extract_input_value(components, 3).unwrap_or_default();
+    let show_age_str = extract_input_value(components, 4).unwrap_or_default();
 
     // Parse and validate using pure functions
     let month = match parse_month(&month_str) {
@@ -151,7 +176,7 @@ pub async fn handle_birthday_modal(
         }
     };
 
-    let year = match parse_year(&year_str) {
+    let year = match parse_year(&year_str, chrono::Utc::now().year()) {
         Ok(y) => y,
         Err(err_msg) => {
             let response = CreateInteractionResponse::Message(
@@ -164,18 +189,37 @@ pub async fn handle_birthday_modal(
         }
     };
 
-    // Validate the date using the pure utility functions
-    let is_valid = if let Some(y) = year {
-        date_exists(y, month, day)
-    } else {
-        // For dates without year, validate month/day combination
-        is_valid_date(month, day)
+    let timezone = match parse_birthday_timezone(&timezone_str) {
+        Ok(tz) => tz,
+        Err(err_msg) => {
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(&err_msg)
+                    .ephemeral(true),
+            );
+            interaction.create_response(ctx, response).await?;
+            return Ok(());
+        }
     };
-    
-    if !is_valid {
+
+    let show_age = match parse_show_age(&show_age_str) {
+        Ok(show_age) => show_age,
+        Err(err_msg) => {
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(&err_msg)
+                    .ephemeral(true),
+            );
+            interaction.create_response(ctx, response).await?;
+            return Ok(());
+        }
+    };
+
+    // Validate the whole date, shared with the admin command
+    if let Err(err_msg) = validate_birthday(month, day, year, chrono::Utc::now().year()) {
         let response = CreateInteractionResponse::Message(
             CreateInteractionResponseMessage::new()
-                .content(format_error("Invalid date! Please check your month and day combination."))
+                .content(format_error(&err_msg))
                 .ephemeral(true),
         );
         interaction.create_response(ctx, response).await?;
@@ -192,8 +236,16 @@ pub async fn handle_birthday_modal(
         )
         .await?;
 
-    // Save to database
-    if let Err(e) = data.db.upsert_birthday(user_id, month, day, year).await {
+    // Save to database, the timezone is the user's own one also set with /my_timezone
+    let saved = match data.db.upsert_birthday(user_id, month, day, year).await {
+        Ok(()) => data.db.set_birthday_show_age(user_id, show_age).await,
+        Err(e) => Err(e),
+    };
+    let saved = match saved {
+        Ok(()) => data.db.set_user_timezone(user_id, timezone.as_deref()).await,
+        Err(e) => Err(e),
+    };
+    if let Err(e) = saved {
         error!("Failed to save birthday to database: {}", e);
         interaction
             .edit_response(
@@ -208,15 +260,18 @@ pub async fn handle_birthday_modal(
     // Format the birthday message using pure function
     let month_name = get_month_name(month);
     let date_display = format_birthday_display(day, month_name, year);
+    let timezone_display = timezone.as_deref().unwrap_or("the server's timezone");
 
     interaction
         .edit_response(
             ctx,
             EditInteractionResponse::new().content(format!(
-                "{}\n\nYour birthday: {}\n\n\
+                "{}\n\nYour birthday: {}\nCelebrated in: {}\nAge shown publicly: {}\n\n\
                 This will be used across all servers where this bot is present.",
                 build_save_success("Birthday"),
-                date_display
+                date_display,
+                timezone_display,
+                if show_age { "yes" } else { "no" }
             )),
         )
         .await?;
@@ -244,55 +299,24 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_month_valid() {
-        assert_eq!(parse_month("1"), Ok(1));
-        assert_eq!(parse_month("12"), Ok(12));
-        assert_eq!(parse_month("6"), Ok(6));
-        assert_eq!(parse_month(" 3 "), Ok(3)); // Test trimming
-    }
-
-    #[test]
-    fn test_parse_month_invalid() {
-        assert!(parse_month("0").is_err());
-        assert!(parse_month("13").is_err());
-        assert!(parse_month("-1").is_err());
-        assert!(parse_month("abc").is_err());
-        assert!(parse_month("").is_err());
-    }
-
-    #[test]
-    fn test_parse_day_valid() {
-        assert_eq!(parse_day("1"), Ok(1));
-        assert_eq!(parse_day("31"), Ok(31));
-        assert_eq!(parse_day("15"), Ok(15));
-        assert_eq!(parse_day(" 20 "), Ok(20)); // Test trimming
-    }
-
-    #[test]
-    fn test_parse_day_invalid() {
-        assert!(parse_day("0").is_err());
-        assert!(parse_day("32").is_err());
-        assert!(parse_day("-5").is_err());
-        assert!(parse_day("abc").is_err());
-        assert!(parse_day("").is_err());
-    }
-
-    #[test]
-    fn test_parse_year_valid() {
-        assert_eq!(parse_year("1995"), Ok(Some(1995)));
-        assert_eq!(parse_year("2000"), Ok(Some(2000)));
-        assert_eq!(parse_year("1901"), Ok(Some(1901)));
-        assert_eq!(parse_year("2100"), Ok(Some(2100)));
-        assert_eq!(parse_year(""), Ok(None)); // Empty is valid
-        assert_eq!(parse_year("  "), Ok(None)); // Whitespace only
+    fn test_parse_birthday_timezone() {
+        assert_eq!(parse_birthday_timezone(""), Ok(None));
+        assert_eq!(parse_birthday_timezone("  "), Ok(None));
+        assert_eq!(
+            parse_birthday_timezone("America/New_York"),
+            Ok(Some("America/New_York".to_string()))
+        );
+        assert!(parse_birthday_timezone("+05:30").is_ok_and(|tz| tz.is_some()));
+        assert!(parse_birthday_timezone("Atlantis").is_err());
     }
 
     #[test]
-    fn test_parse_year_invalid() {
-        assert!(parse_year("1900").is_err()); // Too old
-        assert!(parse_year("2101").is_err()); // Too new
-        assert!(parse_year("abc").is_err());
-        assert!(parse_year("99").is_err()); // Not 4 digits
+    fn test_parse_show_age() {
+        assert_eq!(parse_show_age(""), Ok(true));
+        assert_eq!(parse_show_age(" Yes "), Ok(true));
+        assert_eq!(parse_show_age("n"), Ok(false));
+        assert_eq!(parse_show_age("NO"), Ok(false));
+        assert!(parse_show_age("maybe").is_err());
     }
 
     #[test]
@@ -1,17 +1,33 @@
 use poise::serenity_prelude::{
-    self as serenity, ChannelId, ChannelType, CreateActionRow, CreateButton, CreateChannel,
-    CreateMessage, EditChannel, GetMessages, GuildId, Member, PermissionOverwrite,
-    PermissionOverwriteType, Permissions, UserId,
+    self as serenity, ChannelId, ChannelType, CreateActionRow, CreateChannel,
+    CreateMessage, CreateSelectMenu, CreateSelectMenuKind, CreateSelectMenuOption, EditChannel,
+    GetMessages, GuildId, Member, PermissionOverwrite, PermissionOverwriteType, Permissions,
+    UserId,
 };
+use chrono::Utc;
+use std::time::Duration;
 use tracing::{error, info, warn};
 
 use crate::{
-    constants::{ARCHIVE_CATEGORY_NAME, MAX_MESSAGE_SCAN},
+    constants::{
+        ARCHIVE_CATEGORY_NAME, KICK_COOLDOWN_SECS, MAX_CHANNEL_NAME_LENGTH, MAX_MESSAGE_SCAN,
+        RESTORE_OFFER_COOLDOWN_SECS,
+    },
     models::{Data, Error, TempChannel},
-    utils::channel_utils::format_temp_channel_name,
-    utils::messages::build_context_error,
+    utils::channel_utils::{
+        build_archive_category_name, build_archive_dm, build_restore_picker_id, build_restore_picker_message,
+        clamp_bitrate, format_temp_channel_name_from_template, max_bitrate_for_boost_tier,
+    },
+    utils::messages::{build_context_error, decorate_message},
+    utils::permissions::{
+        archive_denied_permissions, archive_overwrite_is_correct, build_restore_overwrites,
+        deny_member_connect, owner_channel_permissions, restore_member_connect, set_connect_locked,
+        transfer_owner_overwrite,
+    },
 };
 
+use super::interaction::build_channel_config_buttons;
+
 /// Create a temporary voice channel for a user
 pub async fn create_temp_channel(
     ctx: &serenity::Context,
@@ -21,7 +37,26 @@ pub async fn create_temp_channel(
     data: &Data,
 ) -> Result<(), Error> {
     let user_name = member.display_name();
-    let channel_name = format_temp_channel_name(&user_name);
+
+    let (user_limit, name_template, bitrate) = data
+        .lobby_channels
+        .get(&lobby_channel_id)
+        .map(|lobby| (lobby.user_limit, lobby.name_template.clone(), lobby.bitrate))
+        .unwrap_or_default();
+
+    // Position of the new channel among the lobby's active temp channels
+    let count = data
+        .temp_channels
+        .iter()
+        .filter(|tc| tc.lobby_channel_id == lobby_channel_id && !tc.is_archived)
+        .count()
+        + 1;
+    let channel_name = format_temp_channel_name_from_template(
+        name_template.as_deref(),
+        user_name,
+        count,
+        MAX_CHANNEL_NAME_LENGTH as usize,
+    );
 
     // Get the lobby channel to copy its category and permissions
     let lobby_channel = lobby_channel_id.to_channel(ctx).await?;
@@ -35,10 +70,7 @@ pub async fn create_temp_channel(
 
     // Add permission overwrite for the owner (additional permissions on top of inherited ones)
     let owner_permissions = PermissionOverwrite {
-        allow: Permissions::MANAGE_CHANNELS
-            | Permissions::MOVE_MEMBERS
-            | Permissions::MUTE_MEMBERS
-            | Permissions::DEAFEN_MEMBERS,
+        allow: owner_channel_permissions(),
         deny: Permissions::empty(),
         kind: PermissionOverwriteType::Member(member.user.id),
     };
@@ -54,8 +86,33 @@ pub async fn create_temp_channel(
         create_channel = create_channel.category(cat_id);
     }
 
+    // Apply the lobby's user limit, if any
+    if let Some(limit) = user_limit {
+        create_channel = create_channel.user_limit(limit);
+    }
+
+    // Apply the lobby's bitrate, clamped in case the guild lost boosts since it was set
+    // (unless its boost tier is unknown), or inherit the bitrate of the lobby channel itself
+    match bitrate {
+        Some(bitrate) => {
+            let bitrate = match guild_id.to_guild_cached(ctx).map(|guild| u8::from(guild.premium_tier)) {
+                Some(boost_tier) => clamp_bitrate(bitrate, max_bitrate_for_boost_tier(boost_tier)),
+                None => bitrate,
+            };
+            create_channel = create_channel.bitrate(bitrate);
+        }
+        None => {
+            if let Some(lobby_bitrate) = guild_channel.bitrate {
+                create_channel = create_channel.bitrate(lobby_bitrate);
+            }
+        }
+    }
+
     // Create the temporary channel
-    let temp_channel = guild_id.create_channel(ctx, create_channel).await?;
+    let temp_channel = guild_id
+        .create_channel(ctx, create_channel)
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
 
     // Store the temp channel in memory
     data.temp_channels.insert(
@@ -65,7 +122,10 @@ pub async fn create_temp_channel(
             lobby_channel_id,
             is_persistent: false,
             is_archived: false,
+            is_locked: false,
+            owner_left_at: None,
             guild_id,
+            created_at: Utc::now(),
         },
     );
 
@@ -78,13 +138,18 @@ pub async fn create_temp_channel(
         error!("Failed to save temp channel to database: {}", e);
     }
 
-    // Move the user to their new channel
-    guild_id
+    // Move the user to their new channel, an empty channel they aren't in is of no use
+    if let Err(e) = guild_id
         .move_member(ctx, member.user.id, temp_channel.id)
-        .await?;
+        .await
+    {
+        data.record_http_error(&e);
+        rollback_temp_channel(ctx, temp_channel.id, member.user.id, data).await;
+        return Err(e.into());
+    }
 
     // Send configuration message
-    send_channel_config_message(ctx, temp_channel.id, member, false).await?;
+    send_channel_config_message(ctx, temp_channel.id, member, false, data).await?;
 
     info!(
         "Created temp channel {} for user {} in guild {}",
@@ -94,15 +159,43 @@ pub async fn create_temp_channel(
     Ok(())
 }
 
-/// Delete a temporary channel and clean up
-pub async fn delete_temp_channel(
+/// Undo the creation of a temp channel whose owner couldn't be moved into it:
+/// delete it on Discord and forget it in memory and in the database
+async fn rollback_temp_channel(
     ctx: &serenity::Context,
     channel_id: ChannelId,
     owner_id: UserId,
     data: &Data,
 ) {
+    warn!(
+        "Rolling back temp channel {}: its owner {} could not be moved into it",
+        channel_id, owner_id
+    );
+
+    data.temp_channels.remove(&channel_id);
+    if let Err(e) = data.db.remove_temp_channel(channel_id).await {
+        error!("Rollback: failed to remove temp channel {} from database: {}", channel_id, e);
+    }
+
     if let Err(e) = channel_id.delete(ctx).await {
+        data.record_http_error(&e);
+        error!("Rollback: failed to delete temp channel {}: {}", channel_id, e);
+    } else {
+        info!("Rollback: deleted temp channel {}", channel_id);
+    }
+}
+
+/// Delete a temporary channel and clean up, returning whether the channel was deleted
+pub async fn delete_temp_channel(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    owner_id: UserId,
+    data: &Data,
+) -> bool {
+    if let Err(e) = channel_id.delete(ctx).await {
+        data.record_http_error(&e);
         error!("Failed to delete temp channel: {}", e);
+        false
     } else {
         data.temp_channels.remove(&channel_id);
         // Remove from database
@@ -110,34 +203,255 @@ pub async fn delete_temp_channel(
             error!("Failed to remove temp channel from database: {}", e);
         }
         info!(
-            "Deleted empty temp channel {} owned by {}",
+            "Deleted temp channel {} owned by {}",
             channel_id, owner_id
         );
+        true
     }
 }
 
-/// Send the configuration message with buttons in a voice channel
-pub async fn send_channel_config_message(
+/// Give a temp channel to another member, moving the owner permissions to them
+pub async fn transfer_channel_ownership(
     ctx: &serenity::Context,
     channel_id: ChannelId,
-    member: &Member,
-    is_persistent: bool,
+    new_owner_id: UserId,
+    data: &Data,
 ) -> Result<(), Error> {
-    let configure_button = CreateButton::new("configure_channel")
-        .label("⚙️ Configure Channel")
-        .style(serenity::ButtonStyle::Primary);
+    let old_owner_id = data
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| tc.owner_id)
+        .ok_or_else(|| build_context_error("in temp channels"))?;
+
+    let mut permissions = channel_id
+        .to_channel(ctx)
+        .await?
+        .guild()
+        .ok_or_else(|| build_context_error("as a guild channel"))?
+        .permission_overwrites;
+    transfer_owner_overwrite(&mut permissions, old_owner_id, new_owner_id);
 
-    let (persistent_label, persistent_style) = if is_persistent {
-        ("📌 Remove Persistent", serenity::ButtonStyle::Danger)
-    } else {
-        ("📌 Make Persistent", serenity::ButtonStyle::Secondary)
+    channel_id
+        .edit(ctx, EditChannel::new().permissions(permissions))
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    // Update in memory
+    if let Some(mut tc) = data.temp_channels.get_mut(&channel_id) {
+        tc.owner_id = new_owner_id;
+        tc.owner_left_at = None;
+    }
+
+    // Update in database
+    if let Err(e) = data.db.set_channel_owner(channel_id, new_owner_id).await {
+        error!("Failed to update channel owner in database: {}", e);
+    }
+
+    info!(
+        "Transferred temp channel {} from {} to {}",
+        channel_id, old_owner_id, new_owner_id
+    );
+
+    Ok(())
+}
+
+/// Disconnect a member from a temp channel and deny them `CONNECT` for a cooldown, so they
+/// can't rejoin right away. The kick is stored so it is lifted even after a restart.
+pub async fn kick_member_from_channel(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    data: &Data,
+) -> Result<(), Error> {
+    let guild_id = data
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| tc.guild_id)
+        .ok_or_else(|| build_context_error("in temp channels"))?;
+
+    let connect_allowed =
+        edit_member_overwrites(ctx, channel_id, data, |overwrites| deny_member_connect(overwrites, user_id))
+            .await?;
+    data.db
+        .add_channel_kick(channel_id, user_id, KICK_COOLDOWN_SECS, connect_allowed)
+        .await?;
+    guild_id
+        .disconnect_member(ctx, user_id)
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    info!("Kicked {} from temp channel {}", user_id, channel_id);
+
+    schedule_kick_lift(ctx, channel_id, user_id, Duration::from_secs(KICK_COOLDOWN_SECS), data);
+
+    Ok(())
+}
+
+/// Lift the kicks left pending by the previous run: expired ones right away,
+/// the others once their cooldown is over
+pub async fn resume_channel_kicks(ctx: &serenity::Context, data: &Data) {
+    let kicks = match data.db.get_all_channel_kicks().await {
+        Ok(kicks) => kicks,
+        Err(e) => {
+            error!("Failed to load channel kicks: {}", e);
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for (channel_id, kick) in &kicks {
+        let remaining = kick
+            .expires_at
+            .and_then(|expires_at| (expires_at - now).to_std().ok())
+            .unwrap_or_default();
+        schedule_kick_lift(ctx, *channel_id, kick.user_id, remaining, data);
+    }
+
+    info!("Resumed {} pending channel kick(s)", kicks.len());
+}
+
+/// Ban a member from a temp channel until the owner lifts it, disconnecting them if needed.
+/// The ban is stored so it is re-applied when the channel is restored from the archive.
+pub async fn ban_member_from_channel(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    data: &Data,
+) -> Result<(), Error> {
+    let guild_id = data
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| tc.guild_id)
+        .ok_or_else(|| build_context_error("in temp channels"))?;
+
+    let connect_allowed =
+        edit_member_overwrites(ctx, channel_id, data, |overwrites| deny_member_connect(overwrites, user_id))
+            .await?;
+    data.db.add_channel_ban(channel_id, user_id, connect_allowed).await?;
+
+    let is_connected = guild_id.to_guild_cached(ctx).is_some_and(|guild| {
+        guild
+            .voice_states
+            .get(&user_id)
+            .is_some_and(|state| state.channel_id == Some(channel_id))
+    });
+    if is_connected {
+        guild_id
+            .disconnect_member(ctx, user_id)
+            .await
+            .inspect_err(|e| data.record_http_error(e))?;
+    }
+
+    info!("Banned {} from temp channel {}", user_id, channel_id);
+
+    Ok(())
+}
+
+/// Lift the ban of a member from a temp channel (or their kick cooldown), returning whether
+/// they were banned
+pub async fn unban_member_from_channel(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    data: &Data,
+) -> Result<bool, Error> {
+    let Some(ban) = data.db.remove_channel_ban(channel_id, user_id).await? else {
+        return Ok(false);
     };
 
-    let persistent_button = CreateButton::new("toggle_persistent")
-        .label(persistent_label)
-        .style(persistent_style);
+    edit_member_overwrites(ctx, channel_id, data, |overwrites| {
+        restore_member_connect(overwrites, user_id, ban.connect_allowed)
+    })
+    .await?;
+
+    let was_banned = ban.expires_at.is_none();
+    if was_banned {
+        info!("Unbanned {} from temp channel {}", user_id, channel_id);
+    }
+
+    Ok(was_banned)
+}
+
+/// Lift a kick once its cooldown is over
+fn schedule_kick_lift(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    delay: Duration,
+    data: &Data,
+) {
+    let ctx = ctx.clone();
+    let data = data.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(delay).await;
+        lift_kick_cooldown(&ctx, channel_id, user_id, &data).await;
+    });
+}
 
-    let action_row = CreateActionRow::Buttons(vec![configure_button, persistent_button]);
+/// Allow a kicked member back into a temp channel, unless they were kicked again or banned since
+async fn lift_kick_cooldown(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    data: &Data,
+) {
+    let kick = match data.db.remove_expired_channel_kick(channel_id, user_id).await {
+        Ok(Some(kick)) => kick,
+        Ok(None) => return,
+        Err(e) => {
+            warn!("Failed to lift the kick of {} from temp channel {}: {}", user_id, channel_id, e);
+            return;
+        }
+    };
+
+    let restored = edit_member_overwrites(ctx, channel_id, data, |overwrites| {
+        restore_member_connect(overwrites, user_id, kick.connect_allowed)
+    })
+    .await;
+    if let Err(e) = restored {
+        warn!(
+            "Failed to lift the kick of {} from temp channel {}: {}",
+            user_id, channel_id, e
+        );
+    }
+}
+
+/// Change the permission overwrites of a channel, keeping the ones left untouched
+async fn edit_member_overwrites<T>(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    data: &Data,
+    change: impl FnOnce(&mut Vec<PermissionOverwrite>) -> T,
+) -> Result<T, Error> {
+    let mut permissions = channel_id
+        .to_channel(ctx)
+        .await?
+        .guild()
+        .ok_or_else(|| build_context_error("as a guild channel"))?
+        .permission_overwrites;
+    let result = change(&mut permissions);
+
+    channel_id
+        .edit(ctx, EditChannel::new().permissions(permissions))
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    Ok(result)
+}
+
+/// Send the configuration message with buttons in a voice channel
+pub async fn send_channel_config_message(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    member: &Member,
+    is_persistent: bool,
+    data: &Data,
+) -> Result<(), Error> {
+    let is_locked = data
+        .temp_channels
+        .get(&channel_id)
+        .is_some_and(|tc| tc.is_locked);
+    let action_row = build_channel_config_buttons(is_persistent, is_locked);
 
     let content = if is_persistent {
         format!(
@@ -154,11 +468,15 @@ pub async fn send_channel_config_message(
         )
     };
 
+    let settings = data.guild_settings_or_default(member.guild_id).await;
     let message = CreateMessage::new()
-        .content(content)
+        .content(decorate_message(&settings, &content))
         .components(vec![action_row]);
 
-    channel_id.send_message(ctx, message).await?;
+    channel_id
+        .send_message(ctx, message)
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
 
     Ok(())
 }
@@ -188,22 +506,30 @@ pub async fn get_or_create_archive_category(
         }
     }
 
-    // Create new archive category with no permissions (invisible to everyone)
+    // Create new archive category with no permissions (invisible to everyone),
+    // named after the guild's configured base name if any
+    let category_name = data
+        .guild_settings_or_default(guild_id)
+        .await
+        .archive_category_base
+        .map(|base| build_archive_category_name(&base))
+        .unwrap_or_else(|| ARCHIVE_CATEGORY_NAME.to_string());
     let everyone_role = guild_id.everyone_role();
     let deny_permissions = PermissionOverwrite {
         allow: Permissions::empty(),
-        deny: Permissions::VIEW_CHANNEL | Permissions::CONNECT,
+        deny: archive_denied_permissions(),
         kind: PermissionOverwriteType::Role(everyone_role),
     };
 
     let category = guild_id
         .create_channel(
             ctx,
-            CreateChannel::new(ARCHIVE_CATEGORY_NAME)
+            CreateChannel::new(category_name)
                 .kind(ChannelType::Category)
                 .permissions(vec![deny_permissions]),
         )
-        .await?;
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
 
     // Save to database and cache
     if let Err(e) = data.db.set_archive_category(guild_id, category.id).await {
@@ -219,6 +545,48 @@ pub async fn get_or_create_archive_category(
     Ok(category.id)
 }
 
+/// Re-apply the archive category's deny-everyone overwrite if it drifted
+async fn ensure_archive_category_hidden(
+    ctx: &serenity::Context,
+    category_id: ChannelId,
+    guild_id: GuildId,
+    data: &Data,
+) {
+    let everyone_role = guild_id.everyone_role();
+    let overwrites = match category_id.to_channel(ctx).await.map(|c| c.guild()) {
+        Ok(Some(category)) => category.permission_overwrites,
+        Ok(None) => return,
+        Err(e) => {
+            data.record_http_error(&e);
+            warn!("Failed to fetch archive category {}: {}", category_id, e);
+            return;
+        }
+    };
+
+    if archive_overwrite_is_correct(&overwrites, everyone_role) {
+        return;
+    }
+
+    let deny_permissions = PermissionOverwrite {
+        allow: Permissions::empty(),
+        deny: archive_denied_permissions(),
+        kind: PermissionOverwriteType::Role(everyone_role),
+    };
+    match category_id.create_permission(ctx, deny_permissions).await {
+        Ok(()) => warn!(
+            "Archive category {} in guild {} was visible, re-applied its deny-everyone overwrite",
+            category_id, guild_id
+        ),
+        Err(e) => {
+            data.record_http_error(&e);
+            error!(
+                "Failed to re-apply the deny-everyone overwrite on archive category {}: {}",
+                category_id, e
+            );
+        }
+    }
+}
+
 /// Archive a persistent channel by moving it to the archive category
 pub async fn archive_channel(
     ctx: &serenity::Context,
@@ -230,6 +598,9 @@ pub async fn archive_channel(
     // Get or create the archive category
     let archive_category_id = get_or_create_archive_category(ctx, guild_id, data).await?;
 
+    // Make sure the category is still hidden, an admin may have changed its permissions
+    ensure_archive_category_hidden(ctx, archive_category_id, guild_id, data).await;
+
     // Update the channel to be in the archive category with no visibility
     let everyone_role = guild_id.everyone_role();
     let deny_permissions = PermissionOverwrite {
@@ -245,7 +616,8 @@ pub async fn archive_channel(
                 .category(Some(archive_category_id))
                 .permissions(vec![deny_permissions]),
         )
-        .await?;
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
 
     // Update in memory
     if let Some(mut tc) = data.temp_channels.get_mut(&channel_id) {
@@ -263,21 +635,128 @@ pub async fn archive_channel(
     Ok(())
 }
 
+/// DM the owner of an archived channel with instructions to restore it, if enabled for the guild
+pub async fn send_archive_dm(
+    ctx: &serenity::Context,
+    owner_id: UserId,
+    guild_id: GuildId,
+    lobby_channel_id: ChannelId,
+    data: &Data,
+) {
+    match data.db.get_archive_dm_enabled(guild_id).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to get archive DM setting for guild {}: {}", guild_id, e);
+            return;
+        }
+    }
+
+    let message = CreateMessage::new().content(build_archive_dm(lobby_channel_id.get()));
+    if let Err(e) = owner_id.direct_message(ctx, message).await {
+        data.record_http_error(&e);
+        // Users commonly have DMs closed, this is not an error on our side
+        warn!("Failed to send archive DM to user {}: {}", owner_id, e);
+    }
+}
+
+/// DM a user a picker to restore their other archived channels, if enabled for the guild.
+/// The picker is offered at most once per cooldown, not on every lobby join.
+pub async fn offer_archived_channels_restore(
+    ctx: &serenity::Context,
+    user_id: UserId,
+    guild_id: GuildId,
+    data: &Data,
+) {
+    match data.db.get_restore_all_on_join(guild_id).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to get restore-all setting for guild {}: {}", guild_id, e);
+            return;
+        }
+    }
+
+    let archived = match data
+        .db
+        .get_all_archived_channels_for_user(guild_id, user_id)
+        .await
+    {
+        Ok(archived) => archived,
+        Err(e) => {
+            error!("Failed to get archived channels for user {}: {}", user_id, e);
+            return;
+        }
+    };
+    if archived.is_empty() {
+        return;
+    }
+
+    match data
+        .db
+        .claim_restore_offer(guild_id, user_id, RESTORE_OFFER_COOLDOWN_SECS)
+        .await
+    {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to record the restore offer of user {}: {}", user_id, e);
+            return;
+        }
+    }
+
+    // Select menus are limited to 25 options
+    let options: Vec<CreateSelectMenuOption> = {
+        let guild = ctx.cache.guild(guild_id);
+        archived
+            .iter()
+            .take(25)
+            .map(|channel_id| {
+                let name = guild
+                    .as_ref()
+                    .and_then(|g| g.channels.get(channel_id).map(|c| c.name.clone()))
+                    .unwrap_or_else(|| channel_id.to_string());
+                CreateSelectMenuOption::new(name, channel_id.to_string())
+            })
+            .collect()
+    };
+    let option_count = options.len() as u8;
+
+    let menu = CreateSelectMenu::new(
+        build_restore_picker_id(guild_id.get()),
+        CreateSelectMenuKind::String { options },
+    )
+    .placeholder("Channels to restore")
+    .min_values(1)
+    .max_values(option_count);
+
+    let message = CreateMessage::new()
+        .content(build_restore_picker_message(archived.len()))
+        .components(vec![CreateActionRow::SelectMenu(menu)]);
+    if let Err(e) = user_id.direct_message(ctx, message).await {
+        data.record_http_error(&e);
+        // Users commonly have DMs closed, this is not an error on our side
+        warn!("Failed to send restore picker to user {}: {}", user_id, e);
+    }
+}
+
 /// Restore an archived channel by moving it back and adding proper permissions
+/// When `move_member` is set, the owner is also moved into the restored channel
 pub async fn restore_archived_channel(
     ctx: &serenity::Context,
     member: &Member,
     guild_id: GuildId,
     channel_id: ChannelId,
+    move_member: bool,
     data: &Data,
 ) -> Result<(), Error> {
     // Get the temp channel info
-    let lobby_channel_id = {
+    let (lobby_channel_id, is_locked) = {
         let tc = data
             .temp_channels
             .get(&channel_id)
             .ok_or_else(|| build_context_error("in temp channels"))?;
-        tc.lobby_channel_id
+        (tc.lobby_channel_id, tc.is_locked)
     };
 
     // Get the lobby channel to copy its category and permissions
@@ -287,26 +766,61 @@ pub async fn restore_archived_channel(
         .ok_or_else(|| build_context_error("as a guild channel"))?;
     let category_id = guild_channel.parent_id;
 
-    // Get permission overwrites from the lobby channel
-    let mut permissions: Vec<PermissionOverwrite> = guild_channel.permission_overwrites.to_vec();
+    // Drop the archive's deny on @everyone and apply the lobby's overwrites
+    let archived_overwrites = match channel_id.to_channel(ctx).await {
+        Ok(channel) => channel
+            .guild()
+            .map(|channel| channel.permission_overwrites)
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!("Failed to get overwrites of archived channel {}: {}", channel_id, e);
+            Vec::new()
+        }
+    };
+    let mut permissions = build_restore_overwrites(
+        &archived_overwrites,
+        &guild_channel.permission_overwrites,
+        guild_id.everyone_role(),
+    );
 
     // Add permission overwrite for the owner
     let owner_permissions = PermissionOverwrite {
-        allow: Permissions::MANAGE_CHANNELS
-            | Permissions::MOVE_MEMBERS
-            | Permissions::MUTE_MEMBERS
-            | Permissions::DEAFEN_MEMBERS,
+        allow: owner_channel_permissions(),
         deny: Permissions::empty(),
         kind: PermissionOverwriteType::Member(member.user.id),
     };
     permissions.push(owner_permissions);
 
+    // Keep the channel locked if it was before being archived
+    if is_locked {
+        set_connect_locked(&mut permissions, guild_id.everyone_role(), true);
+    }
+
+    // Keep banned and kicked members out, even if the lobby's overwrites let them in
+    match data.db.get_channel_bans(channel_id).await {
+        Ok(bans) => {
+            for ban in bans {
+                deny_member_connect(&mut permissions, ban.user_id);
+            }
+        }
+        Err(e) => warn!("Failed to get bans of temp channel {}: {}", channel_id, e),
+    }
+
     // Move channel back to lobby's category with proper permissions
     let mut edit = EditChannel::new().permissions(permissions);
     if let Some(cat_id) = category_id {
         edit = edit.category(Some(cat_id));
     }
-    channel_id.edit(ctx, edit).await?;
+    // Re-apply the topic set by the owner
+    match data.db.get_channel_topic(channel_id).await {
+        Ok(Some(topic)) => edit = edit.topic(topic),
+        Ok(None) => {}
+        Err(e) => warn!("Failed to get topic of channel {}: {}", channel_id, e),
+    }
+    channel_id
+        .edit(ctx, edit)
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
 
     // Update in memory
     if let Some(mut tc) = data.temp_channels.get_mut(&channel_id) {
@@ -322,15 +836,17 @@ pub async fn restore_archived_channel(
     }
 
     // Move the user to their restored channel
-    guild_id
-        .move_member(ctx, member.user.id, channel_id)
-        .await?;
+    if move_member {
+        guild_id
+            .move_member(ctx, member.user.id, channel_id)
+            .await?;
+    }
 
     // Delete old bot messages that have buttons to keep chat clean
     clean_old_bot_messages(ctx, channel_id).await;
 
     // Send a welcome back message
-    send_channel_config_message(ctx, channel_id, member, true).await?;
+    send_channel_config_message(ctx, channel_id, member, true, data).await?;
 
     info!(
         "Restored archived channel {} for user {} in guild {}",
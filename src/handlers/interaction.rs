@@ -1,19 +1,31 @@
+use poise::serenity_prelude as serenity;
+use tracing::error;
+
+use crate::models::Data;
+
+#[cfg(feature = "birthday")]
+use super::birthday::{handle_birthday_modal, handle_collect_birthday_button};
+#[cfg(feature = "lobby")]
+use super::channel::restore_archived_channel;
+#[cfg(feature = "lobby")]
 use poise::serenity_prelude::{
-    self as serenity, CreateActionRow, CreateButton, CreateInteractionResponse,
-    CreateInteractionResponseMessage, EditChannel, EditInteractionResponse,
+    CreateActionRow, CreateButton, CreateInteractionResponse, CreateInteractionResponseMessage, EditChannel,
+    EditInteractionResponse,
 };
-use tracing::{error, info};
-
+#[cfg(feature = "lobby")]
+use tracing::info;
+#[cfg(feature = "lobby")]
 use crate::{
-    constants::MAX_CHANNEL_NAME_LENGTH,
-    models::{Data, Error},
-    utils::string_utils::{is_empty_or_whitespace, take_chars},
+    constants::{MAX_CHANNEL_NAME_LENGTH, MAX_CHANNEL_TOPIC_LENGTH},
+    models::Error,
     utils::messages::{build_context_error, format_error, format_success},
-    utils::channel_utils::is_valid_channel_name,
+    utils::channel_utils::{
+        has_reached_persistent_cap, is_valid_channel_topic, parse_restore_picker_id, parse_user_limit_input,
+        sanitize_channel_name,
+    },
+    utils::permissions::set_connect_locked,
 };
 
-use super::birthday::handle_collect_birthday_button;
-
 /// Handle component interactions (button clicks)
 pub async fn handle_interaction(
     ctx: &serenity::Context,
@@ -21,26 +33,152 @@ pub async fn handle_interaction(
     data: &Data,
 ) {
     match interaction.data.custom_id.as_str() {
+        #[cfg(feature = "lobby")]
         "configure_channel" => {
             if let Err(e) = handle_configure_button(ctx, &interaction, data).await {
                 error!("Failed to handle configure button: {}", e);
             }
         }
+        #[cfg(feature = "lobby")]
         "toggle_persistent" => {
             if let Err(e) = handle_toggle_persistent_button(ctx, &interaction, data).await {
                 error!("Failed to handle toggle persistent button: {}", e);
             }
         }
+        #[cfg(feature = "lobby")]
+        "toggle_lock" => {
+            if let Err(e) = handle_toggle_lock_button(ctx, &interaction, data).await {
+                error!("Failed to handle toggle lock button: {}", e);
+            }
+        }
+        #[cfg(feature = "lobby")]
+        "set_user_limit" => {
+            if let Err(e) = handle_user_limit_button(ctx, &interaction, data).await {
+                error!("Failed to handle user limit button: {}", e);
+            }
+        }
+        #[cfg(feature = "birthday")]
         "collect_birthday" => {
             if let Err(e) = handle_collect_birthday_button(ctx, &interaction, data).await {
                 error!("Failed to handle collect birthday button: {}", e);
             }
         }
+        #[cfg(feature = "lobby")]
+        custom_id if let Some(guild_id) = parse_restore_picker_id(custom_id) => {
+            let guild_id = serenity::GuildId::new(guild_id);
+            if let Err(e) = handle_restore_picker(ctx, &interaction, guild_id, data).await {
+                error!("Failed to handle restore picker: {}", e);
+            }
+        }
         _ => {}
     }
 }
 
+/// Build the row of buttons shown in a temp channel's configuration message
+#[cfg(feature = "lobby")]
+pub(crate) fn build_channel_config_buttons(is_persistent: bool, is_locked: bool) -> CreateActionRow {
+    let configure_button = CreateButton::new("configure_channel")
+        .label("⚙️ Configure Channel")
+        .style(serenity::ButtonStyle::Primary);
+
+    let (persistent_label, persistent_style) = if is_persistent {
+        ("📌 Remove Persistent", serenity::ButtonStyle::Danger)
+    } else {
+        ("📌 Make Persistent", serenity::ButtonStyle::Secondary)
+    };
+    let persistent_button = CreateButton::new("toggle_persistent")
+        .label(persistent_label)
+        .style(persistent_style);
+
+    let user_limit_button = CreateButton::new("set_user_limit")
+        .label("👥 User Limit")
+        .style(serenity::ButtonStyle::Secondary);
+
+    let (lock_label, lock_style) = if is_locked {
+        ("🔓 Unlock", serenity::ButtonStyle::Danger)
+    } else {
+        ("🔒 Lock", serenity::ButtonStyle::Secondary)
+    };
+    let lock_button = CreateButton::new("toggle_lock")
+        .label(lock_label)
+        .style(lock_style);
+
+    CreateActionRow::Buttons(vec![
+        configure_button,
+        persistent_button,
+        user_limit_button,
+        lock_button,
+    ])
+}
+
+/// Handle the archived channel restore picker sent in DMs
+#[cfg(feature = "lobby")]
+async fn handle_restore_picker(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    guild_id: serenity::GuildId,
+    data: &Data,
+) -> Result<(), Error> {
+    let user_id = interaction.user.id;
+    let selected: Vec<serenity::ChannelId> = match &interaction.data.kind {
+        serenity::ComponentInteractionDataKind::StringSelect { values } => values
+            .iter()
+            .filter_map(|value| value.parse::<u64>().ok())
+            .map(serenity::ChannelId::new)
+            .collect(),
+        _ => return Ok(()),
+    };
+
+    // Restoring several channels can take longer than the interaction deadline
+    interaction.defer(ctx).await?;
+
+    let member = guild_id.member(ctx, user_id).await?;
+
+    let mut restored = Vec::new();
+    let mut failed = 0;
+    for channel_id in selected {
+        // The picker may be stale, only restore channels that are still archived and owned by the user
+        let still_archived = data.temp_channels.get(&channel_id).is_some_and(|tc| {
+            tc.guild_id == guild_id && tc.owner_id == user_id && tc.is_archived
+        });
+        if !still_archived {
+            continue;
+        }
+
+        match restore_archived_channel(ctx, &member, guild_id, channel_id, false, data).await {
+            Ok(()) => restored.push(format!("<#{}>", channel_id)),
+            Err(e) => {
+                error!("Failed to restore archived channel {}: {}", channel_id, e);
+                failed += 1;
+            }
+        }
+    }
+
+    let content = match (restored.is_empty(), failed) {
+        (true, 0) => format_error("None of these channels are archived anymore."),
+        (true, _) => format_error("Failed to restore your channels!"),
+        (false, 0) => format_success(&format!("Restored {}.", restored.join(", "))),
+        (false, _) => format_success(&format!(
+            "Restored {}, {} could not be restored.",
+            restored.join(", "),
+            failed
+        )),
+    };
+
+    interaction
+        .edit_response(
+            ctx,
+            EditInteractionResponse::new()
+                .content(content)
+                .components(Vec::new()),
+        )
+        .await?;
+
+    Ok(())
+}
+
 /// Handle the configure channel button
+#[cfg(feature = "lobby")]
 async fn handle_configure_button(
     ctx: &serenity::Context,
     interaction: &serenity::ComponentInteraction,
@@ -71,6 +209,55 @@ async fn handle_configure_button(
             .placeholder("Enter a new name for your channel")
             .required(true)
             .max_length(MAX_CHANNEL_NAME_LENGTH),
+        ),
+        serenity::CreateActionRow::InputText(
+            serenity::CreateInputText::new(
+                serenity::InputTextStyle::Paragraph,
+                "Topic",
+                "channel_topic",
+            )
+            .placeholder("What is this room about? (leave empty to clear)")
+            .required(false)
+            .max_length(MAX_CHANNEL_TOPIC_LENGTH),
+        )]);
+
+    let response = CreateInteractionResponse::Modal(modal);
+    interaction.create_response(ctx, response).await?;
+
+    Ok(())
+}
+
+/// Handle the user limit button
+#[cfg(feature = "lobby")]
+async fn handle_user_limit_button(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let channel_id = interaction.channel_id;
+    let user_id = interaction.user.id;
+
+    // Check if this is a temp channel and the user is the owner
+    if !data.is_channel_owner(channel_id, user_id) {
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(format_error("Only the channel owner can configure this channel!"))
+                .ephemeral(true),
+        );
+        interaction.create_response(ctx, response).await?;
+        return Ok(());
+    }
+
+    let modal = serenity::CreateModal::new("user_limit_modal", "Set User Limit")
+        .components(vec![serenity::CreateActionRow::InputText(
+            serenity::CreateInputText::new(
+                serenity::InputTextStyle::Short,
+                "User Limit",
+                "user_limit",
+            )
+            .placeholder("0 for unlimited, up to 99")
+            .required(true)
+            .max_length(2),
         )]);
 
     let response = CreateInteractionResponse::Modal(modal);
@@ -80,6 +267,7 @@ async fn handle_configure_button(
 }
 
 /// Handle the toggle persistent button
+#[cfg(feature = "lobby")]
 async fn handle_toggle_persistent_button(
     ctx: &serenity::Context,
     interaction: &serenity::ComponentInteraction,
@@ -171,6 +359,28 @@ async fn handle_toggle_persistent_button(
             interaction.create_response(ctx, response).await?;
             return Ok(());
         }
+
+        // Check the guild's cap on persistent channels per user, across all lobbies
+        let max_channels = data.guild_settings_or_default(guild_id).await.max_persistent_channels;
+        if max_channels > 0 {
+            let owned = data
+                .db
+                .count_persistent_channels_for_user(guild_id, user_id)
+                .await?;
+            if has_reached_persistent_cap(owned, max_channels) {
+                let response = CreateInteractionResponse::Message(
+                    CreateInteractionResponseMessage::new()
+                        .content(format_error(&format!(
+                            "You already have {} persistent channel(s), the maximum on this server is {}! \
+                            Please disable persistence on one of your other channels first.",
+                            owned, max_channels
+                        )))
+                        .ephemeral(true),
+                );
+                interaction.create_response(ctx, response).await?;
+                return Ok(());
+            }
+        }
     }
 
     // Update in memory
@@ -188,37 +398,25 @@ async fn handle_toggle_persistent_button(
     }
 
     // Send response
-    let (message, button_label, button_style) = if new_persistent_state {
-        (
-            format_success(
-                "**Channel is now persistent!**\n\n\
-                When everyone leaves, this channel will be archived instead of deleted.\n\
-                When you join the lobby again, your channel will be restored."
-            ),
-            "📌 Remove Persistent",
-            serenity::ButtonStyle::Danger,
+    let message = if new_persistent_state {
+        format_success(
+            "**Channel is now persistent!**\n\n\
+            When everyone leaves, this channel will be archived instead of deleted.\n\
+            When you join the lobby again, your channel will be restored."
         )
     } else {
-        (
-            format_success(
-                "**Channel is no longer persistent.**\n\n\
-                When everyone leaves, this channel will be deleted."
-            ),
-            "📌 Make Persistent",
-            serenity::ButtonStyle::Secondary,
+        format_success(
+            "**Channel is no longer persistent.**\n\n\
+            When everyone leaves, this channel will be deleted."
         )
     };
 
     // Update the message with new button state
-    let configure_button = CreateButton::new("configure_channel")
-        .label("⚙️ Configure Channel")
-        .style(serenity::ButtonStyle::Primary);
-
-    let persistent_button = CreateButton::new("toggle_persistent")
-        .label(button_label)
-        .style(button_style);
-
-    let action_row = CreateActionRow::Buttons(vec![configure_button, persistent_button]);
+    let is_locked = data
+        .temp_channels
+        .get(&channel_id)
+        .is_some_and(|tc| tc.is_locked);
+    let action_row = build_channel_config_buttons(new_persistent_state, is_locked);
 
     let response = CreateInteractionResponse::UpdateMessage(
         CreateInteractionResponseMessage::new()
@@ -235,25 +433,128 @@ async fn handle_toggle_persistent_button(
     Ok(())
 }
 
+/// Handle the toggle lock button
+#[cfg(feature = "lobby")]
+async fn handle_toggle_lock_button(
+    ctx: &serenity::Context,
+    interaction: &serenity::ComponentInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let channel_id = interaction.channel_id;
+    let user_id = interaction.user.id;
+
+    // Check if this is a temp channel and the user is the owner
+    if !data.is_channel_owner(channel_id, user_id) {
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(format_error("Only the channel owner can lock this channel!"))
+                .ephemeral(true),
+        );
+        interaction.create_response(ctx, response).await?;
+        return Ok(());
+    }
+
+    let (is_persistent, is_currently_locked, guild_id) = data
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| (tc.is_persistent, tc.is_locked, tc.guild_id))
+        .ok_or_else(|| build_context_error("in temp channels"))?;
+
+    let new_locked_state = !is_currently_locked;
+
+    // Only touch CONNECT on @everyone, members already inside stay connected
+    let mut permissions = channel_id
+        .to_channel(ctx)
+        .await?
+        .guild()
+        .ok_or_else(|| build_context_error("as a guild channel"))?
+        .permission_overwrites;
+    set_connect_locked(&mut permissions, guild_id.everyone_role(), new_locked_state);
+
+    channel_id
+        .edit(ctx, EditChannel::new().permissions(permissions))
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    // Update in memory
+    if let Some(mut tc) = data.temp_channels.get_mut(&channel_id) {
+        tc.is_locked = new_locked_state;
+    }
+
+    // Update in database
+    if let Err(e) = data.db.set_channel_locked(channel_id, new_locked_state).await {
+        error!("Failed to update channel lock in database: {}", e);
+    }
+
+    let message = if new_locked_state {
+        format_success(
+            "**Channel is now locked.**\n\n\
+            Nobody else can join, members already inside can stay."
+        )
+    } else {
+        format_success("**Channel is unlocked.**\n\nAnyone can join again.")
+    };
+
+    let response = CreateInteractionResponse::UpdateMessage(
+        CreateInteractionResponseMessage::new()
+            .content(message)
+            .components(vec![build_channel_config_buttons(is_persistent, new_locked_state)]),
+    );
+    interaction.create_response(ctx, response).await?;
+
+    info!(
+        "User {} set channel {} lock to {}",
+        user_id, channel_id, new_locked_state
+    );
+
+    Ok(())
+}
+
 /// Handle modal submissions
 pub async fn handle_modal_submit(
     ctx: &serenity::Context,
     interaction: serenity::ModalInteraction,
     data: &Data,
 ) {
-    if interaction.data.custom_id == "channel_config_modal"
-        && let Err(e) = handle_channel_config_modal(ctx, &interaction, data).await
-    {
-        error!("Failed to handle modal submission: {}", e);
-    } else if interaction.data.custom_id == "birthday_modal" {
-        use super::birthday::handle_birthday_modal;
-        if let Err(e) = handle_birthday_modal(ctx, &interaction, data).await {
-            error!("Failed to handle birthday modal: {}", e);
+    match interaction.data.custom_id.as_str() {
+        #[cfg(feature = "lobby")]
+        "channel_config_modal" => {
+            if let Err(e) = handle_channel_config_modal(ctx, &interaction, data).await {
+                error!("Failed to handle modal submission: {}", e);
+            }
+        }
+        #[cfg(feature = "lobby")]
+        "user_limit_modal" => {
+            if let Err(e) = handle_user_limit_modal(ctx, &interaction, data).await {
+                error!("Failed to handle user limit modal: {}", e);
+            }
+        }
+        #[cfg(feature = "birthday")]
+        "birthday_modal" => {
+            if let Err(e) = handle_birthday_modal(ctx, &interaction, data).await {
+                error!("Failed to handle birthday modal: {}", e);
+            }
         }
+        _ => {}
     }
 }
 
+/// Extract the value of the text input in the given row of a modal submission
+#[cfg(feature = "lobby")]
+fn modal_input_value(interaction: &serenity::ModalInteraction, index: usize) -> Option<String> {
+    interaction
+        .data
+        .components
+        .get(index)
+        .and_then(|row| row.components.first())
+        .and_then(|component| match component {
+            serenity::ActionRowComponent::InputText(input) => input.value.clone(),
+            _ => None,
+        })
+}
+
 /// Handle the channel configuration modal submission
+#[cfg(feature = "lobby")]
 async fn handle_channel_config_modal(
     ctx: &serenity::Context,
     interaction: &serenity::ModalInteraction,
@@ -273,31 +574,32 @@ async fn handle_channel_config_modal(
         return Ok(());
     }
 
-    // Get the new channel name from the modal
-    let new_name = interaction
-        .data
-        .components
-        .first()
-        .and_then(|row| row.components.first())
-        .and_then(|component| match component {
-            serenity::ActionRowComponent::InputText(input) => input.value.clone(),
-            _ => None,
-        })
-        .unwrap_or_default();
+    // Get the new channel name and topic from the modal
+    let new_name = modal_input_value(interaction, 0).unwrap_or_default();
+    let new_topic = modal_input_value(interaction, 1).unwrap_or_default();
+    let new_topic = new_topic.trim();
 
-    // Validate and sanitize the channel name
-    if is_empty_or_whitespace(&new_name) {
-        let response = CreateInteractionResponse::Message(
-            CreateInteractionResponseMessage::new()
-                .content(format_error("Channel name cannot be empty!"))
-                .ephemeral(true),
-        );
-        interaction.create_response(ctx, response).await?;
-        return Ok(());
-    }
+    // Validate the channel name against the guild's word blacklist and sanitize it
+    // to what Discord will actually store
+    let blacklist = match interaction.guild_id {
+        Some(guild_id) => data.guild_settings_or_default(guild_id).await.channel_name_blacklist,
+        None => Vec::new(),
+    };
+    let sanitized_name = match sanitize_channel_name(&new_name, &blacklist) {
+        Ok(name) => name,
+        Err(validation_error) => {
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(format_error(validation_error))
+                    .ephemeral(true),
+            );
+            interaction.create_response(ctx, response).await?;
+            return Ok(());
+        }
+    };
     
-    // Validate channel name
-    if let Err(validation_error) = is_valid_channel_name(&new_name) {
+    // Validate channel topic
+    if let Err(validation_error) = is_valid_channel_topic(new_topic) {
         let response = CreateInteractionResponse::Message(
             CreateInteractionResponseMessage::new()
                 .content(format_error(validation_error))
@@ -306,9 +608,6 @@ async fn handle_channel_config_modal(
         interaction.create_response(ctx, response).await?;
         return Ok(());
     }
-    
-    // Truncate to max length
-    let sanitized_name = take_chars(&new_name, MAX_CHANNEL_NAME_LENGTH as usize);
 
     // Defer the response first
     interaction
@@ -320,10 +619,17 @@ async fn handle_channel_config_modal(
         )
         .await?;
 
-    // Update the channel name
+    // Update the channel name and topic (an empty topic clears it)
     channel_id
-        .edit(ctx, EditChannel::new().name(&sanitized_name))
-        .await?;
+        .edit(ctx, EditChannel::new().name(&sanitized_name).topic(new_topic))
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    // Store the topic so it can be re-applied when the channel is restored
+    let stored_topic = (!new_topic.is_empty()).then(|| new_topic.to_string());
+    if let Err(e) = data.db.set_channel_topic(channel_id, stored_topic).await {
+        error!("Failed to save channel topic to database: {}", e);
+    }
 
     // Send follow-up response
     interaction
@@ -341,3 +647,65 @@ async fn handle_channel_config_modal(
 
     Ok(())
 }
+
+/// Handle the user limit modal submission
+#[cfg(feature = "lobby")]
+async fn handle_user_limit_modal(
+    ctx: &serenity::Context,
+    interaction: &serenity::ModalInteraction,
+    data: &Data,
+) -> Result<(), Error> {
+    let channel_id = interaction.channel_id;
+    let user_id = interaction.user.id;
+
+    // Verify ownership
+    if !data.is_channel_owner(channel_id, user_id) {
+        let response = CreateInteractionResponse::Message(
+            CreateInteractionResponseMessage::new()
+                .content(format_error("Only the channel owner can configure this channel!"))
+                .ephemeral(true),
+        );
+        interaction.create_response(ctx, response).await?;
+        return Ok(());
+    }
+
+    let input = modal_input_value(interaction, 0).unwrap_or_default();
+    let user_limit = match parse_user_limit_input(&input) {
+        Ok(limit) => limit,
+        Err(validation_error) => {
+            let response = CreateInteractionResponse::Message(
+                CreateInteractionResponseMessage::new()
+                    .content(format_error(validation_error))
+                    .ephemeral(true),
+            );
+            interaction.create_response(ctx, response).await?;
+            return Ok(());
+        }
+    };
+
+    // Discord treats a limit of 0 as unlimited
+    channel_id
+        .edit(ctx, EditChannel::new().user_limit(user_limit))
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    let message = if user_limit == 0 {
+        "User limit removed, anyone can join.".to_string()
+    } else {
+        format!("User limit set to **{}**.", user_limit)
+    };
+
+    let response = CreateInteractionResponse::Message(
+        CreateInteractionResponseMessage::new()
+            .content(format_success(&message))
+            .ephemeral(true),
+    );
+    interaction.create_response(ctx, response).await?;
+
+    info!(
+        "User {} set temp channel {} user limit to {}",
+        user_id, channel_id, user_limit
+    );
+
+    Ok(())
+}
@@ -1,9 +1,19 @@
 /// Handler modules for Discord events and interactions
+#[cfg(feature = "lobby")]
 mod voice;
+#[cfg(feature = "lobby")]
 mod channel;
 mod interaction;
+#[cfg(feature = "birthday")]
 mod birthday;
 
 // Re-export main handler functions
+#[cfg(feature = "lobby")]
 pub use voice::handle_voice_state_update;
 pub use interaction::{handle_interaction, handle_modal_submit};
+#[cfg(feature = "lobby")]
+pub use channel::{
+    ban_member_from_channel, delete_temp_channel, kick_member_from_channel,
+    restore_archived_channel, resume_channel_kicks, send_channel_config_message, transfer_channel_ownership,
+    unban_member_from_channel,
+};
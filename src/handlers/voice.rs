@@ -1,9 +1,13 @@
 use poise::serenity_prelude::{self as serenity, ChannelId, GuildId, UserId, VoiceState};
+use std::time::{Duration, Instant};
 use tracing::{error, info};
 
 use crate::models::Data;
 
-use super::channel::{create_temp_channel, delete_temp_channel, restore_archived_channel};
+use super::channel::{
+    archive_channel, create_temp_channel, delete_temp_channel, offer_archived_channels_restore,
+    restore_archived_channel, send_archive_dm,
+};
 
 /// Handle voice state updates (user joins/leaves voice channels)
 pub async fn handle_voice_state_update(
@@ -21,7 +25,7 @@ pub async fn handle_voice_state_update(
     if let Some(old) = &old_state
         && let Some(old_channel_id) = old.channel_id
     {
-        handle_user_left_channel(ctx, old_channel_id, data).await;
+        handle_user_left_channel(ctx, old_channel_id, old.user_id, data).await;
     }
 
     // Handle user joining a channel
@@ -31,42 +35,90 @@ pub async fn handle_voice_state_update(
 }
 
 /// Handle a user leaving a voice channel
-async fn handle_user_left_channel(ctx: &serenity::Context, channel_id: ChannelId, data: &Data) {
+async fn handle_user_left_channel(
+    ctx: &serenity::Context,
+    channel_id: ChannelId,
+    user_id: UserId,
+    data: &Data,
+) {
     // Check if the user left a temporary channel
+    let temp_channel_info = data
+        .temp_channels
+        .get(&channel_id)
+        .map(|tc| (tc.owner_id, tc.guild_id));
+
+    if let Some((owner_id, channel_guild_id)) = temp_channel_info {
+        // Start the grace period after which members can claim the channel
+        if user_id == owner_id
+            && let Some(mut tc) = data.temp_channels.get_mut(&channel_id)
+        {
+            tc.owner_left_at = Some(Instant::now());
+        }
+
+        // Give members a chance to come back, e.g. after a connection drop
+        let grace_secs = data
+            .guild_settings_or_default(channel_guild_id)
+            .await
+            .empty_channel_grace_secs;
+        if grace_secs > 0 && is_channel_empty(ctx, channel_id).await {
+            tokio::time::sleep(Duration::from_secs(grace_secs as u64)).await;
+        }
+
+        cleanup_empty_temp_channel(ctx, channel_id, data).await;
+    }
+}
+
+/// Check if nobody is connected to a voice channel
+async fn is_channel_empty(ctx: &serenity::Context, channel_id: ChannelId) -> bool {
+    if let Ok(channel) = channel_id.to_channel(ctx).await
+        && let Some(guild_channel) = channel.guild()
+        && let Ok(members) = guild_channel.members(ctx)
+    {
+        members.is_empty()
+    } else {
+        false
+    }
+}
+
+/// Archive or delete a temp channel if it is (still) empty
+async fn cleanup_empty_temp_channel(ctx: &serenity::Context, channel_id: ChannelId, data: &Data) {
+    // Re-read the channel, it may have been cleaned up during the grace period
     let temp_channel_info = data.temp_channels.get(&channel_id).map(|tc| {
         (
             tc.owner_id,
             tc.is_persistent,
+            tc.is_archived,
             tc.lobby_channel_id,
             tc.guild_id,
         )
     });
 
-    if let Some((owner_id, is_persistent, lobby_channel_id, channel_guild_id)) = temp_channel_info {
-        // Check if channel is empty
-        if let Ok(channel) = channel_id.to_channel(ctx).await
-            && let Some(guild_channel) = channel.guild()
-            && let Ok(members) = guild_channel.members(ctx)
-            && members.is_empty()
+    let Some((owner_id, is_persistent, is_archived, lobby_channel_id, channel_guild_id)) =
+        temp_channel_info
+    else {
+        return;
+    };
+
+    if is_archived || !is_channel_empty(ctx, channel_id).await {
+        return;
+    }
+
+    if is_persistent {
+        // Archive the channel instead of deleting
+        if let Err(e) =
+            archive_channel(ctx, channel_id, channel_guild_id, lobby_channel_id, data).await
         {
-            if is_persistent {
-                // Archive the channel instead of deleting
-                use super::channel::archive_channel;
-                if let Err(e) =
-                    archive_channel(ctx, channel_id, channel_guild_id, lobby_channel_id, data).await
-                {
-                    error!("Failed to archive channel: {}", e);
-                } else {
-                    info!(
-                        "Archived persistent channel {} owned by {}",
-                        channel_id, owner_id
-                    );
-                }
-            } else {
-                // Delete the empty temporary channel
-                delete_temp_channel(ctx, channel_id, owner_id, data).await;
-            }
+            error!("Failed to archive channel: {}", e);
+        } else {
+            info!(
+                "Archived persistent channel {} owned by {}",
+                channel_id, owner_id
+            );
+            send_archive_dm(ctx, owner_id, channel_guild_id, lobby_channel_id, data).await;
         }
+    } else {
+        // Delete the empty temporary channel
+        delete_temp_channel(ctx, channel_id, owner_id, data).await;
     }
 }
 
@@ -78,6 +130,13 @@ async fn handle_user_joined_channel(
     guild_id: GuildId,
     data: &Data,
 ) {
+    // The owner is back, members can no longer claim their channel
+    if let Some(mut tc) = data.temp_channels.get_mut(&channel_id)
+        && tc.owner_id == user_id
+    {
+        tc.owner_left_at = None;
+    }
+
     // Check if user joined a lobby channel
     if data.lobby_channels.contains_key(&channel_id) {
         let member = match guild_id.member(ctx, user_id).await {
@@ -96,8 +155,15 @@ async fn handle_user_joined_channel(
         {
             Ok(Some(archived_channel_id)) => {
                 // Restore the archived channel
-                match restore_archived_channel(ctx, &member, guild_id, archived_channel_id, data)
-                    .await
+                match restore_archived_channel(
+                    ctx,
+                    &member,
+                    guild_id,
+                    archived_channel_id,
+                    true,
+                    data,
+                )
+                .await
                 {
                     Ok(_) => {
                         // Successfully restored
@@ -145,5 +211,8 @@ async fn handle_user_joined_channel(
                 }
             }
         }
+
+        // Offer to restore the user's archived channels from other lobbies too
+        offer_archived_channels_restore(ctx, user_id, guild_id, data).await;
     }
 }
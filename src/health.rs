@@ -0,0 +1,110 @@
+/// Minimal HTTP health endpoint for container orchestration (liveness/readiness probes)
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::{Duration, timeout};
+use tracing::{debug, error, info};
+
+use crate::models::Data;
+
+/// How long a probe may take to send its request or wait for the database
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Start the health server on the given port. Every request gets a 200 when the bot is
+/// connected to Discord and the database answers, a 503 otherwise.
+pub fn start_health_server(port: u16, data: Arc<Data>) {
+    tokio::spawn(async move {
+        let listener = match TcpListener::bind(("0.0.0.0", port)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                error!("Failed to start the health server on port {}: {}", port, e);
+                return;
+            }
+        };
+        info!("Health server listening on port {}", port);
+
+        let mut shutdown_rx = data.shutdown_tx.subscribe();
+        loop {
+            tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let data = Arc::clone(&data);
+                        tokio::spawn(async move { answer_probe(stream, &data).await });
+                    }
+                    Err(e) => error!("Failed to accept a health probe: {}", e),
+                },
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+
+        info!("Health server stopped");
+    });
+}
+
+/// Answer a single probe with the current health of the bot
+async fn answer_probe(mut stream: TcpStream, data: &Data) {
+    // The request itself doesn't matter, every path reports the same status
+    let mut request = [0u8; 1024];
+    if timeout(HEALTH_CHECK_TIMEOUT, stream.read(&mut request)).await.is_err() {
+        return;
+    }
+
+    let connected = data.is_gateway_connected();
+    let database_ok = matches!(
+        timeout(HEALTH_CHECK_TIMEOUT, data.db.ping()).await,
+        Ok(Ok(()))
+    );
+    if !connected || !database_ok {
+        debug!(
+            "Health probe failed (gateway connected: {}, database ok: {})",
+            connected, database_ok
+        );
+    }
+
+    let response = build_health_response(connected, database_ok);
+    if let Err(e) = stream.write_all(response.as_bytes()).await {
+        debug!("Failed to answer a health probe: {}", e);
+    }
+}
+
+/// Build the raw HTTP response of a probe: 200 when everything is up, 503 otherwise
+pub fn build_health_response(gateway_connected: bool, database_ok: bool) -> String {
+    let (status, body) = if gateway_connected && database_ok {
+        ("200 OK", "ok")
+    } else if !gateway_connected {
+        ("503 Service Unavailable", "discord disconnected")
+    } else {
+        ("503 Service Unavailable", "database unavailable")
+    };
+
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_health_response_healthy() {
+        let response = build_health_response(true, true);
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("\r\n\r\nok"));
+        assert!(response.contains("Content-Length: 2\r\n"));
+    }
+
+    #[test]
+    fn test_build_health_response_unhealthy() {
+        let response = build_health_response(false, true);
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.ends_with("discord disconnected"));
+
+        let response = build_health_response(true, false);
+        assert!(response.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(response.ends_with("database unavailable"));
+    }
+}
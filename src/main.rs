@@ -2,23 +2,41 @@ mod commands;
 mod constants;
 mod database;
 mod handlers;
+mod health;
 mod models;
 mod schedule;
+#[cfg(feature = "birthday")]
 mod services;
 mod utils;
 
 use poise::serenity_prelude as serenity;
-use std::sync::Arc;
-use tracing::{error, info};
+use std::sync::{Arc, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::Duration;
+use tracing::{error, info, warn};
 
 use crate::{
-    commands::{convert_to_lobby, create_lobby, disable_birthday, setup_birthday, setup_timezone},
-    constants::LOG_DIRECTIVE,
+    commands::all_commands,
+    constants::{ROLE_UPDATE_DELAY_MS, SHUTDOWN_TASKS_TIMEOUT_SECS},
     database::Database,
-    handlers::{handle_interaction, handle_modal_submit, handle_voice_state_update},
-    models::Data,
-    schedule::start_schedule_manager,
+    handlers::{handle_interaction, handle_modal_submit},
+    health::start_health_server,
+    models::{Context, Data, Error},
+    schedule::{flush_command_usage, start_command_usage_flusher},
+    utils::http_errors::HttpErrorKind,
+    utils::logging::{LogReloadHandle, build_log_filter},
+    utils::messages::format_warning,
 };
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt};
+
+#[cfg(feature = "lobby")]
+use crate::{
+    handlers::{handle_voice_state_update, resume_channel_kicks},
+    schedule::start_lobby_activity_sampler,
+    utils::http_errors::classify_http_error,
+};
+#[cfg(feature = "birthday")]
+use crate::schedule::{prune_guild_schedules, start_schedule_manager};
 
 #[tokio::main]
 async fn main() {
@@ -26,7 +44,7 @@ async fn main() {
     let _ = dotenvy::dotenv();
 
     // Initialize logging
-    initialize_logging();
+    let log_reload = initialize_logging();
 
     // Load configuration from environment
     let config = match load_configuration() {
@@ -47,15 +65,22 @@ async fn main() {
     };
 
     // Initialize bot data
-    let data = Data::new(db);
+    let data = Data::new(
+        db,
+        config.rng_seed,
+        config.feedback_channel_id,
+        log_reload,
+        std::time::Duration::from_millis(config.role_update_delay_ms),
+    );
 
     // Load existing data from database
+    #[cfg(feature = "lobby")]
     if let Err(e) = data.load_from_database().await {
         error!("Failed to load data from database: {}", e);
     }
 
     // Create and start the bot
-    if let Err(e) = start_bot(config.discord_token, data, config.dev_guild_id).await {
+    if let Err(e) = start_bot(config.discord_token, data, config.dev_guild_id, config.health_port).await {
         error!("Bot error: {}", e);
         std::process::exit(1);
     }
@@ -66,16 +91,33 @@ struct Config {
     discord_token: String,
     database_url: String,
     dev_guild_id: Option<u64>,
+    rng_seed: Option<u64>,
+    feedback_channel_id: Option<u64>,
+    role_update_delay_ms: u64,
+    health_port: Option<u16>,
 }
 
-/// Initialize the logging system
-fn initialize_logging() {
-    tracing_subscriber::fmt()
-        .with_env_filter(
-            tracing_subscriber::EnvFilter::from_default_env()
-                .add_directive(LOG_DIRECTIVE.parse().expect("valid log directive")),
-        )
+/// Initialize the logging system, returning a handle to change the log filter at runtime
+fn initialize_logging() -> LogReloadHandle {
+    let (filter, reload_handle) = reload::Layer::new(build_log_filter(Vec::new()));
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::layer())
         .init();
+    reload_handle
+}
+
+/// Read an optional environment variable, warning when it is set to a value that can't be
+/// parsed (it is then treated as unset)
+fn parse_optional_env<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let value = std::env::var(name).ok()?;
+    match value.parse() {
+        Ok(parsed) => Some(parsed),
+        Err(_) => {
+            warn!("Ignoring invalid {} value {:?}", name, value);
+            None
+        }
+    }
 }
 
 /// Load configuration from environment variables
@@ -95,10 +137,30 @@ fn load_configuration() -> Result<Config, Box<dyn std::error::Error>> {
         info!("Development mode: Commands will be registered to guild only");
     }
 
+    // Optional: fixed seed to make randomized behaviour reproducible
+    let rng_seed = parse_optional_env::<u64>("RNG_SEED");
+
+    if let Some(seed) = rng_seed {
+        info!("Using fixed RNG seed {}", seed);
+    }
+
+    // Optional: channel receiving /feedback messages (owners are DMed otherwise)
+    let feedback_channel_id = parse_optional_env::<u64>("FEEDBACK_CHANNEL");
+
+    // Optional: pause between birthday role changes, raise it if large guilds hit rate limits
+    let role_update_delay_ms = parse_optional_env::<u64>("ROLE_UPDATE_DELAY_MS").unwrap_or(ROLE_UPDATE_DELAY_MS);
+
+    // Optional: port of the HTTP health endpoint, no listener is started without it
+    let health_port = parse_optional_env::<u16>("HEALTH_PORT");
+
     Ok(Config {
         discord_token,
         database_url,
         dev_guild_id,
+        rng_seed,
+        feedback_channel_id,
+        role_update_delay_ms,
+        health_port,
     })
 }
 
@@ -107,24 +169,40 @@ async fn start_bot(
     token: String,
     data: Data,
     dev_guild_id: Option<u64>,
+    health_port: Option<u16>,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     // Wrap data in Arc for sharing with birthday checker
     let data_arc = Arc::new(data);
     let data_for_framework = Arc::clone(&data_arc);
 
+    // Background tasks started once the bot is ready, awaited on shutdown
+    let background_tasks: Arc<Mutex<Vec<JoinHandle<()>>>> = Arc::default();
+    let framework_tasks = Arc::clone(&background_tasks);
+
     // Create framework
     let framework = poise::Framework::builder()
         .options(poise::FrameworkOptions {
-            commands: vec![
-                create_lobby(),
-                convert_to_lobby(),
-                setup_birthday(),
-                disable_birthday(),
-                setup_timezone(),
-            ],
+            commands: all_commands(),
             event_handler: |ctx, event, _framework, data| {
                 Box::pin(async move {
                     match event {
+                        poise::serenity_prelude::FullEvent::Ready { .. }
+                        | poise::serenity_prelude::FullEvent::Resume { .. } => {
+                            data.set_gateway_connected(true);
+                        }
+                        poise::serenity_prelude::FullEvent::ShardStageUpdate { event } => {
+                            data.set_gateway_connected(
+                                event.new == serenity::ConnectionStage::Connected,
+                            );
+                        }
+                        // Only a guild the bot left, not one in an outage, loses its schedules
+                        #[cfg(feature = "birthday")]
+                        poise::serenity_prelude::FullEvent::GuildDelete { incomplete, .. }
+                            if !incomplete.unavailable =>
+                        {
+                            prune_guild_schedules(incomplete.id, data).await;
+                        }
+                        #[cfg(feature = "lobby")]
                         poise::serenity_prelude::FullEvent::VoiceStateUpdate { old, new } => {
                             handle_voice_state_update(ctx, old.clone(), new.clone(), data).await;
                         }
@@ -144,16 +222,44 @@ async fn start_bot(
                     Ok(())
                 })
             },
+            command_check: Some(|ctx| Box::pin(maintenance_check(ctx))),
+            post_command: |ctx| {
+                Box::pin(async move {
+                    ctx.data().record_command_usage(&ctx.command().qualified_name);
+                })
+            },
             ..Default::default()
         })
         .setup(move |ctx, _ready, framework| {
-            let http = ctx.http.clone();
-            let cache = ctx.cache.clone();
-            let data_clone = Arc::clone(&data_for_framework);
+            let mut tasks = framework_tasks.lock().unwrap_or_else(|e| e.into_inner());
 
-            // Start schedule manager
-            start_schedule_manager(http, cache, data_clone);
-            info!("Schedule manager task started");
+            // Start schedule manager (every schedule type is birthday related for now)
+            #[cfg(feature = "birthday")]
+            {
+                let http = ctx.http.clone();
+                let cache = ctx.cache.clone();
+                let data_clone = Arc::clone(&data_for_framework);
+                tasks.push(start_schedule_manager(http, cache, data_clone));
+                info!("Schedule manager task started");
+            }
+
+            #[cfg(feature = "lobby")]
+            tasks.push(start_lobby_activity_sampler(Arc::clone(&data_for_framework)));
+
+            // Channels deleted while the bot was offline are still in the database,
+            // then the kicks pending in the remaining ones are lifted on time
+            #[cfg(feature = "lobby")]
+            {
+                let ctx = ctx.clone();
+                let data = Arc::clone(&data_for_framework);
+                tokio::spawn(async move {
+                    prune_stale_temp_channels(&ctx, &data).await;
+                    resume_channel_kicks(&ctx, &data).await;
+                });
+            }
+
+            tasks.push(start_command_usage_flusher(Arc::clone(&data_for_framework)));
+            drop(tasks);
 
             Box::pin(async move {
                 // Register commands based on dev_guild_id
@@ -180,18 +286,164 @@ async fn start_bot(
         })
         .build();
 
-    // Create client with required intents
-    let intents = serenity::GatewayIntents::non_privileged()
-        | serenity::GatewayIntents::GUILD_VOICE_STATES
-        | serenity::GatewayIntents::GUILD_MEMBERS;
+    // Log the requests serenity's ratelimiter has to hold back and count them with the HTTP errors
+    let mut http = serenity::Http::new(&token);
+    if let Some(ratelimiter) = http.ratelimiter.as_mut() {
+        let http_errors = Arc::clone(&data_arc.http_errors);
+        ratelimiter.set_ratelimit_callback(Box::new(move |info| {
+            http_errors.increment(HttpErrorKind::RateLimited);
+            warn!(
+                "Rate limited on {:?} {} for {:?} (limit {}, global: {})",
+                info.method,
+                info.path,
+                info.timeout,
+                info.limit,
+                info.global
+            );
+        }));
+    }
 
-    let mut client = serenity::ClientBuilder::new(token, intents)
+    // Create client with required intents
+    let mut client = serenity::ClientBuilder::new_with_http(http, required_intents())
         .framework(framework)
         .await?;
 
+    if let Some(port) = health_port {
+        start_health_server(port, Arc::clone(&data_arc));
+    }
+
+    // Stop the shards on SIGINT/SIGTERM so that client.start() returns
+    let shard_manager = Arc::clone(&client.shard_manager);
+    let shutdown_data = Arc::clone(&data_arc);
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        info!("Shutdown signal received, stopping the bot...");
+        shutdown_data.shutdown_tx.send_replace(true);
+        shard_manager.shutdown_all().await;
+    });
+
     // Start the bot
     info!("Starting bot...");
-    client.start().await?;
+    let result = client.start().await;
 
+    // Stop the background tasks (also when the client failed without a signal), so none of
+    // them still uses the database once it is closed
+    data_arc.shutdown_tx.send_replace(true);
+    let tasks = std::mem::take(&mut *background_tasks.lock().unwrap_or_else(|e| e.into_inner()));
+    let stopped = tokio::time::timeout(Duration::from_secs(SHUTDOWN_TASKS_TIMEOUT_SECS), async {
+        for task in tasks {
+            if let Err(e) = task.await {
+                error!("Background task failed while stopping: {}", e);
+            }
+        }
+    })
+    .await;
+    if stopped.is_err() {
+        warn!(
+            "Background tasks didn't stop within {} seconds",
+            SHUTDOWN_TASKS_TIMEOUT_SECS
+        );
+    }
+
+    // Keep the command usage counted since the last flush, then release the connections
+    flush_command_usage(&data_arc).await;
+    data_arc.db.close().await;
+    info!("Bot stopped");
+
+    result?;
     Ok(())
 }
+
+/// Wait for Ctrl+C, or SIGTERM on Unix (sent by container runtimes on redeploys)
+async fn wait_for_shutdown_signal() {
+    let ctrl_c = async {
+        if let Err(e) = tokio::signal::ctrl_c().await {
+            error!("Failed to listen for Ctrl+C: {}", e);
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(e) => {
+                error!("Failed to listen for SIGTERM: {}", e);
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
+    }
+}
+
+/// Forget the temp channels that no longer exist on Discord. Only channels Discord reports as
+/// missing are removed, a transient error keeps the entry for the next start.
+#[cfg(feature = "lobby")]
+async fn prune_stale_temp_channels(ctx: &serenity::Context, data: &Data) {
+    let channel_ids: Vec<serenity::ChannelId> =
+        data.temp_channels.iter().map(|entry| *entry.key()).collect();
+
+    let mut pruned = 0;
+    for channel_id in channel_ids {
+        let Err(e) = channel_id.to_channel(ctx).await else {
+            continue;
+        };
+        if classify_http_error(&e) != HttpErrorKind::NotFound {
+            warn!("Failed to check temp channel {}: {}", channel_id, e);
+            continue;
+        }
+
+        data.temp_channels.remove(&channel_id);
+        match data.db.remove_temp_channel(channel_id).await {
+            Ok(()) => pruned += 1,
+            Err(e) => error!("Failed to remove stale temp channel {} from database: {}", channel_id, e),
+        }
+    }
+
+    info!("Pruned {} stale temp channel(s) on startup", pruned);
+}
+
+/// Reject commands from non-owners while the bot is in maintenance mode
+async fn maintenance_check(ctx: Context<'_>) -> Result<bool, Error> {
+    if !ctx.data().is_in_maintenance()
+        || ctx.framework().options().owners.contains(&ctx.author().id)
+    {
+        return Ok(true);
+    }
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(format_warning(
+                "The bot is under maintenance, please try again later.",
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+
+    Ok(false)
+}
+
+/// Gateway intents required by the enabled features
+fn required_intents() -> serenity::GatewayIntents {
+    let mut intents = serenity::GatewayIntents::non_privileged();
+
+    // Voice state events are only needed to manage lobbies and temp channels
+    if !cfg!(feature = "lobby") {
+        intents.remove(serenity::GatewayIntents::GUILD_VOICE_STATES);
+    }
+
+    // The privileged members intent is only needed to resolve birthday members and roles
+    if cfg!(feature = "birthday") {
+        intents.insert(serenity::GatewayIntents::GUILD_MEMBERS);
+    }
+
+    intents
+}
@@ -1,17 +1,113 @@
+use chrono::{DateTime, Utc};
 use dashmap::DashMap;
-use poise::serenity_prelude::{ChannelId, GuildId, UserId};
+use poise::serenity_prelude::{self as serenity, ChannelId, GuildId, UserId};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use tokio::sync::watch;
 
 use crate::database::Database;
+#[cfg(feature = "birthday")]
+use crate::schedule::ScheduleType;
+use crate::utils::http_errors::{HttpErrorKind, classify_http_error};
+use crate::utils::logging::LogReloadHandle;
+use crate::utils::rng::RngSource;
 
 /// Represents a temporary voice channel owned by a user
 #[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "lobby"), allow(dead_code))]
 pub struct TempChannel {
     pub owner_id: UserId,
     pub lobby_channel_id: ChannelId,
     pub is_persistent: bool,
     pub is_archived: bool,
+    /// Whether @everyone is denied from joining
+    pub is_locked: bool,
+    /// When the owner left the channel while others stayed (not persisted)
+    pub owner_left_at: Option<Instant>,
     pub guild_id: GuildId,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Represents a lobby voice channel and the settings of the temp channels it spawns
+#[derive(Clone, Debug)]
+#[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+pub struct LobbyChannel {
+    pub guild_id: GuildId,
+    /// Maximum users in spawned channels (None for unlimited)
+    pub user_limit: Option<u32>,
+    /// Name of spawned channels (None for the default name)
+    pub name_template: Option<String>,
+    /// Bitrate of spawned channels in bps (None to inherit the lobby's bitrate)
+    pub bitrate: Option<u32>,
+}
+
+/// Per-guild settings that commands commonly need, cached in memory
+#[derive(Clone, Debug)]
+pub struct GuildSettings {
+    pub timezone: String,
+    /// Branding line added before every bot message
+    pub message_prefix: Option<String>,
+    /// Branding line added after every bot message
+    pub message_suffix: Option<String>,
+    /// Delay before an empty temp channel is deleted or archived (seconds)
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+    pub empty_channel_grace_secs: u32,
+    /// Words forbidden in user-set channel names (lowercase)
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+    pub channel_name_blacklist: Vec<String>,
+    /// Maximum number of persistent channels a user may own (0 for no limit)
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+    pub max_persistent_channels: u32,
+    /// Base name of the archive category created in the guild (None for the default name)
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+    pub archive_category_base: Option<String>,
+}
+
+impl Default for GuildSettings {
+    fn default() -> Self {
+        Self {
+            timezone: "UTC".to_string(),
+            message_prefix: None,
+            message_suffix: None,
+            empty_channel_grace_secs: 0,
+            channel_name_blacklist: Vec::new(),
+            max_persistent_channels: 0,
+            archive_category_base: None,
+        }
+    }
+}
+
+/// Counters of failed Discord API calls, by error kind
+#[derive(Debug, Default)]
+pub struct HttpErrorCounters {
+    rate_limited: AtomicU64,
+    forbidden: AtomicU64,
+    not_found: AtomicU64,
+    server_error: AtomicU64,
+    other: AtomicU64,
+}
+
+impl HttpErrorCounters {
+    fn counter(&self, kind: HttpErrorKind) -> &AtomicU64 {
+        match kind {
+            HttpErrorKind::RateLimited => &self.rate_limited,
+            HttpErrorKind::Forbidden => &self.forbidden,
+            HttpErrorKind::NotFound => &self.not_found,
+            HttpErrorKind::ServerError => &self.server_error,
+            HttpErrorKind::Other => &self.other,
+        }
+    }
+
+    /// Increment the counter for an error kind
+    pub fn increment(&self, kind: HttpErrorKind) {
+        self.counter(kind).fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the current count for an error kind
+    pub fn get(&self, kind: HttpErrorKind) -> u64 {
+        self.counter(kind).load(Ordering::Relaxed)
+    }
 }
 
 /// Bot state shared across all handlers
@@ -19,38 +115,89 @@ pub struct TempChannel {
 pub struct Data {
     /// Database connection
     pub db: Database,
-    /// Maps lobby channel IDs to guild IDs
-    pub lobby_channels: DashMap<ChannelId, GuildId>,
+    /// Maps lobby channel IDs to their data
+    pub lobby_channels: DashMap<ChannelId, LobbyChannel>,
     /// Maps temporary channel IDs to their data
     pub temp_channels: DashMap<ChannelId, TempChannel>,
     /// Maps guild IDs to their archive category IDs
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
     pub archive_categories: DashMap<GuildId, ChannelId>,
     /// Signal to reload schedules
+    #[cfg(feature = "birthday")]
     pub schedule_reload_tx: watch::Sender<u64>,
+    /// Set to true when the bot is shutting down, background tasks stop on it
+    pub shutdown_tx: watch::Sender<bool>,
+    /// Schedule the manager is currently waiting on, with its fire time
+    #[cfg(feature = "birthday")]
+    pub next_schedule_tx: watch::Sender<Option<(ScheduleType, DateTime<Utc>)>>,
+    /// Counters of failed Discord API calls
+    pub http_errors: Arc<HttpErrorCounters>,
+    /// Cache of guild settings, filled on first access
+    pub guild_settings: Arc<DashMap<GuildId, GuildSettings>>,
+    /// Source of randomness (seeded when `RNG_SEED` is set)
+    pub rng: Arc<RngSource>,
+    /// Channel receiving user feedback, owners are DMed when unset
+    pub feedback_channel: Option<ChannelId>,
+    /// Command invocations not yet flushed to the database, by command name
+    pub command_usage: Arc<DashMap<String, u64>>,
+    /// When set, commands from non-owners are rejected
+    pub maintenance: Arc<AtomicBool>,
+    /// Handle to change the log filter at runtime
+    pub log_reload: LogReloadHandle,
+    /// Pause after each birthday role change
+    #[cfg_attr(not(feature = "birthday"), allow(dead_code))]
+    pub role_update_delay: Duration,
+    /// Whether the gateway connection to Discord is up, reported by the health endpoint
+    pub gateway_connected: Arc<AtomicBool>,
 }
 
 impl Data {
-    /// Create a new Data instance with the given database connection
-    pub fn new(db: Database) -> Self {
+    /// Create a new Data instance with the given database connection, optional RNG seed,
+    /// optional feedback channel, log filter handle and delay between role changes
+    pub fn new(
+        db: Database,
+        rng_seed: Option<u64>,
+        feedback_channel_id: Option<u64>,
+        log_reload: LogReloadHandle,
+        role_update_delay: Duration,
+    ) -> Self {
+        #[cfg(feature = "birthday")]
         let (schedule_reload_tx, _) = watch::channel(0);
+        let (shutdown_tx, _) = watch::channel(false);
+        #[cfg(feature = "birthday")]
+        let (next_schedule_tx, _) = watch::channel(None);
         Self {
             db,
             lobby_channels: DashMap::new(),
             temp_channels: DashMap::new(),
             archive_categories: DashMap::new(),
+            #[cfg(feature = "birthday")]
             schedule_reload_tx,
+            shutdown_tx,
+            #[cfg(feature = "birthday")]
+            next_schedule_tx,
+            http_errors: Arc::new(HttpErrorCounters::default()),
+            guild_settings: Arc::new(DashMap::new()),
+            rng: Arc::new(RngSource::new(rng_seed)),
+            feedback_channel: feedback_channel_id.map(ChannelId::new),
+            command_usage: Arc::new(DashMap::new()),
+            maintenance: Arc::new(AtomicBool::new(false)),
+            log_reload,
+            role_update_delay,
+            gateway_connected: Arc::new(AtomicBool::new(false)),
         }
     }
 
     /// Load existing data from the database into memory
+    #[cfg(feature = "lobby")]
     pub async fn load_from_database(&self) -> Result<(), Error> {
         // Load lobby channels
         self.db
-            .get_all_lobby_channels()
+            .get_all_lobbies_full()
             .await
             .map(|lobbies| {
-                lobbies.into_iter().for_each(|(channel_id, guild_id)| {
-                    self.lobby_channels.insert(channel_id, guild_id);
+                lobbies.into_iter().for_each(|(channel_id, lobby)| {
+                    self.lobby_channels.insert(channel_id, lobby);
                 });
                 tracing::info!(
                     "Loaded {} lobby channels from database",
@@ -73,6 +220,8 @@ impl Data {
                     lobby_channel_id,
                     is_persistent,
                     is_archived,
+                    is_locked,
+                    created_at,
                 )| {
                     self.temp_channels.insert(
                         channel_id,
@@ -81,7 +230,10 @@ impl Data {
                             lobby_channel_id,
                             is_persistent,
                             is_archived,
+                            is_locked,
+                            owner_left_at: None,
                             guild_id,
+                            created_at,
                         },
                     );
                 });
@@ -98,11 +250,95 @@ impl Data {
     }
 
     /// Check if a user is the owner of a temporary channel
+    #[cfg(feature = "lobby")]
     pub fn is_channel_owner(&self, channel_id: ChannelId, user_id: UserId) -> bool {
         self.temp_channels
             .get(&channel_id)
             .is_some_and(|tc| tc.owner_id == user_id)
     }
+
+    /// Get all temp channels owned by a user in a guild
+    #[cfg(feature = "lobby")]
+    pub fn owned_channels(&self, guild_id: GuildId, user_id: UserId) -> Vec<(ChannelId, TempChannel)> {
+        self.temp_channels
+            .iter()
+            .filter(|entry| entry.guild_id == guild_id && entry.owner_id == user_id)
+            .map(|entry| (*entry.key(), entry.value().clone()))
+            .collect()
+    }
+
+    /// Get the settings of a guild, loading them from the database on a cache miss
+    pub async fn get_guild_settings(&self, guild_id: GuildId) -> Result<GuildSettings, sqlx::Error> {
+        if let Some(settings) = self.guild_settings.get(&guild_id) {
+            return Ok(settings.clone());
+        }
+
+        let settings = self.db.get_guild_settings(guild_id).await?;
+        self.guild_settings.insert(guild_id, settings.clone());
+        Ok(settings)
+    }
+
+    /// Get the settings of a guild, falling back to the defaults if they can't be loaded
+    pub async fn guild_settings_or_default(&self, guild_id: GuildId) -> GuildSettings {
+        self.get_guild_settings(guild_id).await.unwrap_or_else(|e| {
+            tracing::warn!("Failed to load settings of guild {}: {}", guild_id, e);
+            GuildSettings::default()
+        })
+    }
+
+    /// Drop the cached settings of a guild, to be called after any settings change
+    pub fn invalidate_guild_settings(&self, guild_id: GuildId) {
+        self.guild_settings.remove(&guild_id);
+    }
+
+    /// Check whether the bot is in maintenance mode
+    pub fn is_in_maintenance(&self) -> bool {
+        self.maintenance.load(Ordering::Relaxed)
+    }
+
+    /// Whether the bot is currently connected to Discord
+    pub fn is_gateway_connected(&self) -> bool {
+        self.gateway_connected.load(Ordering::Relaxed)
+    }
+
+    /// Record whether the bot is connected to Discord
+    pub fn set_gateway_connected(&self, connected: bool) {
+        self.gateway_connected.store(connected, Ordering::Relaxed);
+    }
+
+    /// Enable or disable maintenance mode
+    pub fn set_maintenance(&self, enabled: bool) {
+        self.maintenance.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Count an invocation of a command, to be flushed to the database later
+    pub fn record_command_usage(&self, command: &str) {
+        *self.command_usage.entry(command.to_string()).or_default() += 1;
+    }
+
+    /// Take the pending command invocation counts, resetting them
+    pub fn take_command_usage(&self) -> Vec<(String, u64)> {
+        let commands: Vec<String> = self.command_usage.iter().map(|e| e.key().clone()).collect();
+        commands
+            .into_iter()
+            .filter_map(|command| self.command_usage.remove(&command))
+            .collect()
+    }
+
+    /// Get the pending command invocation counts without resetting them
+    pub fn pending_command_usage(&self) -> Vec<(String, u64)> {
+        self.command_usage
+            .iter()
+            .map(|e| (e.key().clone(), *e.value()))
+            .collect()
+    }
+
+    /// Record a failed Discord API call in the error counters
+    pub fn record_http_error(&self, error: &serenity::Error) {
+        let kind = classify_http_error(error);
+        self.http_errors.increment(kind);
+        tracing::debug!("Discord API error ({}): {}", kind.label(), error);
+    }
 }
 
 pub type Error = Box<dyn std::error::Error + Send + Sync>;
@@ -0,0 +1,42 @@
+use poise::serenity_prelude::{self as serenity, CreateAllowedMentions, CreateMessage, GuildId};
+use std::sync::Arc;
+use tracing::info;
+
+use crate::models::Data;
+use crate::utils::messages::decorate_message;
+use super::Schedule;
+
+/// Post the message of an announcement schedule in its channel.
+/// Returns how many announcements were posted.
+#[tracing::instrument(name = "announcement", skip_all, fields(guild_id = schedule.guild_id))]
+pub async fn run_announcement(
+    http: &Arc<serenity::Http>,
+    data: &Data,
+    schedule: &Schedule,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(guild_id) = schedule.guild_id else {
+        return Err("announcement schedule has no guild".into());
+    };
+    let guild_id = GuildId::new(guild_id as u64);
+
+    let Some((channel_id, message)) = data.db.get_scheduled_announcement(schedule.id).await? else {
+        return Err(format!("announcement '{}' has no message configured", schedule.label).into());
+    };
+
+    let settings = data.guild_settings_or_default(guild_id).await;
+    // Users and roles may be pinged, @everyone and @here may not
+    let allowed_mentions = CreateAllowedMentions::new().all_users(true).all_roles(true);
+    channel_id
+        .send_message(
+            http,
+            CreateMessage::new()
+                .content(decorate_message(&settings, &message))
+                .allowed_mentions(allowed_mentions),
+        )
+        .await
+        .inspect_err(|e| data.record_http_error(e))?;
+
+    info!("Posted announcement '{}' in channel {}", schedule.label, channel_id);
+
+    Ok(1)
+}
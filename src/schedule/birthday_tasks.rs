@@ -1,178 +1,654 @@
 use chrono::{Datelike, Utc};
-use poise::serenity_prelude::{self as serenity, ChannelId, CreateMessage, GuildId, UserId};
+use poise::serenity_prelude::{
+    self as serenity, ChannelId, CreateAllowedMentions, CreateEmbed, CreateEmbedFooter, CreateMessage,
+    CreateThread, GuildId, MessageFlags, MessageId, RoleId, UserId,
+};
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
-use crate::models::Data;
-use crate::utils::datetime::{format_date_display, get_current_month_day};
+use crate::database::BirthdayConfig;
+use crate::constants::{BIRTHDAY_EMBED_COLOUR, MAX_CHANNEL_NAME_LENGTH, MEMBERS_PAGE_SIZE};
+use crate::models::{Data, GuildSettings};
+use crate::utils::datetime::{advance_month_day, format_date_display, get_current_month_day, matches_birthday};
+use crate::utils::db_retry::with_db_retry;
+use crate::utils::http_errors::{classify_http_error, HttpErrorKind};
 use crate::utils::message_formatter::{
-    build_birthday_entry, build_combined_message, build_default_footer,
-    build_default_header, format_age_info, join_birthday_entries, process_custom_text,
+    build_birthday_dm, build_birthday_embeds, visible_age, build_birthday_reminder, build_birthday_payload, build_wishes_thread_name,
+    BirthdayEmbed, BirthdayTemplates, NamedBirthday,
+};
+use crate::utils::messages::decorate_message;
+use crate::utils::schedule_utils::select_announce_channel;
+use crate::utils::timezone::{local_date, parse_timezone};
+use crate::utils::role_logic::{
+    calculate_role_changes, can_skip_role_update, count_role_changes, milestone_role_targets,
+    RoleAction,
 };
-use crate::utils::role_logic::{determine_role_action, RoleAction};
 
-/// Check for birthdays today and send notifications for a specific guild
+/// Birthday announcement ready to be sent
+pub struct BirthdayNotification {
+    pub channel_id: ChannelId,
+    /// Channels the announcement is copied to
+    pub mirror_channel_ids: Vec<ChannelId>,
+    /// Final message contents, already decorated with the guild branding
+    pub messages: Vec<String>,
+    /// Embeds sent instead of the plain messages (empty unless enabled for the guild)
+    pub embeds: Vec<BirthdayEmbed>,
+    /// Users being celebrated
+    pub user_ids: Vec<UserId>,
+    /// Display names of the users being celebrated
+    pub user_names: Vec<String>,
+    /// Whether the users being celebrated are pinged
+    pub ping_users: bool,
+}
+
+/// Check for birthdays today and send notifications for a specific guild.
+/// Returns how many users were announced.
+#[tracing::instrument(name = "birthday_check", skip_all, fields(guild_id = guild_id))]
 pub async fn run_birthday_check(
     http: &Arc<serenity::Http>,
-    _cache: &Arc<serenity::Cache>,
+    cache: &Arc<serenity::Cache>,
     data: &Data,
     guild_id: i64,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (month, day) = get_current_month_day();
-    
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     let guild_id = serenity::GuildId::new(guild_id as u64);
 
-    info!("Checking birthdays for {}/{} in guild {}", day, month, guild_id);
-
-    // Get all users with birthdays today
-    let birthdays = data.db.get_birthdays_on_date(month, day).await?;
-
-    if birthdays.is_empty() {
-        info!("No birthdays found for today");
-        return Ok(());
-    }
-
-    info!("Found {} birthday(s) today", birthdays.len());
-
-    // Get the birthday notification channel for this guild
-    let channel_config = match data.db.get_birthday_channel(guild_id).await {
+    // Every step of the run works from the same configuration, loaded once
+    let db = &data.db;
+    let config = match with_db_retry("loading birthday config", move || db.get_birthday_config(guild_id)).await {
         Ok(Some(config)) => config,
         Ok(None) => {
-            // No birthday channel configured for this guild
             info!("No birthday channel configured for guild {}", guild_id);
-            return Ok(());
+            return Ok(0);
         }
         Err(e) => {
-            error!(
-                "Failed to get birthday channel for guild {}: {}",
-                guild_id, e
-            );
+            error!("Failed to get birthday config for guild {}: {}", guild_id, e);
             return Err(Box::new(e));
         }
     };
 
-    let (channel_id, _message_id, custom_message, custom_message_without_age, custom_header, custom_footer) = channel_config;
+    // Tidy up old announcements first, even on days without birthdays
+    delete_expired_announcements(http, data, guild_id, &config).await;
+
+    // The heads-up doesn't depend on anyone celebrating today
+    send_birthday_reminder(http, cache, data, guild_id, &config).await;
+
+    let Some(notification) = prepare_birthday_notification(http, cache, data, guild_id, &config).await? else {
+        return Ok(0);
+    };
 
-    // Filter birthdays to only include users who are in this guild (functional approach)
-    let guild_birthdays: Vec<(UserId, Option<i32>)> = {
-        let mut results = Vec::new();
-        for (user_id, birth_year) in &birthdays {
-            if guild_id.member(http, *user_id).await.is_ok() {
-                results.push((*user_id, *birth_year));
+    // Send the birthday notification (combined unless configured otherwise)
+    let announced = match send_birthday_notification(http, guild_id, &notification, config.silent_announcements)
+        .await
+    {
+        Ok(sent_messages) => {
+            for (channel_id, message_ids) in &sent_messages {
+                if let Err(e) = data
+                    .db
+                    .log_announcements(guild_id, *channel_id, message_ids)
+                    .await
+                {
+                    error!("Failed to log birthday announcement of guild {}: {}", guild_id, e);
+                }
+            }
+            // One shared thread, under the first announcement that went through
+            if config.wishes_thread
+                && let Some((channel_id, message_ids)) = sent_messages.first()
+                && let Some(message_id) = message_ids.first()
+            {
+                create_wishes_thread(http, data, guild_id, &notification, *channel_id, *message_id).await;
             }
+            notification.user_ids.len()
+        }
+        Err(e) => {
+            handle_birthday_notification_error(http, cache, data, guild_id, &config, &notification, e)
+                .await;
+            0
         }
-        results
     };
 
-    if guild_birthdays.is_empty() {
-        info!("No birthday users are in guild {}", guild_id);
-        return Ok(());
+    // Greet the users privately too, whether the announcement went through or not
+    if config.dm_notifications {
+        send_birthday_dms(http, cache, data, guild_id, &notification).await;
     }
 
-    // Send a single combined birthday notification
-    if let Err(e) = send_combined_birthday_notification(
-        http,
-        guild_id,
-        channel_id,
-        &guild_birthdays,
-        &custom_message,
-        &custom_message_without_age,
-        &custom_header,
-        &custom_footer,
-    )
-    .await
+    Ok(announced)
+}
+
+/// Post a heads-up for the birthdays coming in the configured number of days, if enabled
+async fn send_birthday_reminder(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Data,
+    guild_id: GuildId,
+    config: &BirthdayConfig,
+) {
+    let Some(days) = config.reminder_days else {
+        return;
+    };
+
+    let today = Utc::now().date_naive();
+    let (month, day) = advance_month_day(today, days);
+    let db = &data.db;
+    let birthdays =
+        match with_db_retry("loading reminder birthdays", move || db.get_birthdays_on_date(month, day)).await {
+            Ok(birthdays) => birthdays,
+            Err(e) => {
+                error!("Failed to load birthdays for the reminder of guild {}: {}", guild_id, e);
+                return;
+            }
+        };
+    if birthdays.is_empty() {
+        return;
+    }
+
+    let members = match fetch_guild_members(http, cache, guild_id).await {
+        Ok(members) => members,
+        Err(e) => {
+            error!("Failed to fetch the members of guild {} for the birthday reminder: {}", guild_id, e);
+            return;
+        }
+    };
+    let mut names: Vec<String> = birthdays
+        .iter()
+        .filter_map(|(user_id, ..)| members.get(user_id))
+        .map(|member| member.display_name().to_string())
+        .collect();
+    if names.is_empty() {
+        return;
+    }
+    names.sort_by_key(|name| name.to_lowercase());
+
+    let channel_id = config.channel_id;
+
+    // Every Birthday schedule run of the day gets here (extra times, downtime catch-up),
+    // only the first one posts the reminder
+    match data.db.claim_birthday_reminder(guild_id, today).await {
+        Ok(true) => {}
+        Ok(false) => return,
+        Err(e) => {
+            error!("Failed to mark the birthday reminder of guild {} as sent: {}", guild_id, e);
+            return;
+        }
+    }
+
+    let settings = data.guild_settings_or_default(guild_id).await;
+    let reminder = build_birthday_reminder(&names, days, &format_date_display(month, day));
+    let message = CreateMessage::new()
+        .content(decorate_message(&settings, &reminder))
+        .allowed_mentions(CreateAllowedMentions::new());
+    match channel_id.send_message(http, message).await {
+        Ok(_) => info!("Posted a birthday reminder for {} user(s) in guild {}", names.len(), guild_id),
+        Err(e) => {
+            data.record_http_error(&e);
+            warn!("Failed to post the birthday reminder in channel {}: {}", channel_id, e);
+        }
+    }
+}
+
+/// Open a thread for wishes under the announcement.
+/// Everyone celebrated on the same day shares the thread.
+async fn create_wishes_thread(
+    http: &Arc<serenity::Http>,
+    data: &Data,
+    guild_id: GuildId,
+    notification: &BirthdayNotification,
+    channel_id: ChannelId,
+    message_id: MessageId,
+) {
+    let name = build_wishes_thread_name(&notification.user_names, MAX_CHANNEL_NAME_LENGTH as usize);
+    if let Err(e) = channel_id
+        .create_thread_from_message(http, message_id, CreateThread::new(name))
+        .await
     {
-        error!(
-            "Failed to send birthday notification in guild {}: {}",
-            guild_id, e
+        data.record_http_error(&e);
+        warn!("Failed to create birthday wishes thread in guild {}: {}", guild_id, e);
+    }
+}
+
+/// Report a failed birthday announcement, falling back to DMs when the channel is unreachable
+/// (unless the users are DMed anyway)
+async fn handle_birthday_notification_error(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Data,
+    guild_id: GuildId,
+    config: &BirthdayConfig,
+    notification: &BirthdayNotification,
+    error: serenity::Error,
+) {
+    data.record_http_error(&error);
+    error!(
+        "Failed to send birthday notification in guild {}: {}",
+        guild_id, error
+    );
+
+    // The bot lost access to the channel, celebrate in DMs instead if enabled
+    if matches!(
+        classify_http_error(&error),
+        HttpErrorKind::Forbidden | HttpErrorKind::NotFound
+    ) {
+        warn!(
+            "Birthday channel {} of guild {} is not reachable, the birthday setup needs to be fixed",
+            notification.channel_id, guild_id
         );
+        if config.dm_fallback && !config.dm_notifications {
+            send_birthday_dms(http, cache, data, guild_id, notification).await;
+            info!("Birthday DMs of guild {} were sent as a fallback", guild_id);
+        }
+    }
+}
+
+/// Delete the birthday announcements older than the guild's retention, if one is set
+async fn delete_expired_announcements(
+    http: &Arc<serenity::Http>,
+    data: &Data,
+    guild_id: GuildId,
+    config: &BirthdayConfig,
+) {
+    let Some(days) = config.announcement_retention_days else {
+        return;
+    };
+
+    let expired = match data.db.get_expired_announcements(guild_id, days).await {
+        Ok(expired) => expired,
+        Err(e) => {
+            error!("Failed to get expired announcements of guild {}: {}", guild_id, e);
+            return;
+        }
+    };
+
+    let mut deleted = Vec::new();
+    for (channel_id, message_id) in expired {
+        match channel_id.delete_message(http, message_id).await {
+            Ok(()) => deleted.push(message_id),
+            Err(e) => {
+                data.record_http_error(&e);
+                // Already gone (deleted by hand or with its channel), no need to try again
+                if classify_http_error(&e) == HttpErrorKind::NotFound {
+                    deleted.push(message_id);
+                } else {
+                    warn!("Failed to delete birthday announcement {}: {}", message_id, e);
+                }
+            }
+        }
+    }
+
+    if deleted.is_empty() {
+        return;
+    }
+
+    if let Err(e) = data.db.remove_announcements(&deleted).await {
+        error!("Failed to forget deleted announcements of guild {}: {}", guild_id, e);
+    }
+    info!(
+        "Deleted {} old birthday announcement(s) in guild {}",
+        deleted.len(),
+        guild_id
+    );
+}
+
+/// DM a private greeting to each birthday user
+async fn send_birthday_dms(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Data,
+    guild_id: GuildId,
+    notification: &BirthdayNotification,
+) {
+    let guild_name = guild_id
+        .name(cache)
+        .unwrap_or_else(|| "your server".to_string());
+    let content = build_birthday_dm(&guild_name);
+
+    for user_id in &notification.user_ids {
+        let message = CreateMessage::new().content(&content);
+        if let Err(e) = user_id.direct_message(http, message).await {
+            data.record_http_error(&e);
+            // Users commonly have DMs closed, this is not an error on our side
+            warn!("Failed to send birthday DM to user {}: {}", user_id, e);
+        }
+    }
+
+    info!(
+        "Sent birthday DMs to {} user(s) of guild {}",
+        notification.user_ids.len(),
+        guild_id
+    );
+}
+
+/// Build today's birthday announcement of a guild without sending it.
+/// Returns None when there is nothing to announce.
+async fn prepare_birthday_notification(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Data,
+    guild_id: GuildId,
+    config: &BirthdayConfig,
+) -> Result<Option<BirthdayNotification>, Box<dyn std::error::Error + Send + Sync>> {
+    let (month, day) = get_current_month_day();
+
+    info!("Checking birthdays for {}/{} in guild {}", day, month, guild_id);
+
+    // Get all users with birthdays today
+    let db = &data.db;
+    let birthdays =
+        with_db_retry("loading birthdays", move || db.get_birthdays_on_date(month, day)).await?;
+
+    if birthdays.is_empty() {
+        info!("No birthdays found for today");
+        return Ok(None);
     }
 
-    Ok(())
+    info!("Found {} birthday(s) today", birthdays.len());
+
+    // One member list serves both the membership filter and the display names
+    let members = fetch_guild_members(http, cache, guild_id).await?;
+
+    Ok(build_guild_birthday_notification(data, guild_id, config, &birthdays, &members).await)
 }
 
-/// Send a combined birthday notification for all users with birthdays today
-async fn send_combined_birthday_notification(
+/// Build a birthday announcement that previews the guild's configuration for a single user,
+/// exactly as it would be announced. Returns None when birthdays aren't configured.
+pub async fn prepare_birthday_preview(
     http: &Arc<serenity::Http>,
+    data: &Data,
     guild_id: GuildId,
+    user_id: UserId,
+    birth_year: Option<i32>,
+) -> Result<Option<BirthdayNotification>, Box<dyn std::error::Error + Send + Sync>> {
+    let Some(config) = data.db.get_birthday_config(guild_id).await? else {
+        return Ok(None);
+    };
+
+    // Only the previewed user is needed, no need for the whole member list
+    let members: HashMap<UserId, serenity::Member> = match guild_id.member(http, user_id).await {
+        Ok(member) => HashMap::from([(user_id, member)]),
+        Err(_) => HashMap::new(),
+    };
+
+    Ok(build_guild_birthday_notification(data, guild_id, &config, &[(user_id, birth_year, true)], &members).await)
+}
+
+/// Members of a guild by user ID, taken from the cache when it holds the full member list
+/// and fetched page by page otherwise
+pub async fn fetch_guild_members(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    guild_id: GuildId,
+) -> Result<HashMap<UserId, serenity::Member>, serenity::Error> {
+    let cached = cache.guild(guild_id).and_then(|guild| {
+        (guild.members.len() as u64 >= guild.member_count).then(|| guild.members.clone())
+    });
+    if let Some(members) = cached {
+        return Ok(members.into_iter().collect());
+    }
+
+    let mut members = HashMap::new();
+    let mut after = None;
+    loop {
+        let page = guild_id.members(http, Some(MEMBERS_PAGE_SIZE), after).await?;
+        let last_page = (page.len() as u64) < MEMBERS_PAGE_SIZE;
+        after = page.last().map(|member| member.user.id);
+        members.extend(page.into_iter().map(|member| (member.user.id, member)));
+
+        if last_page || after.is_none() {
+            break;
+        }
+    }
+
+    info!("Fetched {} member(s) of guild {}", members.len(), guild_id);
+    Ok(members)
+}
+
+/// Build the announcement of a guild for the given birthdays, using its configuration
+async fn build_guild_birthday_notification(
+    data: &Data,
+    guild_id: GuildId,
+    config: &BirthdayConfig,
+    birthdays: &[(UserId, Option<i32>, bool)],
+    members: &HashMap<UserId, serenity::Member>,
+) -> Option<BirthdayNotification> {
+    // Filter birthdays to only include users who are in this guild
+    let guild_birthdays: Vec<(UserId, Option<i32>, bool)> = birthdays
+        .iter()
+        .filter(|(user_id, _, _)| members.contains_key(user_id))
+        .copied()
+        .collect();
+
+    if guild_birthdays.is_empty() {
+        info!("No birthday users are in guild {}", guild_id);
+        return None;
+    }
+
+    let settings = data.guild_settings_or_default(guild_id).await;
+    let templates = BirthdayTemplates {
+        message: config.custom_message.clone(),
+        message_without_age: config.custom_message_without_age.clone(),
+        header: config.custom_header.clone(),
+        footer: config.custom_footer.clone(),
+        age: config.age_template.clone(),
+    };
+
+    let mut notification = build_birthday_notification(
+        members,
+        config.channel_id,
+        config.weekend_channel_id,
+        &guild_birthdays,
+        &templates,
+        config.separate_messages,
+        config.ping_users,
+        config.use_embed,
+        data.rng.index(0..usize::MAX),
+        &settings,
+    );
+    notification.mirror_channel_ids = config
+        .mirror_channel_ids
+        .iter()
+        .copied()
+        .filter(|id| *id != notification.channel_id)
+        .collect();
+
+    Some(notification)
+}
+
+/// Build the birthday notification for all users with birthdays today,
+/// either combined in one message or as one message per user
+#[allow(clippy::too_many_arguments)]
+fn build_birthday_notification(
+    members: &HashMap<UserId, serenity::Member>,
     channel_id: ChannelId,
-    birthdays: &[(UserId, Option<i32>)],
-    custom_message: &Option<String>,
-    custom_message_without_age: &Option<String>,
-    custom_header: &Option<String>,
-    custom_footer: &Option<String>,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    weekend_channel_id: Option<ChannelId>,
+    birthdays: &[(UserId, Option<i32>, bool)],
+    templates: &BirthdayTemplates,
+    separate_messages: bool,
+    ping_users: bool,
+    use_embed: bool,
+    template_seed: usize,
+    settings: &GuildSettings,
+) -> BirthdayNotification {
     let now = Utc::now();
     let date_str = format_date_display(now.month() as i32, now.day() as i32);
-    let current_year = now.year();
 
-    // Build the header using pure function
-    let header = process_custom_text(custom_header)
-        .unwrap_or_else(build_default_header);
-
-    // Build the per-user messages using functional approach with pure functions
-    let mut birthday_messages = Vec::new();
-    for (user_id, birth_year) in birthdays {
-        let user_name = guild_id
-            .member(http, *user_id)
-            .await
-            .ok()
-            .map(|m| m.display_name().to_string())
-            .unwrap_or_else(|| "Unknown".to_string());
-
-        let age_info = format_age_info(*birth_year, current_year);
-        let mention = format!("<@{}>", user_id);
-
-        let message = build_birthday_entry(
-            &user_name,
-            &mention,
-            &age_info,
-            custom_message, // template with age
-            custom_message_without_age, // template without age
+    // Weekends are those of the guild's timezone
+    let weekday = parse_timezone(&settings.timezone)
+        .map(|tz| now.with_timezone(&tz).weekday())
+        .unwrap_or_else(|_| now.weekday());
+    let channel_id = select_announce_channel(weekday, channel_id, weekend_channel_id);
+
+    // Resolve display names, the content itself is built by a pure function
+    let named_birthdays: Vec<NamedBirthday> = birthdays
+        .iter()
+        .map(|(user_id, birth_year, show_age)| {
+            let user_name = members
+                .get(user_id)
+                .map(|m| m.display_name().to_string())
+                .unwrap_or_else(|| "Unknown".to_string());
+            ((user_id.get(), *birth_year, *show_age), user_name)
+        })
+        .collect();
+
+    let messages = build_birthday_payload(
+        &named_birthdays,
+        templates,
+        &date_str,
+        now.year(),
+        separate_messages,
+        ping_users,
+        template_seed,
+    )
+    .iter()
+    .map(|content| decorate_message(settings, content))
+    .collect();
+
+    let embeds = if use_embed {
+        build_birthday_embeds(
+            &named_birthdays,
+            templates,
             &date_str,
-        );
-        
-        birthday_messages.push(message);
-    }
-    
-    let birthday_list = join_birthday_entries(&birthday_messages);
+            now.year(),
+            separate_messages,
+            ping_users,
+            template_seed,
+        )
+        .into_iter()
+        .map(|embed| BirthdayEmbed {
+            description: decorate_message(settings, &embed.description),
+            ..embed
+        })
+        .collect()
+    } else {
+        Vec::new()
+    };
 
-    // Build the footer using pure function
-    let footer = process_custom_text(custom_footer)
-        .unwrap_or_else(build_default_footer);
+    BirthdayNotification {
+        channel_id,
+        mirror_channel_ids: Vec::new(),
+        messages,
+        embeds,
+        user_ids: birthdays.iter().map(|(user_id, _, _)| *user_id).collect(),
+        user_names: named_birthdays.into_iter().map(|(_, name)| name).collect(),
+        ping_users,
+    }
+}
 
-    // Combine everything using pure function
-    let message_content = build_combined_message(&header, &birthday_list, &footer);
+/// Send a prepared birthday notification to its channel and mirrors, returning the messages
+/// sent in each channel. Only fails when no channel could be announced in.
+pub async fn send_birthday_notification(
+    http: &Arc<serenity::Http>,
+    guild_id: GuildId,
+    notification: &BirthdayNotification,
+    silent: bool,
+) -> Result<Vec<(ChannelId, Vec<MessageId>)>, serenity::Error> {
+    // Only the birthday users may be pinged, and only if the guild wants them to be
+    let allowed_mentions = if notification.ping_users {
+        CreateAllowedMentions::new().users(notification.user_ids.iter().copied())
+    } else {
+        CreateAllowedMentions::new()
+    };
 
-    // Send the message
-    let message = CreateMessage::new().content(message_content);
+    let messages: Vec<CreateMessage> = if notification.embeds.is_empty() {
+        notification
+            .messages
+            .iter()
+            .map(|content| CreateMessage::new().content(content))
+            .collect()
+    } else {
+        // Mentions inside embeds never ping, so the users are mentioned once above the first one
+        let mentions = notification
+            .user_ids
+            .iter()
+            .map(|user_id| format!("<@{}>", user_id))
+            .collect::<Vec<_>>()
+            .join(" ");
+        notification
+            .embeds
+            .iter()
+            .enumerate()
+            .map(|(index, embed)| {
+                let message = CreateMessage::new().embed(build_birthday_embed(embed));
+                if notification.ping_users && index == 0 {
+                    message.content(&mentions)
+                } else {
+                    message
+                }
+            })
+            .collect()
+    };
 
-    match channel_id.send_message(http, message).await {
-        Ok(_) => {
-            info!(
-                "Sent birthday notification for {} user(s) in guild {}",
-                birthdays.len(),
-                guild_id
-            );
+    let messages: Vec<CreateMessage> = messages
+        .into_iter()
+        .map(|message| {
+            let message = message.allowed_mentions(allowed_mentions.clone());
+            // Mentions still show, but nobody gets a push or desktop notification
+            if silent {
+                message.flags(MessageFlags::SUPPRESS_NOTIFICATIONS)
+            } else {
+                message
+            }
+        })
+        .collect();
+
+    // A failing channel doesn't prevent announcing in the others
+    let channel_ids =
+        std::iter::once(notification.channel_id).chain(notification.mirror_channel_ids.iter().copied());
+    let mut sent_messages = Vec::new();
+    let mut last_error = None;
+    for channel_id in channel_ids {
+        let mut message_ids = Vec::new();
+        for message in &messages {
+            match channel_id.send_message(http, message.clone()).await {
+                Ok(sent) => message_ids.push(sent.id),
+                Err(e) => {
+                    warn!(
+                        "Failed to send birthday message to channel {} in guild {}: {}",
+                        channel_id, guild_id, e
+                    );
+                    last_error = Some(e);
+                    break;
+                }
+            }
         }
-        Err(e) => {
-            warn!(
-                "Failed to send birthday message to channel {} in guild {}: {}",
-                channel_id, guild_id, e
-            );
-            return Err(Box::new(e));
+        if !message_ids.is_empty() {
+            sent_messages.push((channel_id, message_ids));
         }
     }
 
-    Ok(())
+    if sent_messages.is_empty()
+        && let Some(e) = last_error
+    {
+        return Err(e);
+    }
+
+    info!(
+        "Sent birthday notification for {} user(s) to {} channel(s) in guild {}",
+        notification.user_ids.len(),
+        sent_messages.len(),
+        guild_id
+    );
+
+    Ok(sent_messages)
+}
+
+/// Turn a birthday embed into a Discord embed
+pub fn build_birthday_embed(embed: &BirthdayEmbed) -> CreateEmbed {
+    let builder = CreateEmbed::new()
+        .title(&embed.title)
+        .description(&embed.description)
+        .colour(BIRTHDAY_EMBED_COLOUR);
+    if embed.footer.is_empty() {
+        builder
+    } else {
+        builder.footer(CreateEmbedFooter::new(&embed.footer))
+    }
 }
 
-/// Update birthday roles for all guilds
+/// Update birthday roles for all guilds. Returns how many roles were added or removed.
 pub async fn run_birthday_role_update_all_guilds(
     http: &Arc<serenity::Http>,
     cache: &Arc<serenity::Cache>,
     data: &Data,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
     let (month, day) = get_current_month_day();
 
     info!("Updating birthday roles for {}/{} across all guilds", day, month);
@@ -180,43 +656,71 @@ pub async fn run_birthday_role_update_all_guilds(
     // Get all guilds the bot is in
     let guilds = cache.guilds();
 
+    let mut changes = 0;
     for guild_id in guilds {
-        if let Err(e) = run_birthday_role_update(http, cache, data, guild_id.get() as i64).await {
-            error!("Failed to update birthday roles for guild {}: {}", guild_id, e);
+        match run_birthday_role_update(http, cache, data, guild_id.get() as i64).await {
+            Ok(count) => changes += count,
+            Err(e) => error!("Failed to update birthday roles for guild {}: {}", guild_id, e),
         }
     }
 
-    Ok(())
+    Ok(changes)
 }
 
-/// Update birthday roles - assign to users with birthdays today, remove from others
+/// Update birthday roles - assign to users whose birthday is today in their own timezone,
+/// remove from others. Runs hourly, so roles follow each user's local date.
+/// Milestone roles are additionally given to users reaching a configured age today.
+/// Returns how many roles were added or removed.
+#[tracing::instrument(name = "birthday_role_update", skip_all, fields(guild_id = guild_id))]
 pub async fn run_birthday_role_update(
     http: &Arc<serenity::Http>,
-    _cache: &Arc<serenity::Cache>,
+    cache: &Arc<serenity::Cache>,
     data: &Data,
     guild_id: i64,
-) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    let (month, day) = get_current_month_day();
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    let now = Utc::now();
+    let current_year = now.year();
     
     let guild_id = serenity::GuildId::new(guild_id as u64);
 
-    info!("Updating birthday roles for {}/{} in guild {}", day, month, guild_id);
-
-    // Get all users with birthdays today
-    let birthdays = data.db.get_birthdays_on_date(month, day).await?;
-    let birthday_user_ids: std::collections::HashSet<UserId> = 
-        birthdays.iter().map(|(user_id, _)| *user_id).collect();
-
-    info!("Found {} user(s) with birthdays today", birthday_user_ids.len());
+    let settings = data.guild_settings_or_default(guild_id).await;
+    let guild_timezone = parse_timezone(&settings.timezone).unwrap_or(chrono_tz::UTC);
+    let today = now.with_timezone(&guild_timezone).date_naive();
+
+    info!("Updating birthday roles for {}/{} in guild {}", today.day(), today.month(), guild_id);
+
+    // Users in other timezones may already or still be on a neighbouring date
+    let dates: Vec<(i32, i32)> = [today.pred_opt(), Some(today), today.succ_opt()]
+        .into_iter()
+        .flatten()
+        .map(|date| (date.month() as i32, date.day() as i32))
+        .collect();
+    let (db, dates) = (&data.db, &dates);
+    let birthdays = with_db_retry("loading birthdays", move || {
+        db.get_birthdays_with_timezone_on_dates(dates)
+    })
+    .await?;
+
+    // Keep the users whose birthday is today in their own timezone (or the guild's)
+    // Users who hide their age keep the birthday role but never get a milestone role,
+    // as it would reveal their age
+    let birthday_users: HashMap<UserId, Option<i32>> = birthdays
+        .into_iter()
+        .filter(|birthday| {
+            let date = local_date(now, birthday.timezone.as_deref(), &guild_timezone);
+            matches_birthday(birthday.month, birthday.day, date.month() as i32, date.day() as i32)
+        })
+        .map(|birthday| {
+            let age = visible_age(birthday.year, current_year, birthday.show_age);
+            (birthday.user_id, age)
+        })
+        .collect();
+
+    info!("Found {} user(s) with birthdays today", birthday_users.len());
 
     // Get the birthday role configuration for this guild
     let role_id = match data.db.get_birthday_role(guild_id).await {
-        Ok(Some(role)) => role,
-        Ok(None) => {
-            // No birthday role configured for this guild
-            info!("No birthday role configured for guild {}", guild_id);
-            return Ok(());
-        }
+        Ok(role) => role,
         Err(e) => {
             error!(
                 "Failed to get birthday role for guild {}: {}",
@@ -226,8 +730,46 @@ pub async fn run_birthday_role_update(
         }
     };
 
+    let milestone_roles = match data.db.get_milestone_roles(guild_id).await {
+        Ok(roles) => roles,
+        Err(e) => {
+            error!(
+                "Failed to get milestone roles for guild {}: {}",
+                guild_id, e
+            );
+            return Err(Box::new(e));
+        }
+    };
+
+    if role_id.is_none() && milestone_roles.is_empty() {
+        // No birthday or milestone role configured for this guild
+        info!("No birthday role configured for guild {}", guild_id);
+        return Ok(0);
+    }
+
+    // Skip the expensive member fetch when there is nothing to add and the cache
+    // shows no member still holding a role from a previous day
+    let roles: Vec<RoleId> = role_id
+        .into_iter()
+        .chain(milestone_roles.iter().map(|(_, role)| *role))
+        .collect();
+    let cached_members = cache.guild(guild_id).map(|guild| {
+        let complete = guild.members.len() as u64 >= guild.member_count;
+        let any_has_role = guild
+            .members
+            .values()
+            .any(|member| member.roles.iter().any(|role| roles.contains(role)));
+        (complete, any_has_role)
+    });
+    if let Some((complete, any_has_role)) = cached_members
+        && can_skip_role_update(!birthday_users.is_empty(), complete, any_has_role)
+    {
+        info!("No birthday roles to update in guild {}", guild_id);
+        return Ok(0);
+    }
+
     // Get all members in the guild
-    let members = match guild_id.members(http, None, None).await {
+    let members = match fetch_guild_members(http, cache, guild_id).await {
         Ok(m) => m,
         Err(e) => {
             error!("Failed to get members for guild {}: {}", guild_id, e);
@@ -235,47 +777,105 @@ pub async fn run_birthday_role_update(
         }
     };
 
-    // Process role updates using pure function
-    for member in members {
-        let has_birthday_today = birthday_user_ids.contains(&member.user.id);
-        let has_birthday_role = member.roles.contains(&role_id);
-
-        // Use pure function to determine action
-        let action = determine_role_action(has_birthday_today, has_birthday_role);
-
-        match action {
-            RoleAction::Add => {
-                // Add birthday role
-                if let Err(e) = member.add_role(http, role_id).await {
-                    error!(
-                        "Failed to add birthday role to user {} in guild {}: {}",
-                        member.user.id, guild_id, e
-                    );
-                } else {
-                    info!(
-                        "Added birthday role to user {} in guild {}",
-                        member.user.id, guild_id
-                    );
-                }
-            }
-            RoleAction::Remove => {
-                // Remove birthday role
-                if let Err(e) = member.remove_role(http, role_id).await {
-                    error!(
-                        "Failed to remove birthday role from user {} in guild {}: {}",
-                        member.user.id, guild_id, e
-                    );
-                } else {
-                    info!(
-                        "Removed birthday role from user {} in guild {}",
-                        member.user.id, guild_id
-                    );
-                }
+    // Who should hold each role today (only members of this guild)
+    let mut targets: Vec<(RoleId, &str, HashSet<UserId>)> = Vec::new();
+    if let Some(role_id) = role_id {
+        let wanted = birthday_users
+            .keys()
+            .filter(|user_id| members.contains_key(user_id))
+            .copied()
+            .collect();
+        targets.push((role_id, "birthday", wanted));
+    }
+    let ages = birthday_users
+        .iter()
+        .filter(|(user_id, _)| members.contains_key(user_id))
+        .map(|(user_id, age)| (*user_id, *age));
+    let mut milestone_targets = milestone_role_targets(ages, &milestone_roles);
+    for (_, milestone_role_id) in &milestone_roles {
+        let wanted = milestone_targets.remove(milestone_role_id).unwrap_or_default();
+        targets.push((*milestone_role_id, "milestone", wanted));
+    }
+
+    // Compute every change up front, then apply them one at a time, each followed by the
+    // configured delay (`ROLE_UPDATE_DELAY_MS`)
+    let mut planned = 0;
+    let mut changes = 0;
+    for (role_id, role_kind, wanted) in targets {
+        let holders: HashSet<UserId> = members
+            .values()
+            .filter(|member| member.roles.contains(&role_id))
+            .map(|member| member.user.id)
+            .collect();
+        let (to_add, to_remove) = calculate_role_changes(&wanted, &holders);
+        planned += count_role_changes(&to_add, &to_remove);
+
+        let actions = to_add
+            .iter()
+            .map(|user_id| (user_id, RoleAction::Add))
+            .chain(to_remove.iter().map(|user_id| (user_id, RoleAction::Remove)));
+        for (user_id, action) in actions {
+            let member = &members[user_id];
+            if apply_role_action(http, data, guild_id, member, role_id, action, role_kind).await {
+                changes += 1;
             }
-            RoleAction::NoAction => {} // No action needed
         }
     }
 
-    info!("Birthday role update completed");
-    Ok(())
+    info!(
+        "Birthday role update completed in guild {}: {} of {} role change(s) applied",
+        guild_id, changes, planned
+    );
+    Ok(changes)
+}
+
+/// Add or remove a role from a member according to the computed action.
+/// Returns whether the role was actually changed.
+async fn apply_role_action(
+    http: &Arc<serenity::Http>,
+    data: &Data,
+    guild_id: GuildId,
+    member: &serenity::Member,
+    role_id: RoleId,
+    action: RoleAction,
+    role_kind: &str,
+) -> bool {
+    let changed = match action {
+        RoleAction::Add => {
+            if let Err(e) = member.add_role(http, role_id).await {
+                data.record_http_error(&e);
+                error!(
+                    "Failed to add {} role to user {} in guild {}: {}",
+                    role_kind, member.user.id, guild_id, e
+                );
+                false
+            } else {
+                info!(
+                    "Added {} role to user {} in guild {}",
+                    role_kind, member.user.id, guild_id
+                );
+                true
+            }
+        }
+        RoleAction::Remove => {
+            if let Err(e) = member.remove_role(http, role_id).await {
+                data.record_http_error(&e);
+                error!(
+                    "Failed to remove {} role from user {} in guild {}: {}",
+                    role_kind, member.user.id, guild_id, e
+                );
+                false
+            } else {
+                info!(
+                    "Removed {} role from user {} in guild {}",
+                    role_kind, member.user.id, guild_id
+                );
+                true
+            }
+        }
+    };
+
+    // Pace role changes, a birthday role update can touch many members in a row
+    tokio::time::sleep(data.role_update_delay).await;
+    changed
 }
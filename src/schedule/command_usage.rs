@@ -0,0 +1,49 @@
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, interval};
+use tracing::{debug, error, info};
+
+use crate::constants::COMMAND_USAGE_FLUSH_INTERVAL_SECS;
+use crate::models::Data;
+
+/// Start the background task that periodically flushes command usage counts to the database,
+/// until the bot shuts down (the last counts are flushed by the shutdown itself)
+pub fn start_command_usage_flusher(data: Arc<Data>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "Command usage flusher started (every {} seconds)",
+            COMMAND_USAGE_FLUSH_INTERVAL_SECS
+        );
+
+        let mut ticker = interval(Duration::from_secs(COMMAND_USAGE_FLUSH_INTERVAL_SECS));
+        let mut shutdown_rx = data.shutdown_tx.subscribe();
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => flush_command_usage(&data).await,
+                _ = shutdown_rx.changed() => break,
+            }
+        }
+
+        info!("Command usage flusher stopped");
+    })
+}
+
+/// Write the pending command usage counts in a single batch
+pub async fn flush_command_usage(data: &Data) {
+    let counts = data.take_command_usage();
+    if counts.is_empty() {
+        return;
+    }
+
+    match data.db.add_command_usage(&counts).await {
+        Ok(()) => debug!("Flushed usage counts of {} command(s)", counts.len()),
+        Err(e) => {
+            error!("Failed to flush command usage counts: {}", e);
+            // Keep the counts for the next flush
+            for (command, count) in counts {
+                *data.command_usage.entry(command).or_default() += count;
+            }
+        }
+    }
+}
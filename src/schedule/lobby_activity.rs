@@ -0,0 +1,45 @@
+use std::sync::Arc;
+use tokio::task::JoinHandle;
+use tokio::time::{Duration, interval};
+use tracing::{error, info};
+
+use crate::constants::{LOBBY_ACTIVITY_RETENTION_DAYS, LOBBY_ACTIVITY_SAMPLE_INTERVAL_SECS};
+use crate::models::Data;
+
+/// Start the background task that periodically samples active temp channel counts per guild,
+/// until the bot shuts down
+pub fn start_lobby_activity_sampler(data: Arc<Data>) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        info!(
+            "Lobby activity sampler started (every {} seconds)",
+            LOBBY_ACTIVITY_SAMPLE_INTERVAL_SECS
+        );
+
+        let mut ticker = interval(Duration::from_secs(LOBBY_ACTIVITY_SAMPLE_INTERVAL_SECS));
+        let mut shutdown_rx = data.shutdown_tx.subscribe();
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown_rx.changed() => break,
+            }
+
+            // Counts are taken from the database, which every channel change is written to
+            match data.db.record_lobby_activity_samples().await {
+                Ok(guilds) => info!("Recorded lobby activity samples for {} guild(s)", guilds),
+                Err(e) => error!("Failed to record lobby activity samples: {}", e),
+            }
+
+            // Keep sample retention bounded
+            if let Err(e) = data
+                .db
+                .prune_lobby_activity_samples(LOBBY_ACTIVITY_RETENTION_DAYS)
+                .await
+            {
+                error!("Failed to prune old lobby activity samples: {}", e);
+            }
+        }
+
+        info!("Lobby activity sampler stopped");
+    })
+}
@@ -2,84 +2,237 @@ use chrono::Utc;
 use poise::serenity_prelude as serenity;
 use std::str::FromStr;
 use std::sync::Arc;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
 use tokio::time::{Duration, sleep};
-use tracing::{error, info, warn};
+use tracing::{Instrument, error, info, info_span, warn};
 
+use crate::constants::SCHEDULE_MANAGER_RESTART_DELAY_SECS;
 use crate::models::Data;
+use crate::utils::db_retry::with_db_retry;
 use super::{Schedule, ScheduleType};
+use super::utils::missed_run_today;
+use super::announcement_tasks::run_announcement;
 use super::birthday_tasks::{run_birthday_check, run_birthday_role_update, run_birthday_role_update_all_guilds};
 
-/// Start the schedule manager that monitors and runs scheduled tasks
+/// Start the schedule manager that monitors and runs scheduled tasks.
+/// The manager is supervised and restarted if it ever panics, the task ends on shutdown.
 pub fn start_schedule_manager(
     ctx: Arc<serenity::Http>,
     cache: Arc<serenity::Cache>,
     data: Arc<Data>,
-) {
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        info!("Schedule manager started");
-        
-        let mut reload_rx = data.schedule_reload_tx.subscribe();
-
         loop {
-            // Load schedules from database
-            match data.db.get_all_schedules().await {
-                Ok(schedules) => {
-                    if schedules.is_empty() {
-                        info!("No schedules configured, waiting for schedules to be added...");
-                        // Wait for a reload signal instead of sleeping for an hour
-                        if reload_rx.changed().await.is_ok() {
-                            info!("Schedule reload signal received, reloading schedules");
-                            continue;
-                        } else {
-                            // Channel closed, exit
-                            break;
-                        }
+            let manager = tokio::spawn(run_schedule_manager(
+                Arc::clone(&ctx),
+                Arc::clone(&cache),
+                Arc::clone(&data),
+            ));
+
+            match manager.await {
+                // The manager only returns on shutdown
+                Ok(()) => break,
+                Err(e) if e.is_panic() => {
+                    error!(
+                        "Schedule manager panicked, restarting in {} seconds",
+                        SCHEDULE_MANAGER_RESTART_DELAY_SECS
+                    );
+                    sleep(Duration::from_secs(SCHEDULE_MANAGER_RESTART_DELAY_SECS)).await;
+                    if *data.shutdown_tx.borrow() {
+                        break;
                     }
+                }
+                Err(e) => {
+                    error!("Schedule manager task was cancelled: {}", e);
+                    break;
+                }
+            }
+        }
+    })
+}
 
-                    // Find the next schedule to run
-                    if let Some((schedule, wait_duration)) = find_next_schedule(&schedules) {
-                        info!(
-                            "Next {:?} schedule (cron: '{}') will run in {} minutes",
-                            schedule.schedule_type,
-                            schedule.cron_expression,
-                            wait_duration.as_secs() / 60
-                        );
-
-                        // Wait until it's time to run OR until we get a reload signal
-                        tokio::select! {
-                            _ = sleep(wait_duration) => {
-                                // Time to run the scheduled task
-                                if let Err(e) = run_schedule(&ctx, &cache, &data, &schedule).await {
-                                    error!("Failed to run {:?} schedule: {}", schedule.schedule_type, e);
-                                }
-                            }
-                            _ = reload_rx.changed() => {
-                                // Reload signal received, restart the loop
-                                info!("Schedule reload signal received, reconfiguring schedules");
-                                continue;
-                            }
-                        }
+/// Monitor schedules and run each one when it is due, until the bot shuts down
+async fn run_schedule_manager(
+    ctx: Arc<serenity::Http>,
+    cache: Arc<serenity::Cache>,
+    data: Arc<Data>,
+) {
+    info!("Schedule manager started");
+    
+    let mut reload_rx = data.schedule_reload_tx.subscribe();
+    let mut shutdown_rx = data.shutdown_tx.subscribe();
+
+    // Run today's occurrences that were due while the bot was offline
+    catch_up_missed_schedules(&ctx, &cache, &data).await;
+
+    loop {
+        // Load schedules from database
+        let db = &data.db;
+        match with_db_retry("loading schedules", move || db.get_all_schedules()).await {
+            Ok(schedules) => {
+                if schedules.is_empty() {
+                    data.next_schedule_tx.send_replace(None);
+                    info!("No schedules configured, waiting for schedules to be added...");
+                    // Wait for a reload signal instead of sleeping for an hour
+                    if wait_for_reload(&mut reload_rx, &mut shutdown_rx).await {
+                        continue;
                     } else {
-                        // No valid schedules, wait for a reload signal
-                        info!("No valid schedules found, waiting for configuration...");
-                        if reload_rx.changed().await.is_ok() {
-                            info!("Schedule reload signal received, reloading schedules");
+                        break;
+                    }
+                }
+
+                // Find the next schedule to run
+                if let Some((schedule, wait_duration)) = find_next_schedule(&schedules) {
+                    info!(
+                        "Next {:?} schedule (cron: '{}') will run in {} minutes",
+                        schedule.schedule_type,
+                        schedule.cron_expression,
+                        wait_duration.as_secs() / 60
+                    );
+
+                    let fire_time = Utc::now()
+                        + chrono::Duration::from_std(wait_duration).unwrap_or_default();
+                    data.next_schedule_tx
+                        .send_replace(Some((schedule.schedule_type.clone(), fire_time)));
+
+                    // Wait until it's time to run OR until we get a reload signal
+                    tokio::select! {
+                        _ = sleep(wait_duration) => {
+                            // Time to run the scheduled task
+                            run_schedule_guarded(&ctx, &cache, &data, &schedule).await;
+                        }
+                        _ = reload_rx.changed() => {
+                            // Reload signal received, restart the loop
+                            info!("Schedule reload signal received, reconfiguring schedules");
                             continue;
-                        } else {
-                            // Channel closed, exit
-                            break;
                         }
+                        _ = shutdown_rx.changed() => break,
+                    }
+                } else {
+                    // No valid schedules, wait for a reload signal
+                    data.next_schedule_tx.send_replace(None);
+                    info!("No valid schedules found, waiting for configuration...");
+                    if wait_for_reload(&mut reload_rx, &mut shutdown_rx).await {
+                        continue;
+                    } else {
+                        break;
                     }
                 }
-                Err(e) => {
-                    error!("Failed to load schedules from database: {}", e);
-                    sleep(Duration::from_secs(60)).await; // Retry in 1 minute
+            }
+            Err(e) => {
+                error!("Failed to load schedules from database: {}", e);
+                // Retry in 1 minute
+                tokio::select! {
+                    _ = sleep(Duration::from_secs(60)) => {}
+                    _ = shutdown_rx.changed() => break,
                 }
             }
         }
-        
-        info!("Schedule manager stopped");
-    });
+    }
+    
+    info!("Schedule manager stopped");
+}
+
+/// Wait for a reload signal. Returns false when the bot is shutting down instead
+/// (or the reload channel is closed).
+async fn wait_for_reload(
+    reload_rx: &mut watch::Receiver<u64>,
+    shutdown_rx: &mut watch::Receiver<bool>,
+) -> bool {
+    tokio::select! {
+        changed = reload_rx.changed() => {
+            if changed.is_ok() {
+                info!("Schedule reload signal received, reloading schedules");
+            }
+            changed.is_ok()
+        }
+        _ = shutdown_rx.changed() => false,
+    }
+}
+
+/// Run a scheduled task in its own task, so that a panic is logged instead of
+/// taking the manager down with it
+async fn run_schedule_guarded(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Arc<Data>,
+    schedule: &Schedule,
+) {
+    let task = {
+        let http = Arc::clone(http);
+        let cache = Arc::clone(cache);
+        let data = Arc::clone(data);
+        let schedule = schedule.clone();
+        // Attribute every log line of the run to its schedule
+        let span = info_span!(
+            "schedule_run",
+            schedule_id = schedule.id,
+            label = %schedule.label,
+            guild_id = schedule.guild_id,
+        );
+        tokio::spawn(async move { run_schedule(&http, &cache, &data, &schedule).await }.instrument(span))
+    };
+
+    match task.await {
+        Ok(Ok(())) => {
+            if let Err(e) = data.db.set_schedule_last_run(schedule.id, Utc::now()).await {
+                error!("Failed to record last run of schedule {}: {}", schedule.id, e);
+            }
+        }
+        Ok(Err(e)) => error!("Failed to run {:?} schedule: {}", schedule.schedule_type, e),
+        Err(e) if e.is_panic() => error!(
+            "{:?} schedule (guild {:?}) panicked, continuing with the next schedule",
+            schedule.schedule_type, schedule.guild_id
+        ),
+        Err(e) => error!("{:?} schedule task was cancelled: {}", schedule.schedule_type, e),
+    }
+}
+
+/// Run once every enabled schedule that missed an occurrence today, e.g. because the bot was
+/// offline at 8 AM. Schedules that already ran since that occurrence are left alone.
+async fn catch_up_missed_schedules(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Arc<Data>,
+) {
+    let db = &data.db;
+    let schedules = match with_db_retry("loading schedules", move || db.get_all_schedules()).await {
+        Ok(schedules) => schedules,
+        Err(e) => {
+            error!("Failed to load schedules for catch-up: {}", e);
+            return;
+        }
+    };
+
+    let now = Utc::now();
+    for schedule in schedules.iter().filter(|s| s.enabled) {
+        let Some(missed) = missed_run_today(&schedule.cron_expression, schedule.last_run, now) else {
+            continue;
+        };
+
+        warn!(
+            "{:?} schedule '{}' (guild {:?}) missed its run at {}, catching up",
+            schedule.schedule_type, schedule.label, schedule.guild_id, missed
+        );
+        run_schedule_guarded(http, cache, data, schedule).await;
+    }
+}
+
+/// Delete the schedules of a guild the bot was removed from (or that was deleted), so the
+/// manager stops picking them
+pub async fn prune_guild_schedules(guild_id: serenity::GuildId, data: &Data) {
+    match data.db.delete_schedules_for_guild(guild_id).await {
+        Ok(0) => {}
+        Ok(count) => {
+            info!(
+                "Pruned {} schedule(s) of guild {} the bot is no longer in",
+                count, guild_id
+            );
+            data.schedule_reload_tx.send_modify(|v| *v += 1);
+        }
+        Err(e) => error!("Failed to prune schedules of guild {}: {}", guild_id, e),
+    }
 }
 
 /// Find the next schedule to run and calculate wait duration (more functional approach)
@@ -136,25 +289,49 @@ async fn run_schedule(
     data: &Data,
     schedule: &Schedule,
 ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
-    match schedule.schedule_type {
-        ScheduleType::Birthday => {
-            // Birthday notifications are guild-specific
-            if let Some(guild_id) = schedule.guild_id {
-                run_birthday_check(http, cache, data, guild_id).await
-            } else {
-                error!("Birthday schedule has no guild_id, skipping");
-                Ok(())
-            }
+    if schedule.schedule_type == ScheduleType::Birthday && schedule.guild_id.is_none() {
+        error!("Birthday schedule has no guild_id, skipping");
+        return Ok(());
+    }
+
+    // Announcements carry their own message, the other types run the same task for every schedule
+    let count = if schedule.schedule_type == ScheduleType::Announcement {
+        run_announcement(http, data, schedule).await?
+    } else {
+        run_schedule_task(http, cache, data, &schedule.schedule_type, schedule.guild_id).await?
+    };
+    info!("{:?} schedule completed ({} change(s))", schedule.schedule_type, count);
+    Ok(())
+}
+
+/// Run the task of a schedule type right away, for one guild or all of them.
+/// Shared by the scheduler and the manual trigger. Returns how many birthdays were
+/// announced or how many roles were changed.
+#[tracing::instrument(skip_all, fields(schedule_type = ?schedule_type))]
+pub async fn run_schedule_task(
+    http: &Arc<serenity::Http>,
+    cache: &Arc<serenity::Cache>,
+    data: &Data,
+    schedule_type: &ScheduleType,
+    guild_id: Option<i64>,
+) -> Result<usize, Box<dyn std::error::Error + Send + Sync>> {
+    match (schedule_type, guild_id) {
+        // Birthday notifications are guild-specific
+        (ScheduleType::Birthday, Some(guild_id)) => {
+            run_birthday_check(http, cache, data, guild_id).await
         }
-        ScheduleType::BirthdayRole => {
-            // BirthdayRole can be guild-specific or global
-            if let Some(guild_id) = schedule.guild_id {
-                // Guild-specific: run for this guild only
-                run_birthday_role_update(http, cache, data, guild_id).await
-            } else {
-                // Global: run for all guilds (legacy behavior)
-                run_birthday_role_update_all_guilds(http, cache, data).await
-            }
+        (ScheduleType::Birthday, None) => Ok(0),
+        // BirthdayRole can be guild-specific or global
+        (ScheduleType::BirthdayRole, Some(guild_id)) => {
+            run_birthday_role_update(http, cache, data, guild_id).await
+        }
+        // Global: run for all guilds (legacy behavior)
+        (ScheduleType::BirthdayRole, None) => {
+            run_birthday_role_update_all_guilds(http, cache, data).await
+        }
+        // Each announcement has its own message, they only run from their schedule
+        (ScheduleType::Announcement, _) => {
+            Err("announcements can only run from their own schedule".into())
         }
     }
 }
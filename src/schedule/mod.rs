@@ -1,9 +1,30 @@
 /// Schedule management modules
+#[cfg(feature = "birthday")]
 mod manager;
+#[cfg(feature = "birthday")]
 mod birthday_tasks;
+#[cfg(feature = "birthday")]
+mod announcement_tasks;
+#[cfg(feature = "lobby")]
+mod lobby_activity;
+#[cfg(feature = "birthday")]
 mod types;
+mod command_usage;
+#[cfg(feature = "birthday")]
 mod utils;
 
 // Re-export public types and functions
+#[cfg(feature = "birthday")]
 pub use types::{Schedule, ScheduleType};
-pub use manager::start_schedule_manager;
+#[cfg(feature = "birthday")]
+pub use manager::{prune_guild_schedules, run_schedule_task, start_schedule_manager};
+#[cfg(feature = "birthday")]
+pub use birthday_tasks::{
+    build_birthday_embed, fetch_guild_members, prepare_birthday_preview,
+    send_birthday_notification,
+};
+#[cfg(feature = "birthday")]
+pub use utils::{next_run_from_cron, validate_cron_expression};
+#[cfg(feature = "lobby")]
+pub use lobby_activity::start_lobby_activity_sampler;
+pub use command_usage::{flush_command_usage, start_command_usage_flusher};
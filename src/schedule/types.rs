@@ -1,9 +1,18 @@
+use chrono::{DateTime, Utc};
+
+use super::utils::next_run_from_cron;
+
 /// Type of scheduled task
-#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type)]
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::Type, poise::ChoiceParameter)]
 #[sqlx(type_name = "schedule_type", rename_all = "lowercase")]
 pub enum ScheduleType {
+    #[name = "Birthday announcement"]
     Birthday,
+    #[name = "Birthday roles"]
     BirthdayRole,
+    /// Custom message posted in a channel (see `/add_announcement`)
+    #[name = "Announcement"]
+    Announcement,
 }
 
 /// A scheduled task configuration
@@ -12,6 +21,44 @@ pub struct Schedule {
     pub id: i32,
     pub guild_id: Option<i64>, // None means it runs for all guilds (e.g., BirthdayRole)
     pub schedule_type: ScheduleType,
+    pub label: String, // Tells apart several schedules of the same type in a guild
     pub cron_expression: String, // Cron expression (e.g., "0 0 8 * * *" for 8 AM daily)
     pub enabled: bool,
+    pub last_run: Option<DateTime<Utc>>, // Last successful run, used to catch up after downtime
+}
+
+impl Schedule {
+    /// Next time this schedule fires, or None if its cron expression is invalid or exhausted
+    pub fn next_run(&self) -> Option<DateTime<Utc>> {
+        next_run_from_cron(&self.cron_expression)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn schedule(cron_expression: &str) -> Schedule {
+        Schedule {
+            id: 1,
+            guild_id: Some(1),
+            schedule_type: ScheduleType::Birthday,
+            label: "default".to_string(),
+            cron_expression: cron_expression.to_string(),
+            enabled: true,
+            last_run: None,
+        }
+    }
+
+    #[test]
+    fn test_next_run() {
+        let next = schedule("0 0 8 * * *").next_run().expect("valid cron");
+        assert!(next > Utc::now());
+        assert_eq!(next.format("%H:%M:%S").to_string(), "08:00:00");
+    }
+
+    #[test]
+    fn test_next_run_invalid_cron() {
+        assert_eq!(schedule("not a cron").next_run(), None);
+    }
 }
@@ -1,7 +1,52 @@
+use chrono::{DateTime, Utc};
+use std::str::FromStr;
+
+/// Next time a cron expression fires, or None if it is invalid or exhausted
+pub fn next_run_from_cron(cron_expression: &str) -> Option<DateTime<Utc>> {
+    cron::Schedule::from_str(cron_expression)
+        .ok()?
+        .upcoming(Utc)
+        .next()
+}
+
+/// Check that a cron expression can be parsed and fires at least once more, so it is never
+/// saved only to be skipped by the schedule manager
+pub fn validate_cron_expression(cron_expression: &str) -> Result<(), String> {
+    let schedule = cron::Schedule::from_str(cron_expression)
+        .map_err(|e| format!("Invalid cron expression `{}`: {}", cron_expression, e))?;
+
+    if schedule.upcoming(Utc).next().is_none() {
+        return Err(format!("Cron expression `{}` never fires again", cron_expression));
+    }
+
+    Ok(())
+}
+
+/// Latest occurrence of a cron expression that was due since the last run but didn't happen,
+/// only looking at the current (UTC) day so a long outage triggers at most one catch-up.
+/// Returns None for a schedule that never ran, as there is no way to tell what was missed.
+pub fn missed_run_today(
+    cron_expression: &str,
+    last_run: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Option<DateTime<Utc>> {
+    let last_run = last_run?;
+    let start_of_day = now.date_naive().and_hms_opt(0, 0, 0)?.and_utc();
+    // The occurrence at midnight itself must count as missed too
+    let since = last_run.max(start_of_day - chrono::Duration::seconds(1));
+
+    cron::Schedule::from_str(cron_expression)
+        .ok()?
+        .after(&since)
+        .take_while(|time| *time <= now)
+        .last()
+}
+
 /// Tests for utility functions
 #[cfg(test)]
 mod tests {
     use crate::utils::datetime::get_month_name;
+        use super::{missed_run_today, next_run_from_cron, validate_cron_expression};
 
     #[test]
     fn test_get_month_name_valid() {
@@ -29,4 +74,72 @@ mod tests {
             assert_eq!(get_month_name((i + 1) as i32), expected_name);
         }
     }
+
+        #[test]
+    fn test_next_run_from_cron() {
+        let next = next_run_from_cron("0 30 8 * * *").expect("valid cron");
+        assert!(next > chrono::Utc::now());
+        assert_eq!(next.format("%H:%M:%S").to_string(), "08:30:00");
+        assert!(next - chrono::Utc::now() <= chrono::Duration::days(1));
+    }
+
+        #[test]
+    fn test_next_run_from_cron_invalid() {
+        assert_eq!(next_run_from_cron("not a cron"), None);
+        assert_eq!(next_run_from_cron(""), None);
+    }
+
+        #[test]
+    fn test_validate_cron_expression_valid() {
+        assert!(validate_cron_expression("0 0 8 * * *").is_ok());
+        assert!(validate_cron_expression("0 30 22 * * Mon-Fri").is_ok());
+        assert!(validate_cron_expression("0 0 */2 * * *").is_ok());
+    }
+
+        #[test]
+    fn test_validate_cron_expression_invalid() {
+        assert!(validate_cron_expression("").is_err());
+        assert!(validate_cron_expression("not a cron").is_err());
+        assert!(validate_cron_expression("0 0 25 * * *").is_err());
+        assert!(validate_cron_expression("0 61 8 * * *").is_err());
+        // Only fired in a past year
+        assert!(validate_cron_expression("0 0 8 1 1 * 2000").is_err());
+    }
+
+        fn utc(hour: u32, minute: u32) -> chrono::DateTime<chrono::Utc> {
+        chrono::NaiveDate::from_ymd_opt(2024, 5, 10)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+            .and_utc()
+    }
+
+        #[test]
+    fn test_missed_run_today() {
+        let yesterday = utc(8, 0) - chrono::Duration::days(1);
+
+        // Down at 8 AM, back at 10 AM: the 8 AM run is caught up
+        assert_eq!(missed_run_today("0 0 8 * * *", Some(yesterday), utc(10, 0)), Some(utc(8, 0)));
+        // Already ran today
+        assert_eq!(missed_run_today("0 0 8 * * *", Some(utc(8, 0)), utc(10, 0)), None);
+        // Not due yet today
+        assert_eq!(missed_run_today("0 0 8 * * *", Some(yesterday), utc(7, 0)), None);
+        // A midnight run is still today's
+        assert_eq!(missed_run_today("0 0 0 * * *", Some(yesterday), utc(1, 0)), Some(utc(0, 0)));
+    }
+
+        #[test]
+    fn test_missed_run_today_caps_to_current_day() {
+        // Down for a week: only today's occurrence is returned
+        let last_week = utc(8, 0) - chrono::Duration::days(7);
+        assert_eq!(missed_run_today("0 0 8 * * *", Some(last_week), utc(9, 0)), Some(utc(8, 0)));
+        // Several occurrences missed today: only the latest one
+        assert_eq!(missed_run_today("0 0 */2 * * *", Some(last_week), utc(9, 0)), Some(utc(8, 0)));
+    }
+
+        #[test]
+    fn test_missed_run_today_without_history() {
+        assert_eq!(missed_run_today("0 0 8 * * *", None, utc(10, 0)), None);
+        assert_eq!(missed_run_today("not a cron", Some(utc(0, 0)), utc(10, 0)), None);
+    }
 }
@@ -3,6 +3,7 @@ use chrono::Datelike;
 use poise::serenity_prelude::{ChannelId, GuildId, RoleId, UserId};
 
 use crate::database::Database;
+use crate::utils::datetime::validate_birth_year;
 
 /// Configuration for setting up birthday notifications in a guild
 #[derive(Debug, Clone)]
@@ -65,7 +66,7 @@ impl<'a> BirthdayService<'a> {
         Self::validate_birthday_date(birthday.month, birthday.day)?;
 
         if let Some(year) = birthday.year {
-            Self::validate_birth_year(year)?;
+            validate_birth_year(year, chrono::Utc::now().year())?;
         }
 
         self.db
@@ -102,7 +103,7 @@ impl<'a> BirthdayService<'a> {
 
         Ok(users
             .into_iter()
-            .map(|(user_id, year)| UserBirthday {
+            .map(|(user_id, year, _)| UserBirthday {
                 user_id,
                 month,
                 day,
@@ -130,21 +131,6 @@ impl<'a> BirthdayService<'a> {
 
         Ok(())
     }
-
-    /// Validate birth year
-    fn validate_birth_year(year: i32) -> Result<(), &'static str> {
-        let current_year = chrono::Utc::now().year();
-
-        if year < 1900 {
-            return Err("Birth year must be 1900 or later");
-        }
-
-        if year > current_year {
-            return Err("Birth year cannot be in the future");
-        }
-
-        Ok(())
-    }
 }
 
 #[cfg(test)]
@@ -163,15 +149,6 @@ mod tests {
         assert!(BirthdayService::validate_birthday_date(4, 31).is_err());
     }
 
-    #[test]
-    fn test_validate_birth_year() {
-        assert!(BirthdayService::validate_birth_year(2000).is_ok());
-        assert!(BirthdayService::validate_birth_year(1900).is_ok());
-
-        assert!(BirthdayService::validate_birth_year(1899).is_err());
-        assert!(BirthdayService::validate_birth_year(2030).is_err());
-    }
-
     #[test]
     fn test_user_birthday_age_calculation() {
         let birthday = UserBirthday {
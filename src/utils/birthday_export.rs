@@ -0,0 +1,82 @@
+/// Pure serialization of birthday export files (Discord-agnostic)
+use poise::serenity_prelude::UserId;
+
+/// A stored birthday: `(user_id, month, day, year)`, without the year of users hiding their age
+pub type ExportedBirthday = (UserId, i32, i32, Option<i32>);
+
+/// Serialize birthdays as CSV rows of `user_id,month,day,year` with a header line,
+/// the format accepted by `/import_birthdays`
+pub fn export_birthdays_csv(birthdays: &[ExportedBirthday]) -> String {
+    let mut lines = Vec::with_capacity(birthdays.len() + 1);
+    lines.push("user_id,month,day,year".to_string());
+    lines.extend(birthdays.iter().map(|(user_id, month, day, year)| {
+        let year = year.map(|year| year.to_string()).unwrap_or_default();
+        format!("{},{},{},{}", user_id, month, day, year)
+    }));
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+/// Serialize birthdays as a JSON array of `{"user_id", "month", "day", "year"}` objects.
+/// User IDs are written as strings since they don't fit in a JavaScript number.
+pub fn export_birthdays_json(birthdays: &[ExportedBirthday]) -> String {
+    let entries: Vec<serde_json::Value> = birthdays
+        .iter()
+        .map(|(user_id, month, day, year)| {
+            serde_json::json!({
+                "user_id": user_id.to_string(),
+                "month": month,
+                "day": day,
+                "year": year,
+            })
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).unwrap_or_else(|_| "[]".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::birthday_import::parse_birthday_csv;
+
+    fn sample() -> Vec<ExportedBirthday> {
+        vec![
+            (UserId::new(123), 1, 15, Some(1990)),
+            (UserId::new(456), 12, 31, None),
+        ]
+    }
+
+    #[test]
+    fn test_export_birthdays_csv() {
+        assert_eq!(
+            export_birthdays_csv(&sample()),
+            "user_id,month,day,year\n123,1,15,1990\n456,12,31,\n"
+        );
+        assert_eq!(export_birthdays_csv(&[]), "user_id,month,day,year\n");
+    }
+
+    #[test]
+    fn test_export_birthdays_csv_round_trip() {
+        let parsed = parse_birthday_csv(&export_birthdays_csv(&sample()), 2024);
+        assert!(parsed.errors.is_empty());
+        let rows: Vec<_> = parsed
+            .rows
+            .iter()
+            .map(|row| (row.user_id, row.month, row.day, row.year))
+            .collect();
+        assert_eq!(rows, vec![(123, 1, 15, Some(1990)), (456, 12, 31, None)]);
+    }
+
+    #[test]
+    fn test_export_birthdays_json() {
+        let json: serde_json::Value = serde_json::from_str(&export_birthdays_json(&sample())).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "user_id": "123", "month": 1, "day": 15, "year": 1990 },
+                { "user_id": "456", "month": 12, "day": 31, "year": null },
+            ])
+        );
+        assert_eq!(export_birthdays_json(&[]), "[]");
+    }
+}
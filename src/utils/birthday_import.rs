@@ -0,0 +1,334 @@
+/// Pure parsing of birthday import files (Discord-agnostic)
+use std::collections::HashMap;
+
+use crate::utils::datetime::{date_exists, is_valid_date, validate_birth_year};
+
+/// A birthday row accepted for import
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedBirthday {
+    /// Line of the row in the file (1-based), or position of the entry in a JSON file
+    pub line: usize,
+    pub user_id: u64,
+    pub month: i32,
+    pub day: i32,
+    pub year: Option<i32>,
+}
+
+/// A row rejected during import, with the reason
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportError {
+    /// Line of the row in the file (1-based), or position of the entry in a JSON file
+    pub line: usize,
+    pub message: String,
+}
+
+/// Result of parsing an import file
+#[derive(Debug, Default)]
+pub struct ImportParseResult {
+    pub rows: Vec<ImportedBirthday>,
+    pub errors: Vec<ImportError>,
+}
+
+/// How an import would change the stored birthdays
+#[derive(Debug, Default, PartialEq)]
+pub struct ImportSummary {
+    /// Users without a stored birthday
+    pub added: usize,
+    /// Users whose stored birthday differs
+    pub overwritten: usize,
+    /// Users whose stored birthday is identical
+    pub unchanged: usize,
+}
+
+/// Compare the parsed rows with the stored birthdays, keyed by user ID
+pub fn summarize_import(
+    rows: &[ImportedBirthday],
+    existing: &HashMap<u64, (i32, i32, Option<i32>)>,
+) -> ImportSummary {
+    let mut summary = ImportSummary::default();
+    for row in rows {
+        match existing.get(&row.user_id) {
+            None => summary.added += 1,
+            Some(&stored) if stored == (row.month, row.day, row.year) => summary.unchanged += 1,
+            Some(_) => summary.overwritten += 1,
+        }
+    }
+    summary
+}
+
+/// Parse a CSV file of `user_id,month,day,year` rows (year may be empty).
+/// A header line is detected and skipped when its first field is not a number.
+/// When a user appears several times, the last row wins.
+pub fn parse_birthday_csv(content: &str, current_year: i32) -> ImportParseResult {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes());
+
+    let mut result = ImportParseResult::default();
+    let mut index_by_user: HashMap<u64, usize> = HashMap::new();
+
+    for (position, record) in reader.records().enumerate() {
+        let record = match record {
+            Ok(record) => record,
+            Err(e) => {
+                let line = e.position().map_or(position + 1, |p| p.line() as usize);
+                result.errors.push(ImportError {
+                    line,
+                    message: format!("Malformed row: {}", e),
+                });
+                continue;
+            }
+        };
+        let line = record.position().map_or(position + 1, |p| p.line() as usize);
+
+        // Skip blank lines and the header
+        if record.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+        if position == 0 && record.get(0).is_some_and(|field| field.parse::<u64>().is_err()) {
+            continue;
+        }
+
+        let fields: Vec<&str> = record.iter().collect();
+        push_parsed_row(&mut result, &mut index_by_user, line, parse_row(&fields, current_year));
+    }
+
+    result
+}
+
+/// Parse a JSON array of `{"user_id", "month", "day", "year"}` objects, as written by
+/// `/export_birthdays`. Fields may be numbers or strings, and `year` may be null or missing.
+/// Entries are numbered from 1 in errors. When a user appears several times, the last entry wins.
+pub fn parse_birthday_json(content: &str, current_year: i32) -> ImportParseResult {
+    let mut result = ImportParseResult::default();
+
+    let entries = match serde_json::from_str::<serde_json::Value>(content) {
+        Ok(serde_json::Value::Array(entries)) => entries,
+        Ok(_) => {
+            result.errors.push(ImportError {
+                line: 1,
+                message: "Expected a JSON array of birthdays".to_string(),
+            });
+            return result;
+        }
+        Err(e) => {
+            result.errors.push(ImportError {
+                line: e.line(),
+                message: format!("Malformed JSON: {}", e),
+            });
+            return result;
+        }
+    };
+
+    let mut index_by_user: HashMap<u64, usize> = HashMap::new();
+    for (position, entry) in entries.iter().enumerate() {
+        let line = position + 1;
+        let Some(object) = entry.as_object() else {
+            result.errors.push(ImportError {
+                line,
+                message: "Expected an object with user_id, month, day and year".to_string(),
+            });
+            continue;
+        };
+
+        // Reuse the CSV validation by reading every field as text
+        let fields: Vec<String> = ["user_id", "month", "day", "year"]
+            .iter()
+            .map(|key| match object.get(*key) {
+                Some(serde_json::Value::String(text)) => text.trim().to_string(),
+                Some(serde_json::Value::Null) | None => String::new(),
+                Some(value) => value.to_string(),
+            })
+            .collect();
+        let fields: Vec<&str> = fields.iter().map(String::as_str).collect();
+        push_parsed_row(&mut result, &mut index_by_user, line, parse_row(&fields, current_year));
+    }
+
+    result
+}
+
+/// Whether an uploaded import file holds JSON rather than CSV, from its name or its content
+pub fn is_json_import(filename: &str, content: &str) -> bool {
+    filename.to_lowercase().ends_with(".json") || content.trim_start().starts_with('[')
+}
+
+/// Record a parsed row or its error, replacing an earlier row of the same user
+fn push_parsed_row(
+    result: &mut ImportParseResult,
+    index_by_user: &mut HashMap<u64, usize>,
+    line: usize,
+    parsed: Result<(u64, i32, i32, Option<i32>), String>,
+) {
+    match parsed {
+        Ok((user_id, month, day, year)) => {
+            let row = ImportedBirthday { line, user_id, month, day, year };
+            match index_by_user.get(&user_id) {
+                Some(&index) => result.rows[index] = row,
+                None => {
+                    index_by_user.insert(user_id, result.rows.len());
+                    result.rows.push(row);
+                }
+            }
+        }
+        Err(message) => result.errors.push(ImportError { line, message }),
+    }
+}
+
+/// Validate the fields of a single row
+fn parse_row(fields: &[&str], current_year: i32) -> Result<(u64, i32, i32, Option<i32>), String> {
+    if !(3..=4).contains(&fields.len()) {
+        return Err(format!(
+            "Expected 3 or 4 columns (user_id,month,day,year), found {}",
+            fields.len()
+        ));
+    }
+
+    let user_id = fields[0]
+        .parse::<u64>()
+        .ok()
+        .filter(|&id| id != 0)
+        .ok_or_else(|| format!("Invalid user ID `{}`", fields[0]))?;
+    let month = fields[1]
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid month `{}`", fields[1]))?;
+    let day = fields[2]
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid day `{}`", fields[2]))?;
+
+    if !is_valid_date(month, day) {
+        return Err(format!("Invalid date {}/{}", month, day));
+    }
+
+    let year = match fields.get(3).filter(|field| !field.is_empty()) {
+        Some(field) => {
+            let year = field
+                .parse::<i32>()
+                .map_err(|_| format!("Invalid year `{}`", field))?;
+            validate_birth_year(year, current_year)?;
+            if !date_exists(year, month, day) {
+                return Err(format!("{}/{} does not exist in {}", month, day, year));
+            }
+            Some(year)
+        }
+        None => None,
+    };
+
+    Ok((user_id, month, day, year))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_birthday_csv_with_header() {
+        let content = "user_id,month,day,year\n123,3,15,1990\n456,12,25,\n";
+        let result = parse_birthday_csv(content, 2024);
+
+        assert!(result.errors.is_empty());
+        assert_eq!(
+            result.rows,
+            vec![
+                ImportedBirthday { line: 2, user_id: 123, month: 3, day: 15, year: Some(1990) },
+                ImportedBirthday { line: 3, user_id: 456, month: 12, day: 25, year: None },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_birthday_csv_without_header() {
+        let result = parse_birthday_csv("123,3,15\n", 2024);
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].line, 1);
+        assert_eq!(result.rows[0].year, None);
+    }
+
+    #[test]
+    fn test_parse_birthday_csv_reports_errors_with_lines() {
+        let content = "123,13,1,1990\nabc,1,1\n456,2,30\n789,2,29,1999\n1,1\n321,1,1,1800\n654,1,1,2030\n";
+        let result = parse_birthday_csv(content, 2024);
+
+        assert!(result.rows.is_empty());
+        let lines: Vec<usize> = result.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_parse_birthday_csv_last_row_wins() {
+        let content = "123,1,1\n456,2,2\n123,3,3,2000\n";
+        let result = parse_birthday_csv(content, 2024);
+
+        assert_eq!(result.rows.len(), 2);
+        assert_eq!(
+            result.rows[0],
+            ImportedBirthday { line: 3, user_id: 123, month: 3, day: 3, year: Some(2000) }
+        );
+    }
+
+    #[test]
+    fn test_summarize_import() {
+        let rows = parse_birthday_csv("1,1,1\n2,2,2,2000\n3,3,3\n", 2024).rows;
+        let existing = HashMap::from([(2, (2, 2, Some(2000))), (3, (4, 4, None)), (9, (1, 1, None))]);
+
+        assert_eq!(
+            summarize_import(&rows, &existing),
+            ImportSummary { added: 1, overwritten: 1, unchanged: 1 }
+        );
+        assert_eq!(summarize_import(&[], &existing), ImportSummary::default());
+    }
+
+    #[test]
+    fn test_parse_birthday_json() {
+        let content = r#"[
+            {"user_id": "123", "month": 3, "day": 15, "year": 1990},
+            {"user_id": 456, "month": "12", "day": 25, "year": null},
+            {"user_id": "789", "month": 2, "day": 29, "year": 1999},
+            {"user_id": "321", "month": 1},
+            "oops",
+            {"user_id": "123", "month": 4, "day": 1}
+        ]"#;
+        let result = parse_birthday_json(content, 2024);
+
+        assert_eq!(
+            result.rows,
+            vec![
+                ImportedBirthday { line: 6, user_id: 123, month: 4, day: 1, year: None },
+                ImportedBirthday { line: 2, user_id: 456, month: 12, day: 25, year: None },
+            ]
+        );
+        let lines: Vec<usize> = result.errors.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn test_parse_birthday_json_malformed() {
+        let result = parse_birthday_json("[{\"user_id\": ", 2024);
+        assert!(result.rows.is_empty());
+        assert_eq!(result.errors.len(), 1);
+        assert!(result.errors[0].message.starts_with("Malformed JSON"));
+
+        let result = parse_birthday_json("{\"user_id\": \"123\"}", 2024);
+        assert!(result.rows.is_empty());
+        assert_eq!(result.errors[0].message, "Expected a JSON array of birthdays");
+    }
+
+    #[test]
+    fn test_is_json_import() {
+        assert!(is_json_import("birthdays.JSON", ""));
+        assert!(is_json_import("upload.txt", "  \n[{}]"));
+        assert!(!is_json_import("birthdays.csv", "user_id,month,day,year"));
+    }
+
+    #[test]
+    fn test_parse_birthday_csv_skips_blank_lines() {
+        let result = parse_birthday_csv("\n123, 1 , 2 \n\n", 2024);
+
+        assert!(result.errors.is_empty());
+        assert_eq!(result.rows.len(), 1);
+        assert_eq!(result.rows[0].day, 2);
+    }
+}
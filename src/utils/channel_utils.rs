@@ -1,10 +1,75 @@
 /// Pure functions for channel name and configuration (Discord-agnostic)
+#[cfg(feature = "lobby")]
+use crate::constants::{
+    MAX_CHANNEL_NAME_LENGTH, MAX_CHANNEL_TOPIC_LENGTH, MAX_VOICE_USER_LIMIT, MIN_VOICE_BITRATE,
+};
+#[cfg(feature = "lobby")]
+use crate::utils::string_utils::{contains_blacklisted_word, normalize_whitespace, take_chars};
+#[cfg(feature = "birthday")]
+use crate::utils::string_utils::split_and_trim;
+
+/// Parse a stored comma-separated list of channel IDs, skipping invalid entries
+#[cfg(feature = "birthday")]
+pub fn parse_channel_id_list(text: &str) -> Vec<u64> {
+    split_and_trim(text, ',')
+        .iter()
+        .filter_map(|id| id.parse::<u64>().ok())
+        .filter(|&id| id != 0)
+        .collect()
+}
+
+/// Store a list of channel IDs as a comma-separated string
+#[cfg(feature = "birthday")]
+pub fn join_channel_id_list(ids: &[u64]) -> String {
+    ids.iter().map(u64::to_string).collect::<Vec<_>>().join(",")
+}
 
 /// Format a temporary channel name for a user
+#[cfg(feature = "lobby")]
 pub fn format_temp_channel_name(user_name: &str) -> String {
     format!("{}'s Channel", user_name)
 }
 
+/// Format a temporary channel name, shortening the user name so the result fits in `max_length` characters
+#[cfg(feature = "lobby")]
+pub fn format_temp_channel_name_clamped(user_name: &str, max_length: usize) -> String {
+    let suffix_length = format_temp_channel_name("").chars().count();
+    let available = max_length.saturating_sub(suffix_length);
+    let name = format_temp_channel_name(&take_chars(user_name.trim(), available));
+    // Only truncates the suffix itself when max_length is shorter than it
+    take_chars(&name, max_length)
+}
+
+/// Format a temporary channel name from a lobby template (`{user}` and `{count}` placeholders),
+/// falling back to the default name when there is no template or it yields an empty name
+#[cfg(feature = "lobby")]
+pub fn format_temp_channel_name_from_template(
+    template: Option<&str>,
+    user_name: &str,
+    count: usize,
+    max_length: usize,
+) -> String {
+    let name = template
+        .map(|template| {
+            template
+                .replace("{user}", user_name.trim())
+                .replace("{count}", &count.to_string())
+        })
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty());
+
+    match name {
+        Some(name) => take_chars(&name, max_length),
+        None => format_temp_channel_name_clamped(user_name, max_length),
+    }
+}
+
+/// Check that a temp channel name template is usable (not blank, fits in a channel name)
+#[cfg(feature = "lobby")]
+pub fn is_valid_name_template(template: &str, max_length: usize) -> bool {
+    !template.trim().is_empty() && template.chars().count() <= max_length
+}
+
 /// Check if a channel name looks like a temporary channel
 pub fn is_temp_channel_name(channel_name: &str) -> bool {
     channel_name.ends_with("'s Channel")
@@ -19,12 +84,106 @@ pub fn extract_user_from_channel_name(channel_name: &str) -> Option<&str> {
     channel_name.strip_suffix("'s Channel")
 }
 
+/// Normalize a voice channel user limit: 0 means unlimited (None),
+/// values above Discord's maximum are clamped to it
+#[cfg(feature = "lobby")]
+pub fn normalize_user_limit(limit: u32) -> Option<u32> {
+    match limit {
+        0 => None,
+        limit => Some(limit.min(MAX_VOICE_USER_LIMIT)),
+    }
+}
+
+/// Parse a user limit typed by a channel owner, clamped to Discord's maximum (0 means unlimited)
+#[cfg(feature = "lobby")]
+pub fn parse_user_limit_input(input: &str) -> Result<u32, &'static str> {
+    input
+        .trim()
+        .parse::<u32>()
+        .map(|limit| limit.min(MAX_VOICE_USER_LIMIT))
+        .map_err(|_| "User limit must be a number between 0 and 99")
+}
+
+/// Highest voice channel bitrate (bps) a guild can use at the given boost tier
+#[cfg(feature = "lobby")]
+pub fn max_bitrate_for_boost_tier(boost_tier: u8) -> u32 {
+    match boost_tier {
+        0 => 96_000,
+        1 => 128_000,
+        2 => 256_000,
+        _ => 384_000,
+    }
+}
+
+/// Clamp a voice channel bitrate (bps) between Discord's minimum and the guild's maximum
+#[cfg(feature = "lobby")]
+pub fn clamp_bitrate(bitrate: u32, max_bitrate: u32) -> u32 {
+    bitrate.clamp(MIN_VOICE_BITRATE, max_bitrate.max(MIN_VOICE_BITRATE))
+}
+
+/// Describe the bitrate (bps) saved for a lobby, noting when it differs from the requested kbps
+#[cfg(feature = "lobby")]
+pub fn format_lobby_bitrate(requested_kbps: u32, bitrate: u32) -> String {
+    let kbps = bitrate / 1000;
+    if kbps == requested_kbps {
+        format!("{} kbps", kbps)
+    } else {
+        format!("{} kbps (adjusted from {} kbps to fit the server's boost level)", kbps, requested_kbps)
+    }
+}
+
 /// Build archive category name
+#[cfg(feature = "lobby")]
 pub fn build_archive_category_name(base_name: &str) -> String {
     format!("📦 {} Archive", base_name)
 }
 
+/// Build the DM sent to an owner when their persistent channel is archived
+#[cfg(feature = "lobby")]
+pub fn build_archive_dm(lobby_channel_id: u64) -> String {
+    format!(
+        "📦 **Your channel was archived.**\n\n\
+        Everyone left your persistent channel, so it has been archived. \
+        Rejoin <#{}> to restore it.",
+        lobby_channel_id
+    )
+}
+
+/// Prefix of the custom id of the archived channel restore picker
+#[cfg(feature = "lobby")]
+pub const RESTORE_PICKER_PREFIX: &str = "restore_archived:";
+
+/// Build the custom id of the restore picker, carrying the guild since it is sent in DMs
+#[cfg(feature = "lobby")]
+pub fn build_restore_picker_id(guild_id: u64) -> String {
+    format!("{}{}", RESTORE_PICKER_PREFIX, guild_id)
+}
+
+/// Parse the guild id out of a restore picker custom id
+#[cfg(feature = "lobby")]
+pub fn parse_restore_picker_id(custom_id: &str) -> Option<u64> {
+    custom_id
+        .strip_prefix(RESTORE_PICKER_PREFIX)
+        .and_then(|id| id.parse().ok())
+}
+
+/// Build the message offering to restore a user's other archived channels
+#[cfg(feature = "lobby")]
+pub fn build_restore_picker_message(archived_count: usize) -> String {
+    let channels = if archived_count == 1 {
+        "1 other archived channel".to_string()
+    } else {
+        format!("{} other archived channels", archived_count)
+    };
+    format!(
+        "📦 **You have {}.**\n\n\
+        Pick the ones you want to restore below.",
+        channels
+    )
+}
+
 /// Format a success message for birthday setup
+#[cfg(feature = "birthday")]
 pub fn format_birthday_setup_message(
     channel_name: &str,
     time: &str,
@@ -47,6 +206,7 @@ pub fn format_birthday_setup_message(
 }
 
 /// Format a birthday display string
+#[cfg(feature = "birthday")]
 pub fn format_birthday_display(day: i32, month_name: &str, year: Option<i32>) -> String {
     if let Some(y) = year {
         format!("{} {} {}", day, month_name, y)
@@ -64,18 +224,45 @@ pub fn format_date_compact(month: i32, day: i32, year: Option<i32>) -> String {
     }
 }
 
-/// Validate channel name length and characters
-pub fn is_valid_channel_name(name: &str) -> Result<(), &'static str> {
+/// Sanitize a channel name into what Discord will store (control characters stripped and
+/// whitespace collapsed), then validate it against Discord's rules and the guild's word blacklist
+#[cfg(feature = "lobby")]
+pub fn sanitize_channel_name(name: &str, blacklist: &[String]) -> Result<String, &'static str> {
+    let stripped: String = name
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .collect();
+    let name = normalize_whitespace(&stripped);
+
     if name.is_empty() {
         return Err("Channel name cannot be empty");
     }
     
-    if name.len() > 100 {
+    if name.chars().count() > MAX_CHANNEL_NAME_LENGTH as usize {
         return Err("Channel name cannot exceed 100 characters");
     }
-    
-    // Discord channel names have specific character restrictions
-    // but we'll keep it simple for now
+
+    if contains_blacklisted_word(&name, blacklist) {
+        return Err("Channel name contains a word forbidden on this server");
+    }
+
+    Ok(name)
+}
+
+/// Check whether a user owning `owned` persistent channels has reached the guild's cap
+/// (a cap of 0 means no limit)
+#[cfg(feature = "lobby")]
+pub fn has_reached_persistent_cap(owned: u64, max_channels: u32) -> bool {
+    max_channels > 0 && owned >= max_channels as u64
+}
+
+/// Validate a channel topic (empty clears the topic)
+#[cfg(feature = "lobby")]
+pub fn is_valid_channel_topic(topic: &str) -> Result<(), &'static str> {
+    if topic.chars().count() > MAX_CHANNEL_TOPIC_LENGTH as usize {
+        return Err("Channel topic cannot exceed 1024 characters");
+    }
+
     Ok(())
 }
 
@@ -83,6 +270,7 @@ pub fn is_valid_channel_name(name: &str) -> Result<(), &'static str> {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "lobby")]
     #[test]
     fn test_format_temp_channel_name() {
         assert_eq!(format_temp_channel_name("Alice"), "Alice's Channel");
@@ -90,6 +278,34 @@ mod tests {
         assert_eq!(format_temp_channel_name("User Name"), "User Name's Channel");
     }
 
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_temp_channel_name_clamped() {
+        assert_eq!(format_temp_channel_name_clamped("Alice", 100), "Alice's Channel");
+
+        let long_name = "a".repeat(150);
+        let name = format_temp_channel_name_clamped(&long_name, 100);
+        assert_eq!(name.chars().count(), 100);
+        assert!(is_temp_channel_name(&name));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_temp_channel_name_clamped_multibyte() {
+        let long_name = "🎮é漢".repeat(40);
+        let name = format_temp_channel_name_clamped(&long_name, 100);
+        assert_eq!(name.chars().count(), 100);
+        assert!(name.ends_with("'s Channel"));
+        assert!(name.starts_with("🎮é漢"));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_temp_channel_name_clamped_tiny_limit() {
+        assert_eq!(format_temp_channel_name_clamped("Alice", 5), "'s Ch");
+        assert_eq!(format_temp_channel_name_clamped("Alice", 0), "");
+    }
+
     #[test]
     fn test_is_temp_channel_name() {
         assert!(is_temp_channel_name("Alice's Channel"));
@@ -115,6 +331,37 @@ mod tests {
         assert_eq!(extract_user_from_channel_name("Alice"), None);
     }
 
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_max_bitrate_for_boost_tier() {
+        assert_eq!(max_bitrate_for_boost_tier(0), 96_000);
+        assert_eq!(max_bitrate_for_boost_tier(1), 128_000);
+        assert_eq!(max_bitrate_for_boost_tier(2), 256_000);
+        assert_eq!(max_bitrate_for_boost_tier(3), 384_000);
+        // Unknown future tiers get at least the highest known bitrate
+        assert_eq!(max_bitrate_for_boost_tier(4), 384_000);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_clamp_bitrate() {
+        assert_eq!(clamp_bitrate(64_000, 96_000), 64_000);
+        assert_eq!(clamp_bitrate(256_000, 96_000), 96_000);
+        assert_eq!(clamp_bitrate(1_000, 96_000), 8_000);
+        assert_eq!(clamp_bitrate(96_000, 0), 8_000);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_lobby_bitrate() {
+        assert_eq!(format_lobby_bitrate(64, 64_000), "64 kbps");
+        assert_eq!(
+            format_lobby_bitrate(256, 96_000),
+            "96 kbps (adjusted from 256 kbps to fit the server's boost level)"
+        );
+    }
+
+    #[cfg(feature = "lobby")]
     #[test]
     fn test_build_archive_category_name() {
         assert_eq!(
@@ -127,6 +374,109 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_temp_channel_name_from_template() {
+        assert_eq!(
+            format_temp_channel_name_from_template(Some("🎮 {user} #{count}"), "Alice", 3, 100),
+            "🎮 Alice #3"
+        );
+        assert_eq!(
+            format_temp_channel_name_from_template(Some("Room {count}"), "Alice", 1, 100),
+            "Room 1"
+        );
+        assert_eq!(
+            format_temp_channel_name_from_template(Some("{user}'s very long room"), "Alice", 1, 10),
+            "Alice's ve"
+        );
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_format_temp_channel_name_from_template_falls_back() {
+        assert_eq!(
+            format_temp_channel_name_from_template(None, "Alice", 1, 100),
+            "Alice's Channel"
+        );
+        assert_eq!(
+            format_temp_channel_name_from_template(Some("{user}"), "  ", 1, 100),
+            "'s Channel"
+        );
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_is_valid_name_template() {
+        assert!(is_valid_name_template("{user}'s room", 100));
+        assert!(!is_valid_name_template("", 100));
+        assert!(!is_valid_name_template("   ", 100));
+        assert!(!is_valid_name_template("too long", 5));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_normalize_user_limit() {
+        assert_eq!(normalize_user_limit(0), None);
+        assert_eq!(normalize_user_limit(1), Some(1));
+        assert_eq!(normalize_user_limit(99), Some(99));
+        assert_eq!(normalize_user_limit(100), Some(99));
+        assert_eq!(normalize_user_limit(u32::MAX), Some(99));
+    }
+
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_channel_id_list_round_trip() {
+        assert_eq!(parse_channel_id_list("1, 22 ,333"), vec![1, 22, 333]);
+        assert_eq!(parse_channel_id_list("1,,abc,0,2"), vec![1, 2]);
+        assert!(parse_channel_id_list("").is_empty());
+        assert_eq!(join_channel_id_list(&[1, 22]), "1,22");
+        assert_eq!(parse_channel_id_list(&join_channel_id_list(&[5, 6])), vec![5, 6]);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_parse_user_limit_input() {
+        assert_eq!(parse_user_limit_input("0"), Ok(0));
+        assert_eq!(parse_user_limit_input(" 5 "), Ok(5));
+        assert_eq!(parse_user_limit_input("99"), Ok(99));
+        assert_eq!(parse_user_limit_input("150"), Ok(99));
+        assert!(parse_user_limit_input("").is_err());
+        assert!(parse_user_limit_input("-1").is_err());
+        assert!(parse_user_limit_input("ten").is_err());
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_build_archive_dm() {
+        let msg = build_archive_dm(123456789);
+        assert!(msg.contains("archived"));
+        assert!(msg.contains("Rejoin <#123456789>"));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_restore_picker_id_roundtrip() {
+        let id = build_restore_picker_id(123456789);
+        assert_eq!(id, "restore_archived:123456789");
+        assert_eq!(parse_restore_picker_id(&id), Some(123456789));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_parse_restore_picker_id_invalid() {
+        assert_eq!(parse_restore_picker_id("configure_channel"), None);
+        assert_eq!(parse_restore_picker_id("restore_archived:"), None);
+        assert_eq!(parse_restore_picker_id("restore_archived:abc"), None);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_build_restore_picker_message() {
+        assert!(build_restore_picker_message(1).contains("1 other archived channel."));
+        assert!(build_restore_picker_message(3).contains("3 other archived channels"));
+    }
+
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_format_birthday_setup_message() {
         let msg = format_birthday_setup_message(
@@ -142,6 +492,7 @@ mod tests {
         assert!(msg.contains("Birthday role configured"));
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_format_birthday_setup_message_no_role() {
         let msg = format_birthday_setup_message(
@@ -155,6 +506,7 @@ mod tests {
         assert!(!msg.contains("Birthday role configured"));
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_format_birthday_display_with_year() {
         assert_eq!(
@@ -163,6 +515,7 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_format_birthday_display_without_year() {
         assert_eq!(
@@ -183,15 +536,75 @@ mod tests {
         assert_eq!(format_date_compact(12, 1, None), "12/01");
     }
 
+    #[cfg(feature = "lobby")]
     #[test]
-    fn test_is_valid_channel_name() {
-        assert!(is_valid_channel_name("general").is_ok());
-        assert!(is_valid_channel_name("my-channel").is_ok());
-        assert!(is_valid_channel_name("a").is_ok());
+    fn test_sanitize_channel_name() {
+        assert!(sanitize_channel_name("general", &[]).is_ok());
+        assert!(sanitize_channel_name("my-channel", &[]).is_ok());
+        assert!(sanitize_channel_name("a", &[]).is_ok());
         
-        assert!(is_valid_channel_name("").is_err());
+        assert!(sanitize_channel_name("", &[]).is_err());
         
         let long_name = "a".repeat(101);
-        assert!(is_valid_channel_name(&long_name).is_err());
+        assert!(sanitize_channel_name(&long_name, &[]).is_err());
+
+        let blacklist = vec!["spam".to_string()];
+        assert!(sanitize_channel_name("Spam Central", &blacklist).is_err());
+        assert!(sanitize_channel_name("general", &blacklist).is_ok());
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_sanitize_channel_name_cleans_up() {
+        assert_eq!(
+            sanitize_channel_name("  my   cool\tchannel \n", &[]),
+            Ok("my cool channel".to_string())
+        );
+        assert_eq!(
+            sanitize_channel_name("bell\u{7}less\u{0}", &[]),
+            Ok("bellless".to_string())
+        );
+        assert!(sanitize_channel_name(" \t\n ", &[]).is_err());
+        assert!(sanitize_channel_name("\u{1b}\u{7f}", &[]).is_err());
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_sanitize_channel_name_unicode() {
+        assert_eq!(
+            sanitize_channel_name("🎮 Soirée jeux", &[]),
+            Ok("🎮 Soirée jeux".to_string())
+        );
+
+        // The limit counts characters, not bytes
+        let accented = "é".repeat(100);
+        assert_eq!(sanitize_channel_name(&accented, &[]), Ok(accented.clone()));
+        assert!(sanitize_channel_name(&"é".repeat(101), &[]).is_err());
+
+        // Surrounding whitespace doesn't count towards the limit
+        let padded = format!("   {}   ", "a".repeat(100));
+        assert_eq!(sanitize_channel_name(&padded, &[]), Ok("a".repeat(100)));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_has_reached_persistent_cap() {
+        assert!(!has_reached_persistent_cap(0, 2));
+        assert!(!has_reached_persistent_cap(1, 2));
+        assert!(has_reached_persistent_cap(2, 2));
+        assert!(has_reached_persistent_cap(3, 2));
+
+        // 0 disables the cap
+        assert!(!has_reached_persistent_cap(100, 0));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_is_valid_channel_topic() {
+        assert!(is_valid_channel_topic("").is_ok());
+        assert!(is_valid_channel_topic("Chill gaming session").is_ok());
+        assert!(is_valid_channel_topic(&"é".repeat(1024)).is_ok());
+
+        assert!(is_valid_channel_topic(&"a".repeat(1025)).is_err());
     }
 }
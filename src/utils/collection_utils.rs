@@ -94,6 +94,22 @@ pub fn dedup<T: Eq + std::hash::Hash + Clone>(items: Vec<T>) -> Vec<T> {
     result
 }
 
+/// Sum counts sharing the same key, sorted by count (highest first) then key
+pub fn merge_counts<K: Eq + std::hash::Hash + Ord>(
+    items: impl IntoIterator<Item = (K, u64)>,
+) -> Vec<(K, u64)> {
+    let mut totals: HashMap<K, u64> = HashMap::new();
+    for (key, count) in items {
+        *totals.entry(key).or_default() += count;
+    }
+
+    let mut merged: Vec<(K, u64)> = totals.into_iter().collect();
+    merged.sort_by(|(a_key, a_count), (b_key, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_key.cmp(b_key))
+    });
+    merged
+}
+
 /// Chunk a vector into smaller vectors of size n
 pub fn chunk<T: Clone>(items: &[T], size: usize) -> Vec<Vec<T>> {
     if size == 0 {
@@ -188,6 +204,16 @@ mod tests {
         assert_eq!(unique, vec![1, 2, 3, 4]);
     }
 
+    #[test]
+    fn test_merge_counts() {
+        let items = vec![("b", 2), ("a", 1), ("b", 3), ("c", 5), ("a", 4)];
+        let merged = merge_counts(items);
+
+        assert_eq!(merged, vec![("a", 5), ("b", 5), ("c", 5)]);
+        assert_eq!(merge_counts(vec![("x", 1), ("y", 7)]), vec![("y", 7), ("x", 1)]);
+        assert!(merge_counts(Vec::<(&str, u64)>::new()).is_empty());
+    }
+
     #[test]
     fn test_chunk() {
         let items = vec![1, 2, 3, 4, 5, 6, 7];
@@ -1,6 +1,9 @@
 /// Pure date/time utility functions (Discord-agnostic)
 use chrono::{Datelike, NaiveDate, Utc};
 
+use crate::utils::messages::build_invalid_input_error;
+use crate::utils::string_utils::is_empty_or_whitespace;
+
 /// Calculate age from birth year
 pub fn calculate_age(birth_year: i32, current_year: i32) -> i32 {
     current_year - birth_year
@@ -12,6 +15,23 @@ pub fn calculate_age_today(birth_year: i32) -> i32 {
     calculate_age(birth_year, current_year)
 }
 
+/// Earliest accepted birth year (matches the `user_birthdays.birth_year` CHECK constraint)
+pub const MIN_BIRTH_YEAR: i32 = 1901;
+
+/// Validate a birth year against the accepted range (`MIN_BIRTH_YEAR` up to the current year).
+/// Every birthday write path must go through this, as the database can only enforce the lower bound.
+pub fn validate_birth_year(year: i32, current_year: i32) -> Result<(), String> {
+    if year < MIN_BIRTH_YEAR {
+        return Err(format!("Birth year must be {} or later", MIN_BIRTH_YEAR));
+    }
+
+    if year > current_year {
+        return Err("Birth year cannot be in the future".to_string());
+    }
+
+    Ok(())
+}
+
 /// Check if a given year is a leap year
 pub fn is_leap_year(year: i32) -> bool {
     (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0)
@@ -49,6 +69,131 @@ pub fn date_exists(year: i32, month: i32, day: i32) -> bool {
     NaiveDate::from_ymd_opt(year, month as u32, day as u32).is_some()
 }
 
+/// Validate a birthday before saving it: the day must exist in the month (and in the
+/// birth year when one is given), and the birth year must be accepted
+pub fn validate_birthday(
+    month: i32,
+    day: i32,
+    year: Option<i32>,
+    current_year: i32,
+) -> Result<(), String> {
+    if !is_valid_date(month, day) {
+        return Err("Invalid date! Please check your month and day combination.".to_string());
+    }
+
+    if let Some(year) = year {
+        validate_birth_year(year, current_year)?;
+        if !date_exists(year, month, day) {
+            return Err(format!(
+                "{} {} does not exist in {}!",
+                day,
+                get_month_name(month),
+                year
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse and validate a month number
+pub fn parse_month(month_str: &str) -> Result<i32, String> {
+    month_str
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|&m| (1..=12).contains(&m))
+        .ok_or_else(|| build_invalid_input_error("month", "a number between 1 and 12"))
+}
+
+/// Parse and validate a day number
+pub fn parse_day(day_str: &str) -> Result<i32, String> {
+    day_str
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|&d| (1..=31).contains(&d))
+        .ok_or_else(|| build_invalid_input_error("day", "a number between 1 and 31"))
+}
+
+/// Parse and validate an optional birth year (empty means no year)
+pub fn parse_year(year_str: &str, current_year: i32) -> Result<Option<i32>, String> {
+    if is_empty_or_whitespace(year_str) {
+        return Ok(None);
+    }
+    
+    year_str
+        .trim()
+        .parse::<i32>()
+        .ok()
+        .filter(|&y| validate_birth_year(y, current_year).is_ok())
+        .map(Some)
+        .ok_or_else(|| {
+            build_invalid_input_error(
+                "year",
+                &format!("a valid year ({}-{}) or leave it empty", MIN_BIRTH_YEAR, current_year),
+            )
+        })
+}
+
+/// Get the (month, day) falling `days` days after a date, wrapping over month and year ends
+pub fn advance_month_day(date: NaiveDate, days: u32) -> (i32, i32) {
+    let target = date + chrono::Days::new(days as u64);
+    (target.month() as i32, target.day() as i32)
+}
+
+/// Get the dates of the Monday-to-Sunday week containing a date
+pub fn week_dates(date: NaiveDate) -> Vec<NaiveDate> {
+    let monday = date - chrono::Days::new(date.weekday().num_days_from_monday() as u64);
+    monday.iter_days().take(7).collect()
+}
+
+/// Group birthdays (month, day, value) by the given dates, keeping only dates with birthdays
+pub fn group_birthdays_by_date<T: Clone>(
+    dates: &[NaiveDate],
+    birthdays: &[(i32, i32, T)],
+) -> Vec<(NaiveDate, Vec<T>)> {
+    dates
+        .iter()
+        .map(|date| {
+            let values: Vec<T> = birthdays
+                .iter()
+                .filter(|(month, day, _)| {
+                    matches_birthday(*month, *day, date.month() as i32, date.day() as i32)
+                })
+                .map(|(_, _, value)| value.clone())
+                .collect();
+            (*date, values)
+        })
+        .filter(|(_, values)| !values.is_empty())
+        .collect()
+}
+
+/// Get the next date (today included) a birthday is celebrated on.
+/// February 29 birthdays are only celebrated on leap years, like the announcements do.
+pub fn next_birthday_occurrence(month: i32, day: i32, today: NaiveDate) -> Option<NaiveDate> {
+    // A leap year always comes within 8 years
+    (today.year()..=today.year() + 8)
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month as u32, day as u32))
+        .find(|date| *date >= today)
+}
+
+/// Sort birthdays (month, day, value) by their next occurrence, with the days left until it
+pub fn sort_upcoming_birthdays<T: Clone>(
+    today: NaiveDate,
+    birthdays: &[(i32, i32, T)],
+) -> Vec<(NaiveDate, i64, T)> {
+    let mut upcoming: Vec<(NaiveDate, i64, T)> = birthdays
+        .iter()
+        .filter_map(|(month, day, value)| {
+            next_birthday_occurrence(*month, *day, today)
+                .map(|date| (date, (date - today).num_days(), value.clone()))
+        })
+        .collect();
+    upcoming.sort_by_key(|(date, _, _)| *date);
+    upcoming
+}
+
 /// Format a date as "Day MonthName" (e.g., "15 March")
 pub fn format_date_display(month: i32, day: i32) -> String {
     let month_name = get_month_name(month);
@@ -78,6 +223,31 @@ pub fn get_month_name(month: i32) -> &'static str {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_advance_month_day() {
+        let date = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+        assert_eq!(advance_month_day(date, 0), (3, 10));
+        assert_eq!(advance_month_day(date, 3), (3, 13));
+
+        // Month and year wrap
+        let date = NaiveDate::from_ymd_opt(2024, 1, 30).unwrap();
+        assert_eq!(advance_month_day(date, 3), (2, 2));
+        let date = NaiveDate::from_ymd_opt(2024, 12, 30).unwrap();
+        assert_eq!(advance_month_day(date, 3), (1, 2));
+    }
+
+    #[test]
+    fn test_advance_month_day_leap_years() {
+        // February 29 is only reached on leap years
+        let date = NaiveDate::from_ymd_opt(2024, 2, 27).unwrap();
+        assert_eq!(advance_month_day(date, 2), (2, 29));
+        assert_eq!(advance_month_day(date, 3), (3, 1));
+        let date = NaiveDate::from_ymd_opt(2023, 2, 27).unwrap();
+        assert_eq!(advance_month_day(date, 2), (3, 1));
+        let date = NaiveDate::from_ymd_opt(2100, 2, 28).unwrap();
+        assert_eq!(advance_month_day(date, 1), (3, 1));
+    }
+
     #[test]
     fn test_calculate_age() {
         assert_eq!(calculate_age(1990, 2025), 35);
@@ -86,6 +256,17 @@ mod tests {
         assert_eq!(calculate_age(2010, 2025), 15);
     }
 
+    #[test]
+    fn test_validate_birth_year() {
+        assert!(validate_birth_year(2000, 2025).is_ok());
+        assert!(validate_birth_year(1901, 2025).is_ok());
+        assert!(validate_birth_year(2025, 2025).is_ok());
+
+        assert!(validate_birth_year(1900, 2025).is_err());
+        assert!(validate_birth_year(2026, 2025).is_err());
+        assert!(validate_birth_year(2100, 2025).is_err());
+    }
+
     #[test]
     fn test_is_leap_year() {
         assert!(is_leap_year(2000)); // Divisible by 400
@@ -144,6 +325,59 @@ mod tests {
         assert_eq!(format_date_display(1, 1), "1 January");
     }
 
+    #[test]
+    fn test_parse_month_valid() {
+        assert_eq!(parse_month("1"), Ok(1));
+        assert_eq!(parse_month("12"), Ok(12));
+        assert_eq!(parse_month("6"), Ok(6));
+        assert_eq!(parse_month(" 3 "), Ok(3)); // Test trimming
+    }
+
+    #[test]
+    fn test_parse_month_invalid() {
+        assert!(parse_month("0").is_err());
+        assert!(parse_month("13").is_err());
+        assert!(parse_month("-1").is_err());
+        assert!(parse_month("abc").is_err());
+        assert!(parse_month("").is_err());
+    }
+
+    #[test]
+    fn test_parse_day_valid() {
+        assert_eq!(parse_day("1"), Ok(1));
+        assert_eq!(parse_day("31"), Ok(31));
+        assert_eq!(parse_day("15"), Ok(15));
+        assert_eq!(parse_day(" 20 "), Ok(20)); // Test trimming
+    }
+
+    #[test]
+    fn test_parse_day_invalid() {
+        assert!(parse_day("0").is_err());
+        assert!(parse_day("32").is_err());
+        assert!(parse_day("-5").is_err());
+        assert!(parse_day("abc").is_err());
+        assert!(parse_day("").is_err());
+    }
+
+    #[test]
+    fn test_parse_year_valid() {
+        assert_eq!(parse_year("1995", 2025), Ok(Some(1995)));
+        assert_eq!(parse_year("2000", 2025), Ok(Some(2000)));
+        assert_eq!(parse_year("1901", 2025), Ok(Some(1901)));
+        assert_eq!(parse_year("2025", 2025), Ok(Some(2025)));
+        assert_eq!(parse_year("", 2025), Ok(None)); // Empty is valid
+        assert_eq!(parse_year("  ", 2025), Ok(None)); // Whitespace only
+    }
+
+    #[test]
+    fn test_parse_year_invalid() {
+        assert!(parse_year("1900", 2025).is_err()); // Too old
+        assert!(parse_year("2026", 2025).is_err()); // In the future
+        assert!(parse_year("2100", 2025).is_err());
+        assert!(parse_year("abc", 2025).is_err());
+        assert!(parse_year("99", 2025).is_err()); // Not 4 digits
+    }
+
     #[test]
     fn test_get_month_name() {
         assert_eq!(get_month_name(1), "January");
@@ -153,6 +387,93 @@ mod tests {
         assert_eq!(get_month_name(13), "Unknown");
     }
 
+    #[test]
+    fn test_week_dates() {
+        // Wednesday 15 March 2023
+        let week = week_dates(NaiveDate::from_ymd_opt(2023, 3, 15).unwrap());
+        assert_eq!(week.len(), 7);
+        assert_eq!(week[0], NaiveDate::from_ymd_opt(2023, 3, 13).unwrap());
+        assert_eq!(week[6], NaiveDate::from_ymd_opt(2023, 3, 19).unwrap());
+    }
+
+    #[test]
+    fn test_week_dates_across_months() {
+        // Thursday 1 February 2024, the week starts in January
+        let week = week_dates(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        assert_eq!(week[0], NaiveDate::from_ymd_opt(2024, 1, 29).unwrap());
+        assert_eq!(week[6], NaiveDate::from_ymd_opt(2024, 2, 4).unwrap());
+
+        // A Sunday belongs to the week started on the previous Monday
+        let week = week_dates(NaiveDate::from_ymd_opt(2024, 12, 29).unwrap());
+        assert_eq!(week[0], NaiveDate::from_ymd_opt(2024, 12, 23).unwrap());
+    }
+
+    #[test]
+    fn test_group_birthdays_by_date() {
+        let week = week_dates(NaiveDate::from_ymd_opt(2024, 2, 1).unwrap());
+        let birthdays = vec![(2, 3, "Bob"), (1, 30, "Alice"), (2, 3, "Carol"), (3, 10, "Dave")];
+
+        let grouped = group_birthdays_by_date(&week, &birthdays);
+        assert_eq!(
+            grouped,
+            vec![
+                (NaiveDate::from_ymd_opt(2024, 1, 30).unwrap(), vec!["Alice"]),
+                (NaiveDate::from_ymd_opt(2024, 2, 3).unwrap(), vec!["Bob", "Carol"]),
+            ]
+        );
+
+        assert!(group_birthdays_by_date(&week, &[] as &[(i32, i32, &str)]).is_empty());
+    }
+
+    #[test]
+    fn test_validate_birthday() {
+        assert!(validate_birthday(2, 29, None, 2025).is_ok());
+        assert!(validate_birthday(2, 29, Some(2024), 2025).is_ok());
+        assert!(validate_birthday(12, 31, Some(1990), 2025).is_ok());
+
+        assert!(validate_birthday(2, 30, None, 2025).is_err());
+        assert!(validate_birthday(13, 1, None, 2025).is_err());
+        assert_eq!(
+            validate_birthday(2, 29, Some(2023), 2025),
+            Err("29 February does not exist in 2023!".to_string())
+        );
+        assert!(validate_birthday(1, 1, Some(1900), 2025).is_err());
+        assert!(validate_birthday(1, 1, Some(2026), 2025).is_err());
+    }
+
+    #[test]
+    fn test_next_birthday_occurrence() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let today = date(2023, 12, 20);
+
+        assert_eq!(next_birthday_occurrence(12, 20, today), Some(today));
+        assert_eq!(next_birthday_occurrence(12, 25, today), Some(date(2023, 12, 25)));
+        // Already passed this year, wraps to January of next year
+        assert_eq!(next_birthday_occurrence(1, 5, today), Some(date(2024, 1, 5)));
+        assert_eq!(next_birthday_occurrence(12, 19, today), Some(date(2024, 12, 19)));
+        // February 29 waits for the next leap year
+        assert_eq!(next_birthday_occurrence(2, 29, today), Some(date(2024, 2, 29)));
+        assert_eq!(next_birthday_occurrence(2, 29, date(2024, 3, 1)), Some(date(2028, 2, 29)));
+        assert_eq!(next_birthday_occurrence(2, 30, today), None);
+    }
+
+    #[test]
+    fn test_sort_upcoming_birthdays() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let birthdays = vec![(1, 2, "Bob"), (12, 31, "Alice"), (12, 30, "Carol"), (2, 29, "Dave")];
+
+        let upcoming = sort_upcoming_birthdays(date(2023, 12, 30), &birthdays);
+        assert_eq!(
+            upcoming,
+            vec![
+                (date(2023, 12, 30), 0, "Carol"),
+                (date(2023, 12, 31), 1, "Alice"),
+                (date(2024, 1, 2), 3, "Bob"),
+                (date(2024, 2, 29), 61, "Dave"),
+            ]
+        );
+    }
+
     #[test]
     fn test_get_current_month_day() {
         let (month, day) = get_current_month_day();
@@ -0,0 +1,100 @@
+/// Retry of database operations that fail with transient errors
+use sqlx::Error as SqlxError;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::constants::{DB_RETRY_ATTEMPTS, DB_RETRY_BASE_DELAY_MS, DB_RETRY_MAX_DELAY_MS};
+
+/// Delay before the given retry (0 for the first one): doubles on every retry, capped at `max_ms`
+pub fn backoff_delay(retry: u32, base_ms: u64, max_ms: u64) -> Duration {
+    let factor = 1u64.checked_shl(retry).unwrap_or(u64::MAX);
+    Duration::from_millis(base_ms.saturating_mul(factor).min(max_ms))
+}
+
+/// Check if a Postgres SQLSTATE code reports a condition that may go away by itself:
+/// connection exceptions (08), serialization failures and deadlocks (40001, 40P01),
+/// insufficient resources (53) and server shutdowns (57P)
+pub fn is_transient_sqlstate(code: &str) -> bool {
+    code.starts_with("08")
+        || code == "40001"
+        || code == "40P01"
+        || code.starts_with("53")
+        || code.starts_with("57P")
+}
+
+/// Check if a database error is worth retrying (lost connection, exhausted pool, ...)
+/// rather than permanent (syntax error, constraint violation, decoding failure, ...)
+pub fn is_transient_db_error(error: &SqlxError) -> bool {
+    match error {
+        SqlxError::Io(_) | SqlxError::Tls(_) | SqlxError::PoolTimedOut => true,
+        SqlxError::Database(db_error) => db_error
+            .code()
+            .is_some_and(|code| is_transient_sqlstate(&code)),
+        _ => false,
+    }
+}
+
+/// Run a database operation, retrying it with exponential backoff while it fails with
+/// transient errors. Permanent errors and the last transient one are returned as is.
+pub async fn with_db_retry<T, F, Fut>(operation: &str, mut run: F) -> Result<T, SqlxError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SqlxError>>,
+{
+    let mut retry = 0;
+    loop {
+        match run().await {
+            Err(e) if retry < DB_RETRY_ATTEMPTS && is_transient_db_error(&e) => {
+                let delay = backoff_delay(retry, DB_RETRY_BASE_DELAY_MS, DB_RETRY_MAX_DELAY_MS);
+                retry += 1;
+                warn!(
+                    "Transient database error while {} (retry {}/{} in {:?}): {}",
+                    operation, retry, DB_RETRY_ATTEMPTS, delay, e
+                );
+                tokio::time::sleep(delay).await;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    #[test]
+    fn test_backoff_delay() {
+        assert_eq!(backoff_delay(0, 500, 10_000), Duration::from_millis(500));
+        assert_eq!(backoff_delay(1, 500, 10_000), Duration::from_millis(1_000));
+        assert_eq!(backoff_delay(2, 500, 10_000), Duration::from_millis(2_000));
+        assert_eq!(backoff_delay(3, 500, 10_000), Duration::from_millis(4_000));
+
+        // Capped, even for absurd retry counts
+        assert_eq!(backoff_delay(5, 500, 10_000), Duration::from_millis(10_000));
+        assert_eq!(backoff_delay(200, 500, 10_000), Duration::from_millis(10_000));
+    }
+
+    #[test]
+    fn test_is_transient_sqlstate() {
+        assert!(is_transient_sqlstate("08006")); // connection failure
+        assert!(is_transient_sqlstate("40P01")); // deadlock
+        assert!(is_transient_sqlstate("53300")); // too many connections
+        assert!(is_transient_sqlstate("57P01")); // admin shutdown
+
+        assert!(!is_transient_sqlstate("42601")); // syntax error
+        assert!(!is_transient_sqlstate("23505")); // unique violation
+    }
+
+    #[test]
+    fn test_is_transient_db_error() {
+        let reset = io::Error::from(io::ErrorKind::ConnectionReset);
+        assert!(is_transient_db_error(&SqlxError::Io(reset)));
+        assert!(is_transient_db_error(&SqlxError::PoolTimedOut));
+
+        assert!(!is_transient_db_error(&SqlxError::RowNotFound));
+        assert!(!is_transient_db_error(&SqlxError::PoolClosed));
+        assert!(!is_transient_db_error(&SqlxError::Protocol("unexpected message".into())));
+    }
+}
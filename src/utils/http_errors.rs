@@ -0,0 +1,96 @@
+/// Classification of Discord HTTP API errors for instrumentation
+use poise::serenity_prelude as serenity;
+
+/// Category of a failed Discord API call
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HttpErrorKind {
+    /// 429 Too Many Requests
+    RateLimited,
+    /// 403 Forbidden (missing permissions, closed DMs, ...)
+    Forbidden,
+    /// 404 Not Found (deleted channel, message or member)
+    NotFound,
+    /// 5xx responses from Discord
+    ServerError,
+    /// Any other failure (other status codes, network or gateway errors)
+    Other,
+}
+
+impl HttpErrorKind {
+    /// All kinds, in display order
+    pub const ALL: [HttpErrorKind; 5] = [
+        HttpErrorKind::RateLimited,
+        HttpErrorKind::Forbidden,
+        HttpErrorKind::NotFound,
+        HttpErrorKind::ServerError,
+        HttpErrorKind::Other,
+    ];
+
+    /// Human readable label for the kind
+    pub fn label(&self) -> &'static str {
+        match self {
+            HttpErrorKind::RateLimited => "Rate limited (429)",
+            HttpErrorKind::Forbidden => "Forbidden (403)",
+            HttpErrorKind::NotFound => "Not found (404)",
+            HttpErrorKind::ServerError => "Server error (5xx)",
+            HttpErrorKind::Other => "Other",
+        }
+    }
+}
+
+/// Classify an HTTP status code
+pub fn classify_status_code(status: u16) -> HttpErrorKind {
+    match status {
+        429 => HttpErrorKind::RateLimited,
+        403 => HttpErrorKind::Forbidden,
+        404 => HttpErrorKind::NotFound,
+        500..=599 => HttpErrorKind::ServerError,
+        _ => HttpErrorKind::Other,
+    }
+}
+
+/// Classify a serenity error by the HTTP status code of the failed request
+pub fn classify_http_error(error: &serenity::Error) -> HttpErrorKind {
+    match error {
+        serenity::Error::Http(http_error) => http_error
+            .status_code()
+            .map(|status| classify_status_code(status.as_u16()))
+            .unwrap_or(HttpErrorKind::Other),
+        _ => HttpErrorKind::Other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_status_code() {
+        assert_eq!(classify_status_code(429), HttpErrorKind::RateLimited);
+        assert_eq!(classify_status_code(403), HttpErrorKind::Forbidden);
+        assert_eq!(classify_status_code(404), HttpErrorKind::NotFound);
+        assert_eq!(classify_status_code(500), HttpErrorKind::ServerError);
+        assert_eq!(classify_status_code(502), HttpErrorKind::ServerError);
+        assert_eq!(classify_status_code(599), HttpErrorKind::ServerError);
+
+        assert_eq!(classify_status_code(400), HttpErrorKind::Other);
+        assert_eq!(classify_status_code(401), HttpErrorKind::Other);
+        assert_eq!(classify_status_code(600), HttpErrorKind::Other);
+    }
+
+    #[test]
+    fn test_classify_http_error_without_status() {
+        let error = serenity::Error::Http(serenity::HttpError::RateLimitI64F64);
+        assert_eq!(classify_http_error(&error), HttpErrorKind::Other);
+
+        let error = serenity::Error::Other("not an http error");
+        assert_eq!(classify_http_error(&error), HttpErrorKind::Other);
+    }
+
+    #[test]
+    fn test_all_kinds_have_distinct_labels() {
+        let labels: std::collections::HashSet<_> =
+            HttpErrorKind::ALL.iter().map(|k| k.label()).collect();
+        assert_eq!(labels.len(), HttpErrorKind::ALL.len());
+    }
+}
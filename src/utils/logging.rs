@@ -0,0 +1,57 @@
+/// Log filter construction, shared by startup and runtime log level changes
+use tracing_subscriber::{EnvFilter, Registry, filter::Directive, reload};
+
+use crate::constants::LOG_DIRECTIVE;
+
+/// Handle used to swap the log filter at runtime
+pub type LogReloadHandle = reload::Handle<EnvFilter, Registry>;
+
+/// Parse comma-separated log directives (e.g. `shiibot_rs::schedule=debug,serenity=warn`)
+pub fn parse_log_directives(input: &str) -> Result<Vec<Directive>, String> {
+    let directives: Vec<&str> = input
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    if directives.is_empty() {
+        return Err("No log directive given".to_string());
+    }
+
+    directives
+        .into_iter()
+        .map(|part| {
+            part.parse::<Directive>()
+                .map_err(|e| format!("Invalid log directive `{}`: {}", part, e))
+        })
+        .collect()
+}
+
+/// Build the log filter: `RUST_LOG`, then the default directive, then the extra directives
+pub fn build_log_filter(extra: Vec<Directive>) -> EnvFilter {
+    let filter = EnvFilter::from_default_env()
+        .add_directive(LOG_DIRECTIVE.parse().expect("valid log directive"));
+    extra
+        .into_iter()
+        .fold(filter, |filter, directive| filter.add_directive(directive))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_directives() {
+        let directives = parse_log_directives("shiibot_rs::schedule=debug, serenity=warn").unwrap();
+        assert_eq!(directives.len(), 2);
+        assert_eq!(directives[0].to_string(), "shiibot_rs::schedule=debug");
+        assert_eq!(directives[1].to_string(), "serenity=warn");
+    }
+
+    #[test]
+    fn test_parse_log_directives_invalid() {
+        assert!(parse_log_directives("").is_err());
+        assert!(parse_log_directives(" , ").is_err());
+        assert!(parse_log_directives("shiibot_rs=loud").is_err());
+    }
+}
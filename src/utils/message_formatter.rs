@@ -1,6 +1,7 @@
 /// Pure functions for birthday message formatting (Discord-agnostic)
-use crate::utils::datetime::calculate_age;
-use crate::utils::string_utils::process_newlines;
+use crate::constants::{DEFAULT_AGE_TEMPLATE, MAX_AGE_TEMPLATE_LENGTH, TEMPLATE_VARIANT_SEPARATOR};
+use crate::utils::datetime::{calculate_age, get_month_name};
+use crate::utils::string_utils::{process_newlines, take_chars};
 
 /// Replace placeholders in a message template
 pub fn apply_message_template(
@@ -18,21 +19,40 @@ pub fn apply_message_template(
     process_newlines(&result)
 }
 
-/// Format age information string
-pub fn format_age_info(birth_year: Option<i32>, current_year: i32) -> String {
+/// Age a user is turning, None when they didn't share their birth year or keep their age private
+pub fn visible_age(birth_year: Option<i32>, current_year: i32, show_age: bool) -> Option<i32> {
     birth_year
-        .map(|year| {
-            let age = calculate_age(year, current_year);
-            format!(" (turning {})", age)
-        })
+        .filter(|_| show_age)
+        .map(|year| calculate_age(year, current_year))
+}
+
+/// Format the age phrase of a default entry from the guild's template (`{age}` is replaced
+/// by the age), empty when there is no age to show
+pub fn format_age_info(age: Option<i32>, template: &str) -> String {
+    age.map(|age| format!(" ({})", template.replace("{age}", &age.to_string())))
         .unwrap_or_default()
 }
 
-/// Extract age value from age info string (for template replacement)
-pub fn extract_age_value(age_info: &str) -> &str {
-    age_info
-        .trim_start_matches(" (turning ")
-        .trim_end_matches(")")
+/// Validate an age phrase template, returning it trimmed
+pub fn validate_age_template(template: &str) -> Result<String, String> {
+    let template = template.trim();
+    if !template.contains("{age}") {
+        return Err("The age format must contain `{age}`.".to_string());
+    }
+    if template.chars().count() > MAX_AGE_TEMPLATE_LENGTH {
+        return Err(format!(
+            "The age format is too long (max {} characters).",
+            MAX_AGE_TEMPLATE_LENGTH
+        ));
+    }
+    let unknown: Vec<String> = find_unknown_placeholders(template)
+        .into_iter()
+        .filter(|placeholder| placeholder != "{age}")
+        .collect();
+    if !unknown.is_empty() {
+        return Err(format!("Unknown placeholder(s) in the age format: {}", unknown.join(", ")));
+    }
+    Ok(template.to_string())
 }
 
 /// Build a combined birthday message from parts
@@ -50,28 +70,40 @@ pub fn build_default_footer() -> String {
     "\nEveryone wish them a happy birthday! 🎂🎈".to_string()
 }
 
+/// Build the one-off message posted when birthday announcements are activated,
+/// reusing the configured header/footer when there is one
+pub fn build_activation_message(custom_header: &Option<String>, custom_footer: &Option<String>) -> String {
+    let header = process_custom_text(custom_header)
+        .unwrap_or_else(|| "🎉 **Birthday Announcements** 🎉".to_string());
+    let body = "Birthday announcements are now active here!";
+
+    match process_custom_text(custom_footer) {
+        Some(footer) => build_combined_message(&header, body, &footer),
+        None => format!("{}\n{}", header, body),
+    }
+}
+
 /// Process custom text by converting literal \n to actual newlines
 pub fn process_custom_text(text: &Option<String>) -> Option<String> {
     text.as_ref().map(|t| t.replace("\\n", "\n"))
 }
 
-/// Build a single birthday entry line
+/// Build a single birthday entry line. Custom templates get the bare age as `{age}`,
+/// the default entry phrases it with `age_template`.
 pub fn build_birthday_entry(
     user_name: &str,
     mention: &str,
-    age_info: &str,
+    age: Option<i32>,
+    age_template: &str,
     custom_template_with_age: &Option<String>,
     custom_template_without_age: &Option<String>,
     date: &str,
 ) -> String {
-    let has_age = !age_info.is_empty();
-    
-    if has_age {
+    if let Some(age_value) = age {
         if let Some(template) = custom_template_with_age {
-            let age_value = extract_age_value(age_info);
-            apply_message_template(template, user_name, mention, date, age_value)
+            apply_message_template(template, user_name, mention, date, &age_value.to_string())
         } else {
-            format!("• {}{}!", mention, age_info)
+            format!("• {}{}!", mention, format_age_info(Some(age_value), age_template))
         }
     } else {
         if let Some(template) = custom_template_without_age {
@@ -82,6 +114,279 @@ pub fn build_birthday_entry(
     }
 }
 
+/// Split a custom message into its variants (separated by `{or}`), ignoring empty ones
+pub fn split_template_variants(template: &str) -> Vec<String> {
+    template
+        .split(TEMPLATE_VARIANT_SEPARATOR)
+        .map(str::trim)
+        .filter(|variant| !variant.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Pick one of the variants of a custom message from an index (wrapping around),
+/// None when there are no variants so the default entry format is used
+pub fn select_template_variant(variants: &[String], index: usize) -> Option<String> {
+    if variants.is_empty() {
+        return None;
+    }
+    variants.get(index % variants.len()).cloned()
+}
+
+/// Refer to a birthday user by mention when they are pinged, by display name otherwise
+pub fn format_birthday_mention(user_id: u64, user_name: &str, ping: bool) -> String {
+    if ping {
+        format!("<@{}>", user_id)
+    } else {
+        format!("**{}**", user_name)
+    }
+}
+
+/// Sort birthday entries by display name (case-insensitive), using the key to break ties
+pub fn sort_birthday_entries<T: Ord>(entries: &mut [(T, String)]) {
+    entries.sort_by(|(a_key, a_name), (b_key, b_name)| {
+        a_name
+            .to_lowercase()
+            .cmp(&b_name.to_lowercase())
+            .then_with(|| a_key.cmp(b_key))
+    });
+}
+
+/// Build the announcement messages for a set of entries: one combined message,
+/// or one message per entry (each with the header and footer) when `separate` is set
+pub fn build_birthday_messages(header: &str, entries: &[String], footer: &str, separate: bool) -> Vec<String> {
+    if separate {
+        entries
+            .iter()
+            .map(|entry| build_combined_message(header, entry, footer))
+            .collect()
+    } else {
+        vec![build_combined_message(header, &join_birthday_entries(entries), footer)]
+    }
+}
+
+/// A birthday to announce: `((user_id, birth_year, show_age), display_name)`
+pub type NamedBirthday = ((u64, Option<i32>, bool), String);
+
+/// Custom texts of a guild's birthday announcements (None falls back to the defaults)
+#[derive(Debug, Clone, Default)]
+pub struct BirthdayTemplates {
+    /// Entry template for users who shared their birth year
+    pub message: Option<String>,
+    /// Entry template for users without a birth year
+    pub message_without_age: Option<String>,
+    pub header: Option<String>,
+    pub footer: Option<String>,
+    /// Age phrase of the default entries, `{age}` being replaced by the age
+    pub age: Option<String>,
+}
+
+/// Build the announcement messages for today's birthdays.
+/// Display names of the birthdays are resolved beforehand.
+/// Users are mentioned when pinged, named otherwise.
+/// `seed` chooses the variant of the custom message used for each user.
+pub fn build_birthday_payload(
+    birthdays: &[NamedBirthday],
+    templates: &BirthdayTemplates,
+    date: &str,
+    current_year: i32,
+    separate: bool,
+    ping: bool,
+    seed: usize,
+) -> Vec<String> {
+    let header = process_custom_text(&templates.header).unwrap_or_else(build_default_header);
+    let footer = process_custom_text(&templates.footer).unwrap_or_else(build_default_footer);
+    let entries = build_birthday_entries(birthdays, templates, date, current_year, ping, seed);
+
+    build_birthday_messages(&header, &entries, &footer, separate)
+}
+
+/// Birthday announcement laid out as an embed
+#[derive(Debug, Clone, PartialEq)]
+pub struct BirthdayEmbed {
+    pub title: String,
+    /// Header followed by the birthday entries
+    pub description: String,
+    pub footer: String,
+}
+
+/// Build the announcement embeds for today's birthdays: one for everyone,
+/// or one per user when `separate` is set
+pub fn build_birthday_embeds(
+    birthdays: &[NamedBirthday],
+    templates: &BirthdayTemplates,
+    date: &str,
+    current_year: i32,
+    separate: bool,
+    ping: bool,
+    seed: usize,
+) -> Vec<BirthdayEmbed> {
+    let header = process_custom_text(&templates.header).unwrap_or_else(build_default_header);
+    let footer = process_custom_text(&templates.footer).unwrap_or_else(build_default_footer);
+    let entries = build_birthday_entries(birthdays, templates, date, current_year, ping, seed);
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    let bodies = if separate {
+        entries
+    } else {
+        vec![join_birthday_entries(&entries)]
+    };
+
+    bodies
+        .into_iter()
+        .map(|body| BirthdayEmbed {
+            title: format!("🎂 Birthdays of {}", date),
+            description: format!("{}\n{}", header, body),
+            footer: footer.trim().to_string(),
+        })
+        .collect()
+}
+
+/// Build the entry of each birthday, sorted by display name.
+/// Each user gets the variant at `seed` plus their position.
+fn build_birthday_entries(
+    birthdays: &[NamedBirthday],
+    templates: &BirthdayTemplates,
+    date: &str,
+    current_year: i32,
+    ping: bool,
+    seed: usize,
+) -> Vec<String> {
+    let mut birthdays = birthdays.to_vec();
+    sort_birthday_entries(&mut birthdays);
+
+    let message_variants = templates.message.as_deref().map(split_template_variants).unwrap_or_default();
+    let message_without_age_variants = templates
+        .message_without_age
+        .as_deref()
+        .map(split_template_variants)
+        .unwrap_or_default();
+    let age_template = templates.age.as_deref().unwrap_or(DEFAULT_AGE_TEMPLATE);
+
+    birthdays
+        .iter()
+        .enumerate()
+        .map(|(position, ((user_id, birth_year, show_age), user_name))| {
+            let age = visible_age(*birth_year, current_year, *show_age);
+            let mention = format_birthday_mention(*user_id, user_name, ping);
+            let index = seed.wrapping_add(position);
+
+            build_birthday_entry(
+                user_name,
+                &mention,
+                age,
+                age_template,
+                &select_template_variant(&message_variants, index),
+                &select_template_variant(&message_without_age_variants, index),
+                date,
+            )
+        })
+        .collect()
+}
+
+/// Build the DM sent to a birthday user when the announcement channel can't be reached
+pub fn build_birthday_dm(guild_name: &str) -> String {
+    format!("🎉 **Happy Birthday from {}!** 🎂", guild_name)
+}
+
+/// Build the name of the thread opened under a birthday announcement.
+/// All users share one thread, a generic name is used when theirs don't fit.
+pub fn build_wishes_thread_name(user_names: &[String], max_length: usize) -> String {
+    let name = format!("🎂 Wishes for {}", user_names.join(", "));
+    if user_names.is_empty() || name.chars().count() > max_length {
+        return take_chars("🎂 Birthday wishes", max_length);
+    }
+    name
+}
+
+/// Find the `{placeholders}` left in a rendered text, e.g. misspelled or no longer supported ones
+pub fn find_unknown_placeholders(text: &str) -> Vec<String> {
+    let mut placeholders = Vec::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('{') {
+        rest = &rest[start + 1..];
+        let Some(end) = rest.find('}') else {
+            break;
+        };
+        let name = &rest[..end];
+        if !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            placeholders.push(format!("{{{}}}", name));
+            rest = &rest[end + 1..];
+        }
+    }
+    placeholders
+}
+
+/// List the suspicious parts of a template rendered with sample values:
+/// unreplaced placeholders, broken new lines, blank or overly long output
+pub fn find_template_issues(rendered: &str, max_length: usize) -> Vec<String> {
+    let mut issues: Vec<String> = find_unknown_placeholders(rendered)
+        .into_iter()
+        .map(|placeholder| format!("unknown placeholder `{}`", placeholder))
+        .collect();
+
+    // A doubled `\\n` in the stored text leaves a stray backslash before the new line
+    if rendered.contains("\\\n") {
+        issues.push("contains `\\\\n`, a backslash is left before a new line".to_string());
+    }
+    if rendered.trim().is_empty() {
+        issues.push("renders as an empty text".to_string());
+    }
+    let length = rendered.chars().count();
+    if length > max_length {
+        issues.push(format!("is {} characters long (max {})", length, max_length));
+    }
+
+    issues
+}
+
+/// Build the heads-up posted some days before birthdays, naming the users without pinging them
+pub fn build_birthday_reminder(names: &[String], days: u32, date: &str) -> String {
+    let when = if days == 1 {
+        "tomorrow".to_string()
+    } else {
+        format!("in {} days", days)
+    };
+    let (names, verb) = match names {
+        [name] => (format!("**{}**", name), "is"),
+        [rest @ .., last] => (
+            format!(
+                "{} and **{}**",
+                rest.iter().map(|name| format!("**{}**", name)).collect::<Vec<_>>().join(", "),
+                last
+            ),
+            "are",
+        ),
+        [] => ("Nobody".to_string(), "is"),
+    };
+    format!("⏰ Heads-up: {} {} celebrating their birthday {} ({})!", names, verb, when, date)
+}
+
+/// Build a text chart of birthday counts per month (index 0 is January), one line per
+/// month with a bar scaled so the busiest month spans `bar_width` characters
+pub fn build_month_chart(counts: &[u32; 12], bar_width: usize) -> String {
+    let max = counts.iter().copied().max().unwrap_or(0);
+    counts
+        .iter()
+        .enumerate()
+        .map(|(index, &count)| {
+            // Round up so a month with a single birthday still shows a bar
+            let length = if max == 0 {
+                0
+            } else {
+                (count as usize * bar_width).div_ceil(max as usize)
+            };
+            let month = &get_month_name(index as i32 + 1)[..3];
+            format!("{} {:<width$} {}", month, "█".repeat(length), count, width = bar_width)
+                .trim_end()
+                .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Join multiple birthday entries with newlines
 pub fn join_birthday_entries(entries: &[String]) -> String {
     entries.join("\n")
@@ -91,6 +396,139 @@ pub fn join_birthday_entries(entries: &[String]) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_build_birthday_payload_combined() {
+        let birthdays = vec![((2, None, true), "bob".to_string()), ((1, Some(2000), true), "Alice".to_string())];
+        let messages = build_birthday_payload(&birthdays, &BirthdayTemplates::default(), "15 March", 2024, false, true, 0);
+
+        assert_eq!(
+            messages,
+            vec![format!(
+                "{}\n• <@1> (turning 24)!\n• <@2>!\n{}",
+                build_default_header(),
+                build_default_footer()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_build_birthday_payload_separate_with_templates() {
+        let birthdays = vec![((1, Some(2000), true), "Alice".to_string()), ((2, None, true), "Bob".to_string())];
+        let templates = BirthdayTemplates {
+            message: Some("{user} is {age} on {date}".to_string()),
+            message_without_age: Some("{mention} celebrates".to_string()),
+            header: Some("Hi".to_string()),
+            footer: Some("Bye".to_string()),
+            // Custom entries get the bare age whatever the age format
+            age: Some("{age} ans".to_string()),
+        };
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, true, true, 0);
+
+        assert_eq!(
+            messages,
+            vec![
+                "Hi\nAlice is 24 on 15 March\nBye".to_string(),
+                "Hi\n<@2> celebrates\nBye".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_birthday_dm() {
+        assert_eq!(build_birthday_dm("My Server"), "🎉 **Happy Birthday from My Server!** 🎂");
+    }
+
+    #[test]
+    fn test_build_birthday_payload_empty() {
+        assert!(build_birthday_payload(&[], &BirthdayTemplates::default(), "1 January", 2024, true, true, 0).is_empty());
+    }
+
+    #[test]
+    fn test_build_birthday_payload_without_ping() {
+        let birthdays = vec![((1, Some(2000), true), "Alice".to_string()), ((2, None, true), "Bob".to_string())];
+        let templates = BirthdayTemplates {
+            message_without_age: Some("{mention} celebrates".to_string()),
+            ..Default::default()
+        };
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, true, false, 0);
+
+        assert_eq!(
+            messages,
+            vec![
+                format!("{}\n• **Alice** (turning 24)!\n{}", build_default_header(), build_default_footer()),
+                format!("{}\n**Bob** celebrates\n{}", build_default_header(), build_default_footer()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_birthday_embeds() {
+        let birthdays = vec![((2, None, true), "Bob".to_string()), ((1, Some(2000), true), "Alice".to_string())];
+        let templates = BirthdayTemplates {
+            header: Some("Hi".to_string()),
+            footer: Some("\\nBye".to_string()),
+            ..Default::default()
+        };
+
+        let embeds = build_birthday_embeds(&birthdays, &templates, "15 March", 2024, false, true, 0);
+        assert_eq!(
+            embeds,
+            vec![BirthdayEmbed {
+                title: "🎂 Birthdays of 15 March".to_string(),
+                description: "Hi\n• <@1> (turning 24)!\n• <@2>!".to_string(),
+                footer: "Bye".to_string(),
+            }]
+        );
+
+        let embeds = build_birthday_embeds(&birthdays, &templates, "15 March", 2024, true, true, 0);
+        assert_eq!(embeds.len(), 2);
+        assert_eq!(embeds[1].description, "Hi\n• <@2>!");
+
+        assert!(build_birthday_embeds(&[], &templates, "15 March", 2024, false, true, 0).is_empty());
+    }
+
+    #[test]
+    fn test_build_birthday_payload_template_variants() {
+        let birthdays = vec![((1, None, true), "Alice".to_string()), ((2, None, true), "Bob".to_string())];
+        let templates = BirthdayTemplates {
+            message_without_age: Some("Yay {user} {or} Cheers {user}".to_string()),
+            ..Default::default()
+        };
+
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, true, true, 0);
+        assert!(messages[0].contains("Yay Alice"));
+        assert!(messages[1].contains("Cheers Bob"));
+
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, true, true, 1);
+        assert!(messages[0].contains("Cheers Alice"));
+        assert!(messages[1].contains("Yay Bob"));
+    }
+
+    #[test]
+    fn test_split_template_variants() {
+        assert_eq!(split_template_variants("Hi {user}"), vec!["Hi {user}"]);
+        assert_eq!(split_template_variants("A {or} B{or}C"), vec!["A", "B", "C"]);
+        assert_eq!(split_template_variants("A {or} {or} "), vec!["A"]);
+        // Spoilers are left alone
+        assert_eq!(split_template_variants("Hi ||{user}||"), vec!["Hi ||{user}||"]);
+        assert!(split_template_variants("").is_empty());
+    }
+
+    #[test]
+    fn test_select_template_variant() {
+        let variants = vec!["A".to_string(), "B".to_string()];
+        assert_eq!(select_template_variant(&variants, 0), Some("A".to_string()));
+        assert_eq!(select_template_variant(&variants, 1), Some("B".to_string()));
+        assert_eq!(select_template_variant(&variants, 2), Some("A".to_string()));
+        assert_eq!(select_template_variant(&[], 5), None);
+    }
+
+    #[test]
+    fn test_format_birthday_mention() {
+        assert_eq!(format_birthday_mention(123, "Alice", true), "<@123>");
+        assert_eq!(format_birthday_mention(123, "Alice", false), "**Alice**");
+    }
+
     #[test]
     fn test_apply_message_template() {
         let result = apply_message_template(
@@ -128,21 +566,54 @@ mod tests {
     }
 
     #[test]
-    fn test_format_age_info_with_year() {
-        assert_eq!(format_age_info(Some(2000), 2025), " (turning 25)");
-        assert_eq!(format_age_info(Some(1990), 2025), " (turning 35)");
+    fn test_format_age_info_with_age() {
+        assert_eq!(format_age_info(Some(25), DEFAULT_AGE_TEMPLATE), " (turning 25)");
+        assert_eq!(format_age_info(Some(35), "{age} ans"), " (35 ans)");
+    }
+
+    #[test]
+    fn test_visible_age() {
+        assert_eq!(visible_age(Some(2000), 2025, true), Some(25));
+        assert_eq!(visible_age(Some(2000), 2025, false), None);
+        assert_eq!(visible_age(None, 2025, true), None);
+    }
+
+    #[test]
+    fn test_validate_age_template() {
+        assert_eq!(validate_age_template("  {age} ans "), Ok("{age} ans".to_string()));
+        assert!(validate_age_template("turning").is_err());
+        assert!(validate_age_template("{age} {years}").is_err());
+        assert!(validate_age_template(&format!("{{age}}{}", "x".repeat(MAX_AGE_TEMPLATE_LENGTH))).is_err());
+    }
+
+    #[test]
+    fn test_build_birthday_payload_custom_age_template() {
+        let birthdays = vec![((1, Some(2000), true), "Alice".to_string())];
+        let templates = BirthdayTemplates {
+            age: Some("{age} ans".to_string()),
+            ..Default::default()
+        };
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, false, true, 0);
+
+        assert!(messages[0].contains("• <@1> (24 ans)!"));
     }
 
     #[test]
-    fn test_format_age_info_without_year() {
-        assert_eq!(format_age_info(None, 2025), "");
+    fn test_build_birthday_payload_hidden_age_uses_template_without_age() {
+        let birthdays = vec![((1, Some(2000), false), "Alice".to_string())];
+        let templates = BirthdayTemplates {
+            message: Some("{user} is {age}".to_string()),
+            message_without_age: Some("{user} celebrates".to_string()),
+            ..Default::default()
+        };
+        let messages = build_birthday_payload(&birthdays, &templates, "15 March", 2024, true, true, 0);
+
+        assert!(messages[0].contains("Alice celebrates"));
     }
 
     #[test]
-    fn test_extract_age_value() {
-        assert_eq!(extract_age_value(" (turning 25)"), "25");
-        assert_eq!(extract_age_value(" (turning 0)"), "0");
-        assert_eq!(extract_age_value(""), "");
+    fn test_format_age_info_without_age() {
+        assert_eq!(format_age_info(None, DEFAULT_AGE_TEMPLATE), "");
     }
 
     #[test]
@@ -165,6 +636,25 @@ mod tests {
         assert!(footer.contains("🎂"));
     }
 
+    #[test]
+    fn test_build_activation_message_default() {
+        let message = build_activation_message(&None, &None);
+        assert!(message.starts_with("🎉 **Birthday Announcements** 🎉\n"));
+        assert!(message.ends_with("now active here!"));
+    }
+
+    #[test]
+    fn test_build_activation_message_custom() {
+        let message = build_activation_message(
+            &Some("Party time\\n---".to_string()),
+            &Some("See you soon".to_string()),
+        );
+        assert_eq!(
+            message,
+            "Party time\n---\nBirthday announcements are now active here!\nSee you soon"
+        );
+    }
+
     #[test]
     fn test_process_custom_text() {
         assert_eq!(
@@ -179,7 +669,8 @@ mod tests {
         let entry = build_birthday_entry(
             "Alice",
             "<@123>",
-            " (turning 25)",
+            Some(25),
+            DEFAULT_AGE_TEMPLATE,
             &Some("{user} ({age})".to_string()),
             &Some("{user}".to_string()),
             "15 March",
@@ -192,7 +683,8 @@ mod tests {
         let entry = build_birthday_entry(
             "Bob",
             "<@456>",
-            " (turning 30)",
+            Some(30),
+            DEFAULT_AGE_TEMPLATE,
             &None,
             &None,
             "20 April",
@@ -205,7 +697,8 @@ mod tests {
         let entry = build_birthday_entry(
             "Charlie",
             "<@789>",
-            "",
+            None,
+            DEFAULT_AGE_TEMPLATE,
             &Some("{user} ({age})".to_string()),
             &Some("{mention} celebrates today!".to_string()),
             "1 January",
@@ -213,6 +706,90 @@ mod tests {
         assert_eq!(entry, "<@789> celebrates today!");
     }
 
+    #[test]
+    fn test_sort_birthday_entries_by_name() {
+        let mut entries = vec![
+            (3, "charlie".to_string()),
+            (1, "Alice".to_string()),
+            (2, "bob".to_string()),
+        ];
+        sort_birthday_entries(&mut entries);
+        assert_eq!(
+            entries,
+            vec![
+                (1, "Alice".to_string()),
+                (2, "bob".to_string()),
+                (3, "charlie".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sort_birthday_entries_ties_use_key() {
+        let mut entries = vec![
+            (20, "Sam".to_string()),
+            (10, "sam".to_string()),
+            (5, "Zoe".to_string()),
+        ];
+        sort_birthday_entries(&mut entries);
+        assert_eq!(
+            entries,
+            vec![
+                (10, "sam".to_string()),
+                (20, "Sam".to_string()),
+                (5, "Zoe".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_build_birthday_messages_combined() {
+        let entries = vec!["• <@1>!".to_string(), "• <@2>!".to_string()];
+        assert_eq!(
+            build_birthday_messages("H", &entries, "F", false),
+            vec!["H\n• <@1>!\n• <@2>!\nF".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_build_birthday_messages_separate() {
+        let entries = vec!["• <@1>!".to_string(), "• <@2>!".to_string()];
+        assert_eq!(
+            build_birthday_messages("H", &entries, "F", true),
+            vec!["H\n• <@1>!\nF".to_string(), "H\n• <@2>!\nF".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_find_unknown_placeholders() {
+        assert!(find_unknown_placeholders("Happy birthday Alice!").is_empty());
+        assert_eq!(
+            find_unknown_placeholders("{username} turns {years} on {date"),
+            vec!["{username}".to_string(), "{years}".to_string()]
+        );
+        // Braces that aren't placeholders are left alone
+        assert!(find_unknown_placeholders("{} { } {a b} 🎉 {").is_empty());
+        assert_eq!(find_unknown_placeholders("{{nested}}"), vec!["{nested}".to_string()]);
+    }
+
+    #[test]
+    fn test_find_template_issues() {
+        let rendered = apply_message_template("{user} is {age}!", "Alice", "<@1>", "15 March", "30");
+        assert!(find_template_issues(&rendered, 100).is_empty());
+
+        let rendered = apply_message_template("{name} is {age}", "Alice", "<@1>", "15 March", "30");
+        assert_eq!(find_template_issues(&rendered, 100), vec!["unknown placeholder `{name}`"]);
+
+        let rendered = apply_message_template("Hi\\\\n{user}", "Alice", "<@1>", "15 March", "");
+        assert_eq!(find_template_issues(&rendered, 100).len(), 1);
+
+        assert_eq!(find_template_issues("   ", 100), vec!["renders as an empty text"]);
+        assert_eq!(
+            find_template_issues(&"a".repeat(11), 10),
+            vec!["is 11 characters long (max 10)"]
+        );
+    }
+
     #[test]
     fn test_join_birthday_entries() {
         let entries = vec![
@@ -234,6 +811,51 @@ mod tests {
         assert_eq!(result, "• <@123>!");
     }
 
+    #[test]
+    fn test_build_wishes_thread_name() {
+        assert_eq!(build_wishes_thread_name(&["Alice".to_string()], 100), "🎂 Wishes for Alice");
+        assert_eq!(
+            build_wishes_thread_name(&["Alice".to_string(), "Bob".to_string()], 100),
+            "🎂 Wishes for Alice, Bob"
+        );
+        assert_eq!(build_wishes_thread_name(&["Alice".to_string()], 10), "🎂 Birthday");
+        assert_eq!(build_wishes_thread_name(&[], 100), "🎂 Birthday wishes");
+    }
+
+    #[test]
+    fn test_build_birthday_reminder() {
+        assert_eq!(
+            build_birthday_reminder(&["Alice".to_string()], 3, "15 March"),
+            "⏰ Heads-up: **Alice** is celebrating their birthday in 3 days (15 March)!"
+        );
+        assert_eq!(
+            build_birthday_reminder(&["Alice".to_string(), "Bob".to_string(), "Carol".to_string()], 1, "1 May"),
+            "⏰ Heads-up: **Alice**, **Bob** and **Carol** are celebrating their birthday tomorrow (1 May)!"
+        );
+    }
+
+    #[test]
+    fn test_build_month_chart() {
+        let mut counts = [0u32; 12];
+        counts[0] = 4;
+        counts[1] = 1;
+        counts[11] = 2;
+        let chart = build_month_chart(&counts, 4);
+        let lines: Vec<&str> = chart.lines().collect();
+        assert_eq!(lines.len(), 12);
+        assert_eq!(lines[0], "Jan ████ 4");
+        assert_eq!(lines[1], "Feb █    1");
+        assert_eq!(lines[2], "Mar      0");
+        assert_eq!(lines[11], "Dec ██   2");
+    }
+
+    #[test]
+    fn test_build_month_chart_empty() {
+        let chart = build_month_chart(&[0; 12], 4);
+        assert!(chart.lines().all(|line| line.ends_with(" 0")));
+        assert!(!chart.contains('█'));
+    }
+
     #[test]
     fn test_join_birthday_entries_empty() {
         let entries: Vec<String> = vec![];
@@ -1,4 +1,5 @@
 /// Pure functions for formatting error and success messages (Discord-agnostic)
+use crate::models::GuildSettings;
 
 /// Format a validation error message with emoji
 pub fn format_error(message: &str) -> String {
@@ -20,7 +21,21 @@ pub fn format_info(message: &str) -> String {
     format!("ℹ️ {}", message)
 }
 
+/// Decorate a message with the guild's branding prefix/suffix (no-op when none is configured)
+pub fn decorate_message(settings: &GuildSettings, body: &str) -> String {
+    let mut parts = Vec::with_capacity(3);
+    if let Some(prefix) = settings.message_prefix.as_deref().filter(|p| !p.is_empty()) {
+        parts.push(prefix.replace("\\n", "\n"));
+    }
+    parts.push(body.to_string());
+    if let Some(suffix) = settings.message_suffix.as_deref().filter(|s| !s.is_empty()) {
+        parts.push(suffix.replace("\\n", "\n"));
+    }
+    parts.join("\n")
+}
+
 /// Build an error message for invalid input
+#[cfg(feature = "birthday")]
 pub fn build_invalid_input_error(field_name: &str, expected: &str) -> String {
     format_error(&format!(
         "Invalid {}! Please enter {}.",
@@ -37,6 +52,7 @@ pub fn build_permission_error(required_permission: &str) -> String {
 }
 
 /// Build an error message for command usage in wrong context
+#[cfg(feature = "lobby")]
 pub fn build_context_error(required_context: &str) -> String {
     format_error(&format!(
         "This command must be used {}",
@@ -50,11 +66,13 @@ pub fn build_database_error() -> String {
 }
 
 /// Build a success message for saving data
+#[cfg(feature = "birthday")]
 pub fn build_save_success(item_type: &str) -> String {
     format_success(&format!("{} saved successfully!", item_type))
 }
 
 /// Build a success message for deleting data
+#[cfg(feature = "birthday")]
 pub fn build_delete_success(item_type: &str) -> String {
     format_success(&format!("{} deleted successfully!", item_type))
 }
@@ -70,6 +88,7 @@ pub fn build_date_format_help() -> String {
 }
 
 /// Truncate a long message with ellipsis
+#[cfg(feature = "birthday")]
 pub fn truncate_message(message: &str, max_length: usize) -> String {
     if message.len() <= max_length {
         message.to_string()
@@ -126,6 +145,7 @@ mod tests {
         assert_eq!(format_info("Good to know"), "ℹ️ Good to know");
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_build_invalid_input_error() {
         let result = build_invalid_input_error("month", "a number between 1 and 12");
@@ -142,6 +162,7 @@ mod tests {
         assert!(result.contains("MANAGE_GUILD"));
     }
 
+    #[cfg(feature = "lobby")]
     #[test]
     fn test_build_context_error() {
         let result = build_context_error("in a server");
@@ -156,11 +177,13 @@ mod tests {
         assert!(result.contains("database"));
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_build_save_success() {
         assert_eq!(build_save_success("Birthday"), "✅ Birthday saved successfully!");
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_build_delete_success() {
         assert_eq!(build_delete_success("Channel"), "✅ Channel deleted successfully!");
@@ -179,11 +202,13 @@ mod tests {
         assert!(help.contains("MM/DD"));
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_truncate_message_short() {
         assert_eq!(truncate_message("Hello", 10), "Hello");
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_truncate_message_long() {
         assert_eq!(
@@ -192,11 +217,13 @@ mod tests {
         );
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_truncate_message_exact() {
         assert_eq!(truncate_message("Hello", 5), "Hello");
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_truncate_message_very_short_limit() {
         assert_eq!(truncate_message("Hello", 2), "He");
@@ -229,4 +256,30 @@ mod tests {
         assert!(result.contains("2. Second error"));
         assert!(result.contains("3. Third error"));
     }
+
+    #[test]
+    fn test_decorate_message_default_is_noop() {
+        let settings = GuildSettings::default();
+        assert_eq!(decorate_message(&settings, "Hello"), "Hello");
+    }
+
+    #[test]
+    fn test_decorate_message_with_branding() {
+        let settings = GuildSettings {
+            message_prefix: Some("**Acme Gaming**".to_string()),
+            message_suffix: Some("-- powered by Acme\\nacme.gg".to_string()),
+            ..GuildSettings::default()
+        };
+        assert_eq!(
+            decorate_message(&settings, "Hello"),
+            "**Acme Gaming**\nHello\n-- powered by Acme\nacme.gg"
+        );
+
+        let prefix_only = GuildSettings {
+            message_prefix: Some("[Acme]".to_string()),
+            message_suffix: Some(String::new()),
+            ..GuildSettings::default()
+        };
+        assert_eq!(decorate_message(&prefix_only, "Hello"), "[Acme]\nHello");
+    }
 }
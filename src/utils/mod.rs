@@ -1,10 +1,22 @@
 /// Utility modules for common functionality
+#[cfg(feature = "birthday")]
+pub mod birthday_export;
+#[cfg(feature = "birthday")]
+pub mod birthday_import;
 pub mod channel_utils;
 pub mod collection_utils;
+#[cfg(feature = "birthday")]
 pub mod datetime;
+#[cfg(feature = "birthday")]
+pub mod db_retry;
+pub mod http_errors;
+pub mod logging;
+#[cfg(feature = "birthday")]
 pub mod message_formatter;
 pub mod messages;
 pub mod permissions;
+pub mod rng;
+#[cfg(feature = "birthday")]
 pub mod role_logic;
 pub mod schedule_utils;
 pub mod string_utils;
@@ -1,4 +1,157 @@
 /// Pure functions for permission and ownership logic (Discord-agnostic)
+#[cfg(feature = "lobby")]
+use poise::serenity_prelude::{PermissionOverwrite, PermissionOverwriteType, Permissions, RoleId, UserId};
+
+/// Permissions denied to @everyone on the archive category
+#[cfg(feature = "lobby")]
+pub fn archive_denied_permissions() -> Permissions {
+    Permissions::VIEW_CHANNEL | Permissions::CONNECT
+}
+
+/// Check that the @everyone overwrite of the archive category still hides it
+/// (denies `VIEW_CHANNEL` and `CONNECT` without allowing them back)
+#[cfg(feature = "lobby")]
+pub fn archive_overwrite_is_correct(overwrites: &[PermissionOverwrite], everyone_role: RoleId) -> bool {
+    let required = archive_denied_permissions();
+    overwrites.iter().any(|overwrite| {
+        overwrite.kind == PermissionOverwriteType::Role(everyone_role)
+            && overwrite.deny.contains(required)
+            && !overwrite.allow.intersects(required)
+    })
+}
+
+/// Overwrites of a channel restored from the archive: its own overwrites without the archive's
+/// deny on @everyone, with the lobby's overwrites applied on top (they win for the same role/member)
+#[cfg(feature = "lobby")]
+pub fn build_restore_overwrites(
+    archived: &[PermissionOverwrite],
+    lobby: &[PermissionOverwrite],
+    everyone_role: RoleId,
+) -> Vec<PermissionOverwrite> {
+    let everyone = PermissionOverwriteType::Role(everyone_role);
+    let mut overwrites: Vec<PermissionOverwrite> = archived
+        .iter()
+        .cloned()
+        .filter_map(|mut overwrite| {
+            if overwrite.kind == everyone {
+                overwrite.deny.remove(archive_denied_permissions());
+                if overwrite.allow.is_empty() && overwrite.deny.is_empty() {
+                    return None;
+                }
+            }
+            Some(overwrite)
+        })
+        .collect();
+
+    for lobby_overwrite in lobby {
+        overwrites.retain(|overwrite| overwrite.kind != lobby_overwrite.kind);
+        overwrites.push(lobby_overwrite.clone());
+    }
+
+    overwrites
+}
+
+/// Permissions granted to the owner of a temp channel on top of the inherited ones.
+/// `CONNECT` lets the owner back into their channel once it is locked.
+#[cfg(feature = "lobby")]
+pub fn owner_channel_permissions() -> Permissions {
+    Permissions::CONNECT
+        | Permissions::MANAGE_CHANNELS
+        | Permissions::MOVE_MEMBERS
+        | Permissions::MUTE_MEMBERS
+        | Permissions::DEAFEN_MEMBERS
+}
+
+/// Move the owner overwrite of a temp channel from the old owner to the new one
+#[cfg(feature = "lobby")]
+pub fn transfer_owner_overwrite(
+    overwrites: &mut Vec<PermissionOverwrite>,
+    old_owner: UserId,
+    new_owner: UserId,
+) {
+    overwrites.retain(|overwrite| {
+        overwrite.kind != PermissionOverwriteType::Member(old_owner)
+            && overwrite.kind != PermissionOverwriteType::Member(new_owner)
+    });
+    overwrites.push(PermissionOverwrite {
+        allow: owner_channel_permissions(),
+        deny: Permissions::empty(),
+        kind: PermissionOverwriteType::Member(new_owner),
+    });
+}
+
+/// Deny `CONNECT` to a member in a channel's overwrites, keeping their other permissions.
+/// Returns whether the member was explicitly allowed to connect, so it can be given back.
+#[cfg(feature = "lobby")]
+pub fn deny_member_connect(overwrites: &mut Vec<PermissionOverwrite>, user_id: UserId) -> bool {
+    let kind = PermissionOverwriteType::Member(user_id);
+    match overwrites.iter_mut().find(|overwrite| overwrite.kind == kind) {
+        Some(overwrite) => {
+            let was_allowed = overwrite.allow.contains(Permissions::CONNECT);
+            overwrite.allow.remove(Permissions::CONNECT);
+            overwrite.deny.insert(Permissions::CONNECT);
+            was_allowed
+        }
+        None => {
+            overwrites.push(PermissionOverwrite {
+                allow: Permissions::empty(),
+                deny: Permissions::CONNECT,
+                kind,
+            });
+            false
+        }
+    }
+}
+
+/// Lift the `CONNECT` deny of a member in a channel's overwrites, giving back the explicit
+/// allow they had before when `allowed` is set (an overwrite left empty is removed)
+#[cfg(feature = "lobby")]
+pub fn restore_member_connect(overwrites: &mut Vec<PermissionOverwrite>, user_id: UserId, allowed: bool) {
+    let kind = PermissionOverwriteType::Member(user_id);
+    match overwrites.iter_mut().find(|overwrite| overwrite.kind == kind) {
+        Some(overwrite) => {
+            overwrite.deny.remove(Permissions::CONNECT);
+            if allowed {
+                overwrite.allow.insert(Permissions::CONNECT);
+            }
+        }
+        None if allowed => overwrites.push(PermissionOverwrite {
+            allow: Permissions::CONNECT,
+            deny: Permissions::empty(),
+            kind,
+        }),
+        None => {}
+    }
+    overwrites.retain(|overwrite| {
+        overwrite.kind != kind || !overwrite.allow.is_empty() || !overwrite.deny.is_empty()
+    });
+}
+
+/// Check if a member may claim a temp channel: its owner must be gone for at least the grace period
+/// (an unknown departure time, e.g. after a restart, counts as elapsed)
+#[cfg(feature = "lobby")]
+pub fn can_claim_channel(owner_present: bool, secs_since_owner_left: Option<u64>, grace_secs: u64) -> bool {
+    !owner_present && secs_since_owner_left.is_none_or(|secs| secs >= grace_secs)
+}
+
+/// Deny or allow back `CONNECT` to @everyone in a channel's overwrites, keeping its other permissions
+#[cfg(feature = "lobby")]
+pub fn set_connect_locked(overwrites: &mut Vec<PermissionOverwrite>, everyone_role: RoleId, locked: bool) {
+    let kind = PermissionOverwriteType::Role(everyone_role);
+    match overwrites.iter_mut().find(|overwrite| overwrite.kind == kind) {
+        Some(overwrite) if locked => {
+            overwrite.allow.remove(Permissions::CONNECT);
+            overwrite.deny.insert(Permissions::CONNECT);
+        }
+        Some(overwrite) => overwrite.deny.remove(Permissions::CONNECT),
+        None if locked => overwrites.push(PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::CONNECT,
+            kind,
+        }),
+        None => {}
+    }
+}
 
 /// Check if a user ID matches an owner ID
 pub fn is_owner(user_id: u64, owner_id: u64) -> bool {
@@ -44,6 +197,247 @@ pub fn has_duplicates<T: Eq + std::hash::Hash>(items: &[T]) -> bool {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "lobby")]
+    fn everyone_overwrite(allow: Permissions, deny: Permissions) -> PermissionOverwrite {
+        PermissionOverwrite {
+            allow,
+            deny,
+            kind: PermissionOverwriteType::Role(RoleId::new(1)),
+        }
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_build_restore_overwrites_strips_archive_deny() {
+        let archived = vec![everyone_overwrite(Permissions::empty(), archive_denied_permissions())];
+
+        let overwrites = build_restore_overwrites(&archived, &[], RoleId::new(1));
+        assert!(overwrites.is_empty());
+
+        // Other denied permissions of @everyone are kept
+        let archived = vec![everyone_overwrite(
+            Permissions::empty(),
+            archive_denied_permissions() | Permissions::SPEAK,
+        )];
+        let overwrites = build_restore_overwrites(&archived, &[], RoleId::new(1));
+        assert_eq!(overwrites.len(), 1);
+        assert_eq!(overwrites[0].deny, Permissions::SPEAK);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_build_restore_overwrites_applies_lobby_overwrites() {
+        let member = PermissionOverwrite {
+            allow: Permissions::SPEAK,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(UserId::new(2)),
+        };
+        let archived = vec![everyone_overwrite(Permissions::empty(), archive_denied_permissions())];
+        let lobby = vec![
+            everyone_overwrite(Permissions::VIEW_CHANNEL, Permissions::STREAM),
+            member.clone(),
+        ];
+
+        let overwrites = build_restore_overwrites(&archived, &lobby, RoleId::new(1));
+        assert_eq!(overwrites.len(), 2);
+        let everyone = overwrites
+            .iter()
+            .find(|overwrite| overwrite.kind == PermissionOverwriteType::Role(RoleId::new(1)))
+            .unwrap();
+        assert_eq!(everyone.allow, Permissions::VIEW_CHANNEL);
+        assert_eq!(everyone.deny, Permissions::STREAM);
+        assert!(overwrites.contains(&member));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_archive_overwrite_is_correct() {
+        let everyone = RoleId::new(1);
+
+        let correct = vec![everyone_overwrite(Permissions::empty(), archive_denied_permissions())];
+        assert!(archive_overwrite_is_correct(&correct, everyone));
+
+        let stricter = vec![everyone_overwrite(
+            Permissions::empty(),
+            archive_denied_permissions() | Permissions::SPEAK,
+        )];
+        assert!(archive_overwrite_is_correct(&stricter, everyone));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_archive_overwrite_drifted() {
+        let everyone = RoleId::new(1);
+
+        // No overwrite at all
+        assert!(!archive_overwrite_is_correct(&[], everyone));
+
+        // Only part of the permissions denied
+        let partial = vec![everyone_overwrite(Permissions::empty(), Permissions::CONNECT)];
+        assert!(!archive_overwrite_is_correct(&partial, everyone));
+
+        // View allowed back
+        let allowed = vec![everyone_overwrite(Permissions::VIEW_CHANNEL, Permissions::CONNECT)];
+        assert!(!archive_overwrite_is_correct(&allowed, everyone));
+
+        // Deny set on another role
+        let other_role = vec![everyone_overwrite(Permissions::empty(), archive_denied_permissions())];
+        assert!(!archive_overwrite_is_correct(&other_role, RoleId::new(2)));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_set_connect_locked() {
+        let everyone = RoleId::new(1);
+
+        // Adds an overwrite when there is none
+        let mut overwrites = Vec::new();
+        set_connect_locked(&mut overwrites, everyone, true);
+        assert_eq!(overwrites, vec![everyone_overwrite(Permissions::empty(), Permissions::CONNECT)]);
+
+        // Keeps the other permissions of an existing overwrite
+        let mut overwrites = vec![everyone_overwrite(
+            Permissions::CONNECT | Permissions::SPEAK,
+            Permissions::STREAM,
+        )];
+        set_connect_locked(&mut overwrites, everyone, true);
+        assert_eq!(
+            overwrites,
+            vec![everyone_overwrite(Permissions::SPEAK, Permissions::STREAM | Permissions::CONNECT)]
+        );
+
+        set_connect_locked(&mut overwrites, everyone, false);
+        assert_eq!(overwrites, vec![everyone_overwrite(Permissions::SPEAK, Permissions::STREAM)]);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_owner_can_rejoin_locked_channel() {
+        let everyone = RoleId::new(1);
+        let owner = UserId::new(10);
+        let mut overwrites = vec![PermissionOverwrite {
+            allow: owner_channel_permissions(),
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(owner),
+        }];
+        set_connect_locked(&mut overwrites, everyone, true);
+
+        // Member overwrites take precedence over the @everyone one
+        let connect = |user_id: UserId| {
+            let mut permissions = Permissions::CONNECT;
+            for overwrite in &overwrites {
+                if overwrite.kind == PermissionOverwriteType::Role(everyone) {
+                    permissions = (permissions - overwrite.deny) | overwrite.allow;
+                }
+            }
+            for overwrite in &overwrites {
+                if overwrite.kind == PermissionOverwriteType::Member(user_id) {
+                    permissions = (permissions - overwrite.deny) | overwrite.allow;
+                }
+            }
+            permissions.contains(Permissions::CONNECT)
+        };
+        assert!(connect(owner));
+        assert!(!connect(UserId::new(20)));
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_set_connect_unlocked_without_overwrite() {
+        let mut overwrites = Vec::new();
+        set_connect_locked(&mut overwrites, RoleId::new(1), false);
+        assert!(overwrites.is_empty());
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_deny_and_restore_member_connect() {
+        let user = UserId::new(30);
+        let everyone = everyone_overwrite(Permissions::empty(), Permissions::CONNECT);
+        let denied = PermissionOverwrite {
+            allow: Permissions::empty(),
+            deny: Permissions::CONNECT,
+            kind: PermissionOverwriteType::Member(user),
+        };
+
+        let mut overwrites = vec![everyone.clone()];
+        assert!(!deny_member_connect(&mut overwrites, user));
+        assert_eq!(overwrites, vec![everyone.clone(), denied]);
+
+        // Restoring drops the now empty overwrite
+        restore_member_connect(&mut overwrites, user, false);
+        assert_eq!(overwrites, vec![everyone.clone()]);
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_restore_member_connect_gives_back_allow() {
+        let user = UserId::new(30);
+
+        // Other permissions of the member are kept, and their explicit allow comes back
+        let mut overwrites = vec![PermissionOverwrite {
+            allow: Permissions::SPEAK | Permissions::CONNECT,
+            deny: Permissions::empty(),
+            kind: PermissionOverwriteType::Member(user),
+        }];
+        let was_allowed = deny_member_connect(&mut overwrites, user);
+        assert!(was_allowed);
+        assert_eq!(overwrites[0].allow, Permissions::SPEAK);
+        assert_eq!(overwrites[0].deny, Permissions::CONNECT);
+
+        // Denying again while kicked doesn't lose track of the original allow
+        assert!(!deny_member_connect(&mut overwrites, user));
+
+        restore_member_connect(&mut overwrites, user, was_allowed);
+        assert_eq!(overwrites[0].allow, Permissions::SPEAK | Permissions::CONNECT);
+        assert!(overwrites[0].deny.is_empty());
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_transfer_owner_overwrite() {
+        let old_owner = UserId::new(10);
+        let new_owner = UserId::new(20);
+        let everyone = everyone_overwrite(Permissions::empty(), Permissions::CONNECT);
+
+        let mut overwrites = vec![
+            everyone.clone(),
+            PermissionOverwrite {
+                allow: owner_channel_permissions(),
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(old_owner),
+            },
+            PermissionOverwrite {
+                allow: Permissions::SPEAK,
+                deny: Permissions::empty(),
+                kind: PermissionOverwriteType::Member(new_owner),
+            },
+        ];
+        transfer_owner_overwrite(&mut overwrites, old_owner, new_owner);
+
+        assert_eq!(
+            overwrites,
+            vec![
+                everyone,
+                PermissionOverwrite {
+                    allow: owner_channel_permissions(),
+                    deny: Permissions::empty(),
+                    kind: PermissionOverwriteType::Member(new_owner),
+                },
+            ]
+        );
+    }
+
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_can_claim_channel() {
+        assert!(!can_claim_channel(true, None, 60));
+        assert!(!can_claim_channel(true, Some(120), 60));
+        assert!(!can_claim_channel(false, Some(30), 60));
+        assert!(can_claim_channel(false, Some(60), 60));
+        assert!(can_claim_channel(false, None, 60));
+    }
+
     #[test]
     fn test_is_owner() {
         assert!(is_owner(123, 123));
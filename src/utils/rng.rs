@@ -0,0 +1,69 @@
+/// Central source of randomness, so randomized behaviour can be made reproducible
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::ops::Range;
+use std::sync::Mutex;
+
+/// Random number source shared by every feature that needs randomness.
+/// Uses real randomness by default, or a fixed seed for reproducible tests and debugging.
+#[derive(Debug)]
+pub struct RngSource {
+    rng: Mutex<StdRng>,
+}
+
+impl RngSource {
+    /// Create a source seeded from the given value, or from the OS entropy when `None`
+    pub fn new(seed: Option<u64>) -> Self {
+        let rng = match seed {
+            Some(seed) => StdRng::seed_from_u64(seed),
+            None => StdRng::from_entropy(),
+        };
+        Self {
+            rng: Mutex::new(rng),
+        }
+    }
+
+    /// Pick a random index in the given range (returns `range.start` for an empty range)
+    #[cfg_attr(not(feature = "birthday"), allow(dead_code))]
+    pub fn index(&self, range: Range<usize>) -> usize {
+        if range.is_empty() {
+            return range.start;
+        }
+        self.rng.lock().expect("rng lock poisoned").gen_range(range)
+    }
+
+    /// Pick a random duration in milliseconds between 0 and `max_ms` (inclusive), e.g. for jitter
+    #[cfg_attr(not(feature = "lobby"), allow(dead_code))]
+    pub fn jitter_ms(&self, max_ms: u64) -> u64 {
+        self.rng.lock().expect("rng lock poisoned").gen_range(0..=max_ms)
+    }
+}
+
+impl Default for RngSource {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_seed_is_deterministic() {
+        let a = RngSource::new(Some(42));
+        let b = RngSource::new(Some(42));
+
+        let picks_a: Vec<usize> = (0..20).map(|_| a.index(0..1000)).collect();
+        let picks_b: Vec<usize> = (0..20).map(|_| b.index(0..1000)).collect();
+        assert_eq!(picks_a, picks_b);
+        assert_eq!(a.jitter_ms(10_000), b.jitter_ms(10_000));
+    }
+
+    #[test]
+    fn test_empty_inputs() {
+        let rng = RngSource::new(Some(1));
+        assert_eq!(rng.index(5..5), 5);
+        assert_eq!(rng.jitter_ms(0), 0);
+    }
+}
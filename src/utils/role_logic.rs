@@ -1,21 +1,45 @@
 /// Pure functions for birthday role logic (Discord-agnostic)
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 /// Represents an action to take on a user's roles
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum RoleAction {
     Add,
     Remove,
-    NoAction,
 }
 
-/// Determine what role action should be taken for a user
-pub fn determine_role_action(has_birthday_today: bool, has_role: bool) -> RoleAction {
-    match (has_birthday_today, has_role) {
-        (true, false) => RoleAction::Add,
-        (false, true) => RoleAction::Remove,
-        _ => RoleAction::NoAction,
+/// Select the milestone role for an age, if that age is a configured milestone
+pub fn select_milestone_role<R: Copy>(age: Option<i32>, milestones: &[(i32, R)]) -> Option<R> {
+    let age = age?;
+    milestones
+        .iter()
+        .find(|(milestone_age, _)| *milestone_age == age)
+        .map(|(_, role)| *role)
+}
+
+/// Check whether a role update pass can be skipped entirely: nobody has a birthday today
+/// and a complete member list shows that nobody still holds one of the roles
+pub fn can_skip_role_update(has_birthdays_today: bool, members_complete: bool, any_member_has_role: bool) -> bool {
+    !has_birthdays_today && members_complete && !any_member_has_role
+}
+
+/// Group the users reaching a milestone age today by the milestone role they should get.
+/// At most one milestone role applies to a user: the one matching their new age.
+pub fn milestone_role_targets<U, R>(
+    ages: impl IntoIterator<Item = (U, Option<i32>)>,
+    milestones: &[(i32, R)],
+) -> HashMap<R, HashSet<U>>
+where
+    U: Eq + std::hash::Hash,
+    R: Copy + Eq + std::hash::Hash,
+{
+    let mut targets: HashMap<R, HashSet<U>> = HashMap::new();
+    for (user, age) in ages {
+        if let Some(role) = select_milestone_role(age, milestones) {
+            targets.entry(role).or_default().insert(user);
+        }
     }
+    targets
 }
 
 /// Calculate which users need role additions and removals
@@ -59,30 +83,57 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_determine_role_action() {
-        // Should add role
-        assert_eq!(
-            determine_role_action(true, false),
-            RoleAction::Add
-        );
-
-        // Should remove role
-        assert_eq!(
-            determine_role_action(false, true),
-            RoleAction::Remove
-        );
-
-        // No action needed (has birthday and has role)
-        assert_eq!(
-            determine_role_action(true, true),
-            RoleAction::NoAction
-        );
-
-        // No action needed (no birthday and no role)
-        assert_eq!(
-            determine_role_action(false, false),
-            RoleAction::NoAction
-        );
+    fn test_select_milestone_role() {
+        let milestones = vec![(18, 1u64), (21, 2u64), (30, 3u64)];
+
+        assert_eq!(select_milestone_role(Some(18), &milestones), Some(1));
+        assert_eq!(select_milestone_role(Some(30), &milestones), Some(3));
+        assert_eq!(select_milestone_role(Some(25), &milestones), None);
+    }
+
+    #[test]
+    fn test_select_milestone_role_requires_age() {
+        let milestones = vec![(18, 1u64)];
+
+        assert_eq!(select_milestone_role(None, &milestones), None);
+        assert_eq!(select_milestone_role(Some(18), &[] as &[(i32, u64)]), None);
+    }
+
+    #[test]
+    fn test_milestone_role_targets() {
+        let milestones = vec![(18, 1u64), (30, 3u64)];
+        let ages = vec![("a", Some(18)), ("b", Some(30)), ("c", Some(18)), ("d", Some(25)), ("e", None)];
+
+        let targets = milestone_role_targets(ages, &milestones);
+        assert_eq!(targets.len(), 2);
+        assert_eq!(targets[&1], HashSet::from(["a", "c"]));
+        assert_eq!(targets[&3], HashSet::from(["b"]));
+    }
+
+    #[test]
+    fn test_milestone_role_targets_skip_hidden_ages() {
+        use crate::utils::message_formatter::visible_age;
+
+        let milestones = vec![(30, 3u64)];
+        let ages = vec![
+            ("shown", visible_age(Some(1995), 2025, true)),
+            ("hidden", visible_age(Some(1995), 2025, false)),
+        ];
+
+        let targets = milestone_role_targets(ages, &milestones);
+        assert_eq!(targets[&3], HashSet::from(["shown"]));
+    }
+
+    #[test]
+    fn test_can_skip_role_update() {
+        assert!(can_skip_role_update(false, true, false));
+
+        // Birthdays today always need a pass
+        assert!(!can_skip_role_update(true, true, false));
+        // Lingering roles must be removed
+        assert!(!can_skip_role_update(false, true, true));
+        // An incomplete member list can't prove nobody has the role
+        assert!(!can_skip_role_update(false, false, false));
     }
 
     #[test]
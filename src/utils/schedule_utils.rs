@@ -1,4 +1,6 @@
 /// Pure cron and schedule calculation utilities (Discord-agnostic)
+#[cfg(feature = "birthday")]
+use chrono::Weekday;
 use std::cmp::Ordering;
 
 /// Parse a cron expression and validate basic structure
@@ -84,10 +86,45 @@ pub fn filter_enabled<T>(items: Vec<(T, bool)>) -> Vec<T> {
         .collect()
 }
 
+/// Check that a schedule label is non-empty, short enough and made of letters, digits,
+/// `-` or `_`, so it can be typed back to remove the schedule
+#[cfg(feature = "birthday")]
+pub fn is_valid_schedule_label(label: &str, max_length: usize) -> bool {
+    !label.is_empty()
+        && label.chars().count() <= max_length
+        && label.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Pick the channel to announce in: the weekend channel on Saturdays and Sundays when
+/// one is set, the regular channel otherwise
+#[cfg(feature = "birthday")]
+pub fn select_announce_channel<C>(weekday: Weekday, weekday_channel: C, weekend_channel: Option<C>) -> C {
+    match (weekday, weekend_channel) {
+        (Weekday::Sat | Weekday::Sun, Some(weekend_channel)) => weekend_channel,
+        _ => weekday_channel,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_select_announce_channel() {
+        assert_eq!(select_announce_channel(Weekday::Mon, 1, Some(2)), 1);
+        assert_eq!(select_announce_channel(Weekday::Fri, 1, Some(2)), 1);
+        assert_eq!(select_announce_channel(Weekday::Sat, 1, Some(2)), 2);
+        assert_eq!(select_announce_channel(Weekday::Sun, 1, Some(2)), 2);
+    }
+
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_select_announce_channel_without_weekend_channel() {
+        assert_eq!(select_announce_channel(Weekday::Sat, 1, None), 1);
+        assert_eq!(select_announce_channel(Weekday::Wed, 1, None), 1);
+    }
+
     #[test]
     fn test_is_valid_cron_format() {
         assert!(is_valid_cron_format("0 30 8 * * *"));
@@ -149,6 +186,17 @@ mod tests {
         assert_eq!(min_duration(50, 50), 50);
     }
 
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_is_valid_schedule_label() {
+        assert!(is_valid_schedule_label("default", 32));
+        assert!(is_valid_schedule_label("evening_2", 32));
+        assert!(is_valid_schedule_label("late-reminder", 32));
+        assert!(!is_valid_schedule_label("", 32));
+        assert!(!is_valid_schedule_label("with space", 32));
+        assert!(!is_valid_schedule_label("toolong", 3));
+    }
+
     #[test]
     fn test_is_schedule_enabled() {
         assert!(is_schedule_enabled(true));
@@ -1,11 +1,13 @@
 /// Pure string processing utilities (Discord-agnostic)
 
 /// Replace literal \n with actual newlines
+#[cfg(feature = "birthday")]
 pub fn process_newlines(text: &str) -> String {
     text.replace("\\n", "\n")
 }
 
 /// Trim and normalize whitespace in a string
+#[cfg(feature = "lobby")]
 pub fn normalize_whitespace(text: &str) -> String {
     text.trim()
         .split_whitespace()
@@ -19,6 +21,7 @@ pub fn take_chars(text: &str, n: usize) -> String {
 }
 
 /// Check if a string is empty after trimming
+#[cfg(feature = "birthday")]
 pub fn is_empty_or_whitespace(text: &str) -> bool {
     text.trim().is_empty()
 }
@@ -57,10 +60,24 @@ pub fn join_non_empty(parts: &[String], separator: &str) -> String {
 }
 
 /// Check if a string contains any of the given substrings
+#[cfg(feature = "lobby")]
 pub fn contains_any(text: &str, patterns: &[&str]) -> bool {
     patterns.iter().any(|pattern| text.contains(pattern))
 }
 
+/// Check if a string contains any of the blacklisted words, ignoring case
+#[cfg(feature = "lobby")]
+pub fn contains_blacklisted_word(text: &str, blacklist: &[String]) -> bool {
+    let text = text.to_lowercase();
+    let words: Vec<String> = blacklist
+        .iter()
+        .map(|word| word.trim().to_lowercase())
+        .filter(|word| !word.is_empty())
+        .collect();
+    let words: Vec<&str> = words.iter().map(String::as_str).collect();
+    contains_any(&text, &words)
+}
+
 /// Check if a string starts with any of the given prefixes
 pub fn starts_with_any(text: &str, prefixes: &[&str]) -> bool {
     prefixes.iter().any(|prefix| text.starts_with(prefix))
@@ -95,6 +112,7 @@ pub fn pad_right(text: &str, total_length: usize, pad_char: char) -> String {
 mod tests {
     use super::*;
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_process_newlines() {
         assert_eq!(process_newlines("Hello\\nWorld"), "Hello\nWorld");
@@ -102,6 +120,7 @@ mod tests {
         assert_eq!(process_newlines("No newlines"), "No newlines");
     }
 
+    #[cfg(feature = "lobby")]
     #[test]
     fn test_normalize_whitespace() {
         assert_eq!(normalize_whitespace("  hello   world  "), "hello world");
@@ -116,6 +135,7 @@ mod tests {
         assert_eq!(take_chars("Test", 0), "");
     }
 
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_is_empty_or_whitespace() {
         assert!(is_empty_or_whitespace(""));
@@ -156,6 +176,7 @@ mod tests {
         assert_eq!(join_non_empty(&all_empty, " "), "");
     }
 
+    #[cfg(feature = "lobby")]
     #[test]
     fn test_contains_any() {
         assert!(contains_any("hello world", &["world", "test"]));
@@ -164,6 +185,16 @@ mod tests {
         assert!(!contains_any("hello world", &["foo", "bar"]));
     }
 
+    #[cfg(feature = "lobby")]
+    #[test]
+    fn test_contains_blacklisted_word() {
+        let blacklist = vec!["spam".to_string(), " Scam ".to_string(), "".to_string()];
+        assert!(contains_blacklisted_word("free-spam-here", &blacklist));
+        assert!(contains_blacklisted_word("SCAMMERS", &blacklist));
+        assert!(!contains_blacklisted_word("gaming", &blacklist));
+        assert!(!contains_blacklisted_word("anything", &[]));
+    }
+
     #[test]
     fn test_starts_with_any() {
         assert!(starts_with_any("hello world", &["hello", "test"]));
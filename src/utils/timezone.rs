@@ -1,11 +1,15 @@
-use chrono::{LocalResult, NaiveTime, TimeZone, Timelike};
+use chrono::{DateTime, Utc};
+#[cfg(feature = "birthday")]
+use chrono::{LocalResult, NaiveDate, NaiveTime, TimeZone, Timelike};
 use chrono_tz::Tz;
 
 /// Error types for timezone operations
 #[derive(Debug)]
 pub enum TimezoneError {
     InvalidTimezone(String),
+    #[cfg(feature = "birthday")]
     InvalidTime(String),
+    #[cfg(feature = "birthday")]
     TimeDoesNotExist,
 }
 
@@ -13,7 +17,9 @@ impl std::fmt::Display for TimezoneError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             TimezoneError::InvalidTimezone(tz) => write!(f, "Invalid timezone: {}", tz),
+            #[cfg(feature = "birthday")]
             TimezoneError::InvalidTime(msg) => write!(f, "Invalid time format: {}", msg),
+            #[cfg(feature = "birthday")]
             TimezoneError::TimeDoesNotExist => write!(f, "Time doesn't exist in this timezone (DST transition)"),
         }
     }
@@ -23,6 +29,7 @@ impl std::error::Error for TimezoneError {}
 
 /// Convert a naive time in a specific timezone to UTC time
 /// Returns (UTC hour, UTC minute) as used in cron expressions
+#[cfg(feature = "birthday")]
 pub fn convert_local_time_to_utc(
     time: NaiveTime,
     timezone: &Tz,
@@ -46,13 +53,142 @@ pub fn parse_timezone(tz_str: &str) -> Result<Tz, TimezoneError> {
     tz_str.parse().map_err(|_| TimezoneError::InvalidTimezone(tz_str.to_string()))
 }
 
+/// Timezone names containing the partial input (case-insensitive), names starting with it first,
+/// capped to `limit` suggestions
+pub fn suggest_timezones(partial: &str, limit: usize) -> Vec<&'static str> {
+    let partial = partial.trim().to_lowercase();
+    let (mut prefixed, others): (Vec<&'static str>, Vec<&'static str>) = chrono_tz::TZ_VARIANTS
+        .iter()
+        .map(|tz| tz.name())
+        .filter(|name| name.to_lowercase().contains(&partial))
+        .partition(|name| name.to_lowercase().starts_with(&partial));
+
+    prefixed.extend(others);
+    prefixed.truncate(limit);
+    prefixed
+}
+
+/// Format an instant in a timezone as "HH:MM" followed by the date, e.g. "14:05, Friday 10 May 2024"
+pub fn format_local_time(now: DateTime<Utc>, tz: &Tz) -> String {
+    now.with_timezone(tz).format("%H:%M, %A %-d %B %Y").to_string()
+}
+
+/// Parse a user-provided timezone, either a name (e.g. Europe/Paris) or a UTC offset (e.g. +02:00),
+/// into the timezone name to store
+#[cfg(feature = "birthday")]
+pub fn resolve_timezone_input(input: &str) -> Option<String> {
+    let input = input.trim();
+    if parse_timezone(input).is_ok() {
+        return Some(input.to_string());
+    }
+    offset_to_timezone(input)
+}
+
+/// Get the current date of a user, in their own timezone or in the guild's when they have
+/// none (or an invalid one)
+#[cfg(feature = "birthday")]
+pub fn local_date(now: DateTime<Utc>, user_timezone: Option<&str>, guild_timezone: &Tz) -> NaiveDate {
+    match user_timezone.and_then(|tz| parse_timezone(tz).ok()) {
+        Some(tz) => now.with_timezone(&tz).date_naive(),
+        None => now.with_timezone(guild_timezone).date_naive(),
+    }
+}
+
 /// Parse a time string in HH:MM format
+#[cfg(feature = "birthday")]
 pub fn parse_time_string(time_str: &str) -> Result<NaiveTime, TimezoneError> {
     NaiveTime::parse_from_str(time_str, "%H:%M")
         .map_err(|_| TimezoneError::InvalidTime(format!("Expected HH:MM format, got '{}'", time_str)))
 }
 
+/// Zones for UTC offsets that are not a whole number of hours
+/// (the `Etc/GMT` zones only cover whole hours). Only zones without daylight saving time are
+/// listed, so the offset stays the same all year; the others (e.g. -03:30, +10:30, +12:45)
+/// need their IANA name.
+const FRACTIONAL_OFFSET_ZONES: &[(i32, &str)] = &[
+    (-9 * 60 - 30, "Pacific/Marquesas"),
+    (3 * 60 + 30, "Asia/Tehran"),
+    (4 * 60 + 30, "Asia/Kabul"),
+    (5 * 60 + 30, "Asia/Kolkata"),
+    (5 * 60 + 45, "Asia/Kathmandu"),
+    (6 * 60 + 30, "Asia/Yangon"),
+    (8 * 60 + 45, "Australia/Eucla"),
+    (9 * 60 + 30, "Australia/Darwin"),
+];
+
+/// Parse a UTC offset string into a number of minutes east of UTC
+/// Accepts forms like "+02:00", "-0500", "+2", "UTC+5:30" or "GMT-3"
+pub fn parse_utc_offset(offset_str: &str) -> Option<i32> {
+    let trimmed = offset_str.trim();
+    let upper = trimmed.to_ascii_uppercase();
+    let without_prefix = upper
+        .strip_prefix("UTC")
+        .or_else(|| upper.strip_prefix("GMT"))
+        .unwrap_or(&upper);
+
+    if without_prefix.is_empty() {
+        return Some(0);
+    }
+
+    let (sign, digits) = match without_prefix.chars().next()? {
+        '+' => (1, &without_prefix[1..]),
+        '-' => (-1, &without_prefix[1..]),
+        _ => return None,
+    };
+
+    let (hours_str, minutes_str) = match digits.split_once(':') {
+        Some((h, m)) => (h, m),
+        None if digits.len() == 4 => digits.split_at(2),
+        None if digits.len() <= 2 => (digits, "0"),
+        None => return None,
+    };
+
+    if hours_str.is_empty() || !hours_str.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    if minutes_str.is_empty() || !minutes_str.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let hours: i32 = hours_str.parse().ok()?;
+    let minutes: i32 = minutes_str.parse().ok()?;
+    if minutes >= 60 {
+        return None;
+    }
+
+    let total = sign * (hours * 60 + minutes);
+    (-12 * 60..=14 * 60).contains(&total).then_some(total)
+}
+
+/// Map a UTC offset string to a representative IANA timezone name
+/// Whole-hour offsets map to the fixed `Etc/GMT` zones (note their inverted sign),
+/// other offsets map to a city zone keeping that offset all year (None if there is none)
+pub fn offset_to_timezone(offset_str: &str) -> Option<String> {
+    let total_minutes = parse_utc_offset(offset_str)?;
+
+    if total_minutes == 0 {
+        return Some("UTC".to_string());
+    }
+
+    if total_minutes % 60 == 0 {
+        let hours = total_minutes / 60;
+        // Etc/GMT zones use POSIX sign convention: Etc/GMT-2 is UTC+2
+        let name = if hours > 0 {
+            format!("Etc/GMT-{}", hours)
+        } else {
+            format!("Etc/GMT+{}", -hours)
+        };
+        return Some(name);
+    }
+
+    FRACTIONAL_OFFSET_ZONES
+        .iter()
+        .find(|(minutes, _)| *minutes == total_minutes)
+        .map(|(_, name)| name.to_string())
+}
+
 /// Create a cron expression from UTC time
+#[cfg(feature = "birthday")]
 pub fn create_cron_expression(utc_time: NaiveTime) -> String {
     format!(
         "0 {} {} * * *",
@@ -62,6 +198,7 @@ pub fn create_cron_expression(utc_time: NaiveTime) -> String {
 }
 
 /// Convert local time string to UTC cron expression
+#[cfg(feature = "birthday")]
 pub fn local_time_to_cron(
     time_str: &str,
     timezone_str: &str,
@@ -77,7 +214,61 @@ pub fn local_time_to_cron(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
     
+    #[test]
+    fn test_format_local_time() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 10, 22, 5, 0).unwrap();
+        assert_eq!(format_local_time(now, &chrono_tz::UTC), "22:05, Friday 10 May 2024");
+        assert_eq!(
+            format_local_time(now, &chrono_tz::Europe::Paris),
+            "00:05, Saturday 11 May 2024"
+        );
+    }
+
+    #[test]
+    fn test_suggest_timezones() {
+        let suggestions = suggest_timezones("paris", 25);
+        assert_eq!(suggestions, vec!["Europe/Paris"]);
+
+        // Case-insensitive substring match, names starting with the input first
+        let suggestions = suggest_timezones("AMERICA/new", 25);
+        assert_eq!(suggestions.first(), Some(&"America/New_York"));
+        let suggestions = suggest_timezones("utc", 25);
+        assert_eq!(suggestions.first(), Some(&"UTC"));
+        assert!(suggestions.contains(&"Etc/UTC"));
+    }
+
+    #[test]
+    fn test_suggest_timezones_limit() {
+        assert_eq!(suggest_timezones("", 25).len(), 25);
+        assert_eq!(suggest_timezones("europe", 3).len(), 3);
+        assert!(suggest_timezones("Mars/Olympus", 25).is_empty());
+    }
+
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_resolve_timezone_input() {
+        assert_eq!(resolve_timezone_input(" Europe/Paris "), Some("Europe/Paris".to_string()));
+        assert!(resolve_timezone_input("+02:00").is_some());
+        assert_eq!(resolve_timezone_input("Mars/Olympus"), None);
+    }
+
+    #[cfg(feature = "birthday")]
+    #[test]
+    fn test_local_date() {
+        // 23:30 UTC on 14 March
+        let now = Utc.with_ymd_and_hms(2024, 3, 14, 23, 30, 0).unwrap();
+        let march = |day| NaiveDate::from_ymd_opt(2024, 3, day).unwrap();
+
+        assert_eq!(local_date(now, Some("Europe/Paris"), &chrono_tz::UTC), march(15));
+        assert_eq!(local_date(now, None, &chrono_tz::UTC), march(14));
+        assert_eq!(local_date(now, None, &chrono_tz::Asia::Tokyo), march(15));
+        // An invalid user timezone falls back to the guild's
+        assert_eq!(local_date(now, Some("Nowhere"), &chrono_tz::America::New_York), march(14));
+    }
+
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_parse_time_string() {
         assert!(parse_time_string("08:00").is_ok());
@@ -92,10 +283,58 @@ mod tests {
         assert!(parse_timezone("Invalid/Timezone").is_err());
     }
     
+    #[cfg(feature = "birthday")]
     #[test]
     fn test_create_cron_expression() {
         let time = NaiveTime::from_hms_opt(8, 30, 0).unwrap();
         let cron = create_cron_expression(time);
         assert_eq!(cron, "0 30 8 * * *");
     }
+
+    #[test]
+    fn test_parse_utc_offset() {
+        assert_eq!(parse_utc_offset("+02:00"), Some(120));
+        assert_eq!(parse_utc_offset("-0500"), Some(-300));
+        assert_eq!(parse_utc_offset("+2"), Some(120));
+        assert_eq!(parse_utc_offset("UTC+5:30"), Some(330));
+        assert_eq!(parse_utc_offset("gmt-3"), Some(-180));
+        assert_eq!(parse_utc_offset("UTC"), Some(0));
+
+        assert_eq!(parse_utc_offset("02:00"), None); // Missing sign
+        assert_eq!(parse_utc_offset("+25:00"), None); // Out of range
+        assert_eq!(parse_utc_offset("+02:75"), None); // Invalid minutes
+        assert_eq!(parse_utc_offset("+abc"), None);
+        assert_eq!(parse_utc_offset("+123"), None);
+    }
+
+    #[test]
+    fn test_offset_to_timezone_whole_hours() {
+        assert_eq!(offset_to_timezone("+02:00"), Some("Etc/GMT-2".to_string()));
+        assert_eq!(offset_to_timezone("-0500"), Some("Etc/GMT+5".to_string()));
+        assert_eq!(offset_to_timezone("+14"), Some("Etc/GMT-14".to_string()));
+        assert_eq!(offset_to_timezone("+00:00"), Some("UTC".to_string()));
+    }
+
+    #[test]
+    fn test_offset_to_timezone_fractional() {
+        assert_eq!(offset_to_timezone("+05:30"), Some("Asia/Kolkata".to_string()));
+        assert_eq!(offset_to_timezone("+01:15"), None);
+    }
+
+    #[test]
+    fn test_offset_to_timezone_skips_dst_zones() {
+        // St. John's, Lord Howe and Chatham change offset with daylight saving time
+        for offset in ["-03:30", "+10:30", "+12:45"] {
+            assert!(parse_utc_offset(offset).is_some());
+            assert_eq!(offset_to_timezone(offset), None);
+        }
+    }
+
+    #[test]
+    fn test_offset_to_timezone_is_parseable() {
+        for offset in ["-12:00", "-09:30", "+03:30", "+05:45", "+08:45", "+14:00"] {
+            let tz = offset_to_timezone(offset).unwrap();
+            assert!(parse_timezone(&tz).is_ok(), "{} should be a valid zone", tz);
+        }
+    }
 }
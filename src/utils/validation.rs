@@ -1,5 +1,8 @@
 use poise::serenity_prelude::{ChannelType, GuildChannel, GuildId};
 
+#[cfg(feature = "birthday")]
+use crate::models::{Context, Error};
+
 /// Validation error types
 #[derive(Debug)]
 pub enum ValidationError {
@@ -43,6 +46,26 @@ pub fn require_guild(guild_id: Option<GuildId>) -> Result<GuildId, ValidationErr
     guild_id.ok_or(ValidationError::NotInGuild)
 }
 
+/// Guild ID of a command invocation along with the guild settings commands commonly need
+#[cfg(feature = "birthday")]
+#[derive(Debug, Clone)]
+pub struct GuildContext {
+    pub guild_id: GuildId,
+    pub timezone: String,
+}
+
+/// Like `require_guild`, but also fetches the (cached) guild settings
+#[cfg(feature = "birthday")]
+pub async fn guild_context(ctx: Context<'_>) -> Result<GuildContext, Error> {
+    let guild_id = require_guild(ctx.guild_id())?;
+    let settings = ctx.data().get_guild_settings(guild_id).await?;
+
+    Ok(GuildContext {
+        guild_id,
+        timezone: settings.timezone,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;